@@ -0,0 +1,54 @@
+use crate::VariableCurve;
+use bevy_core::Name;
+use bevy_reflect::TypeUuid;
+use bevy_utils::HashMap;
+
+/// The path to an entity from the root of an animated hierarchy, as the [`Name`]s of the
+/// entities from a child of the root down to the entity
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct EntityPath {
+    pub parts: Vec<Name>,
+}
+
+impl EntityPath {
+    /// Returns the path to the child named `name` of the entity at this path
+    pub fn child(&self, name: Name) -> EntityPath {
+        let mut parts = self.parts.clone();
+        parts.push(name);
+        EntityPath { parts }
+    }
+}
+
+/// Curves animating the entities of a hierarchy, each entity being targeted by its
+/// [`EntityPath`]
+#[derive(Debug, Clone, Default, TypeUuid)]
+#[uuid = "d81b7179-0448-4eb0-89fe-c067222725bf"]
+pub struct AnimationClip {
+    curves: HashMap<EntityPath, Vec<VariableCurve>>,
+    duration: f32,
+}
+
+impl AnimationClip {
+    /// The curves of the clip, by the path of the entity they animate
+    pub fn curves(&self) -> &HashMap<EntityPath, Vec<VariableCurve>> {
+        &self.curves
+    }
+
+    /// The curves animating the entity at `path`
+    pub fn curves_for_path(&self, path: &EntityPath) -> &[VariableCurve] {
+        self.curves
+            .get(path)
+            .map_or(&[], |curves| curves.as_slice())
+    }
+
+    /// The time of the last keyframe of the clip, in seconds
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Adds a curve animating the entity at `path`
+    pub fn add_curve_to_path(&mut self, path: EntityPath, curve: VariableCurve) {
+        self.duration = self.duration.max(curve.duration());
+        self.curves.entry(path).or_default().push(curve);
+    }
+}
@@ -0,0 +1,409 @@
+use crate::{AnimationClip, EntityPath, KeyframeValue};
+use bevy_asset::{Assets, Handle};
+use bevy_core::{Name, Time};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    reflect::ReflectComponent,
+    world::{Mut, World},
+};
+use bevy_reflect::{GetPath, Reflect, TypeRegistry, TypeRegistryArc};
+use bevy_transform::components::{Children, Transform};
+use std::time::Duration;
+
+/// Plays an [`AnimationClip`] on the hierarchy of its entity.
+///
+/// The curves of the clip target the descendants of the entity by their [`EntityPath`], and
+/// animate their [`Transform`] or a field of any reflected component.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct AnimationPlayer {
+    paused: bool,
+    repeat: bool,
+    speed: f32,
+    elapsed: f32,
+    animation_clip: Handle<AnimationClip>,
+    #[reflect(ignore)]
+    transition: Option<Transition>,
+}
+
+/// A clip fading out while the current clip of an [`AnimationPlayer`] fades in
+#[derive(Debug, Clone)]
+struct Transition {
+    animation_clip: Handle<AnimationClip>,
+    repeat: bool,
+    speed: f32,
+    elapsed: f32,
+    /// The time since the start of the transition, in seconds
+    progress: f32,
+    duration: f32,
+}
+
+impl Transition {
+    /// The weight of the clip fading in, from 0 at the start of the transition to 1 at its end
+    fn weight(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        (self.progress / self.duration).min(1.0)
+    }
+}
+
+impl Default for AnimationPlayer {
+    fn default() -> Self {
+        AnimationPlayer {
+            paused: false,
+            repeat: false,
+            speed: 1.0,
+            elapsed: 0.0,
+            animation_clip: Default::default(),
+            transition: None,
+        }
+    }
+}
+
+impl AnimationPlayer {
+    /// Starts playing `handle` from the beginning, stopping the current clip
+    pub fn start(&mut self, handle: Handle<AnimationClip>) -> &mut Self {
+        *self = AnimationPlayer {
+            animation_clip: handle,
+            ..Default::default()
+        };
+        self
+    }
+
+    /// Starts playing `handle` from the beginning, unless it is already the current clip
+    pub fn play(&mut self, handle: Handle<AnimationClip>) -> &mut Self {
+        if self.animation_clip != handle {
+            self.start(handle);
+        }
+        self
+    }
+
+    /// Starts playing `handle` from the beginning, cross-fading from the current clip over
+    /// `duration`. The current clip keeps playing until it has faded out. If a cross-fade is
+    /// already in progress, the clip fading out is stopped.
+    ///
+    /// With a zero `duration`, `handle` is started without cross-fading.
+    pub fn cross_fade(&mut self, handle: Handle<AnimationClip>, duration: Duration) -> &mut Self {
+        if duration.is_zero() {
+            return self.start(handle);
+        }
+        let transition = Transition {
+            animation_clip: std::mem::take(&mut self.animation_clip),
+            repeat: self.repeat,
+            speed: self.speed,
+            elapsed: self.elapsed,
+            progress: 0.0,
+            duration: duration.as_secs_f32(),
+        };
+        self.start(handle);
+        self.transition = Some(transition);
+        self
+    }
+
+    /// The clip being played
+    pub fn animation_clip(&self) -> &Handle<AnimationClip> {
+        &self.animation_clip
+    }
+
+    /// Loops the current clip
+    pub fn repeat(&mut self) -> &mut Self {
+        self.repeat = true;
+        self
+    }
+
+    /// Stops looping the current clip, which stops at its last keyframe
+    pub fn stop_repeating(&mut self) -> &mut Self {
+        self.repeat = false;
+        self
+    }
+
+    pub fn is_repeating(&self) -> bool {
+        self.repeat
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// The speed of the playback, 1 being the normal speed. A negative speed plays the clip
+    /// backward.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) -> &mut Self {
+        self.speed = speed;
+        self
+    }
+
+    /// The time since the start of the current clip, in seconds
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Seeks the current clip to `elapsed` seconds
+    pub fn set_elapsed(&mut self, elapsed: f32) -> &mut Self {
+        self.elapsed = elapsed;
+        self
+    }
+
+    /// Advances the playback by `delta_seconds`, ending the cross-fade once it's complete
+    fn tick(&mut self, delta_seconds: f32) {
+        if self.paused {
+            return;
+        }
+        self.elapsed += delta_seconds * self.speed;
+        if let Some(transition) = &mut self.transition {
+            transition.elapsed += delta_seconds * transition.speed;
+            transition.progress += delta_seconds;
+            if transition.progress >= transition.duration {
+                self.transition = None;
+            }
+        }
+    }
+}
+
+/// The time in a clip of `duration` seconds, `elapsed` seconds after its start
+fn seek_time(elapsed: f32, duration: f32, repeat: bool) -> f32 {
+    if repeat && duration > 0.0 {
+        elapsed.rem_euclid(duration)
+    } else {
+        elapsed.clamp(0.0, duration)
+    }
+}
+
+/// Advances the [`AnimationPlayer`]s and animates the hierarchies of their entities
+pub fn animation_player_system(world: &mut World) {
+    let delta_seconds = world
+        .get_resource::<Time>()
+        .map_or(0.0, |time| time.delta_seconds());
+    let type_registry = world.get_resource::<TypeRegistryArc>().cloned();
+    let mut players = Vec::new();
+    for (entity, mut player) in world
+        .query::<(Entity, &mut AnimationPlayer)>()
+        .iter_mut(world)
+    {
+        player.tick(delta_seconds);
+        players.push((entity, player.clone()));
+    }
+    if players.is_empty() || world.get_resource::<Assets<AnimationClip>>().is_none() {
+        return;
+    }
+
+    world.resource_scope(|world, animation_clips: Mut<Assets<AnimationClip>>| {
+        let type_registry = type_registry
+            .as_ref()
+            .map(|type_registry| type_registry.read());
+        for (root, player) in players {
+            // the clip fading out is applied first, for the current clip to be blended over it
+            let weight = match &player.transition {
+                Some(transition) => {
+                    if let Some(clip) = animation_clips.get(&transition.animation_clip) {
+                        let time =
+                            seek_time(transition.elapsed, clip.duration(), transition.repeat);
+                        apply_clip(world, type_registry.as_deref(), root, clip, time, 1.0);
+                    }
+                    transition.weight()
+                }
+                None => 1.0,
+            };
+            if let Some(clip) = animation_clips.get(&player.animation_clip) {
+                let time = seek_time(player.elapsed, clip.duration(), player.repeat);
+                apply_clip(world, type_registry.as_deref(), root, clip, time, weight);
+            }
+        }
+    });
+}
+
+/// Blends the values of `clip` at `time` into the hierarchy of `root`, by `weight`
+fn apply_clip(
+    world: &mut World,
+    type_registry: Option<&TypeRegistry>,
+    root: Entity,
+    clip: &AnimationClip,
+    time: f32,
+    weight: f32,
+) {
+    for (path, curves) in clip.curves() {
+        let entity = match find_entity(world, root, path) {
+            Some(entity) => entity,
+            None => continue,
+        };
+        for curve in curves {
+            match (curve.sample(time), curve.field_target()) {
+                (Some(KeyframeValue::Field(value)), Some(target)) => {
+                    let reflect_component = type_registry
+                        .and_then(|type_registry| type_registry.get_with_name(&target.component))
+                        .and_then(|registration| registration.data::<ReflectComponent>());
+                    let mut component = match reflect_component.and_then(|reflect_component| {
+                        reflect_component.reflect_component_mut(world, entity)
+                    }) {
+                        Some(component) => component,
+                        None => continue,
+                    };
+                    if let Ok(field) = component.path_mut(&target.path) {
+                        value.blend_into(field, weight);
+                    }
+                }
+                (Some(value), _) => {
+                    if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+                        value.blend_into_transform(&mut transform, weight);
+                    }
+                }
+                (None, _) => {}
+            }
+        }
+    }
+}
+
+/// Finds the descendant of `root` at `path`
+fn find_entity(world: &World, root: Entity, path: &EntityPath) -> Option<Entity> {
+    let mut entity = root;
+    for part in path.parts.iter() {
+        entity = *world
+            .get::<Children>(entity)?
+            .iter()
+            .find(|child| world.get::<Name>(**child) == Some(part))?;
+    }
+    Some(entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AnimationPlugin, FieldKeyframes, FieldTarget, Interpolation, Keyframes, VariableCurve,
+    };
+    use bevy_app::App;
+    use bevy_asset::AssetPlugin;
+    use bevy_core::CorePlugin;
+    use bevy_math::Vec3;
+    use bevy_transform::hierarchy::BuildWorldChildren;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Intensity {
+        value: f32,
+    }
+
+    fn linear_curve(keyframes: Keyframes) -> VariableCurve {
+        VariableCurve {
+            keyframe_timestamps: vec![0.0, 2.0],
+            keyframes,
+            interpolation: Interpolation::Linear,
+        }
+    }
+
+    #[test]
+    fn play_and_cross_fade() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_plugin(AnimationPlugin)
+            .register_type::<Intensity>();
+
+        let arm = EntityPath::default().child(Name::new("arm"));
+        let mut walk = AnimationClip::default();
+        walk.add_curve_to_path(
+            arm.clone(),
+            linear_curve(Keyframes::Translation(vec![Vec3::ZERO, Vec3::X * 2.0])),
+        );
+        walk.add_curve_to_path(
+            arm.clone(),
+            linear_curve(Keyframes::Field(
+                FieldTarget::new(std::any::type_name::<Intensity>(), "value"),
+                FieldKeyframes::F32(vec![0.0, 4.0]),
+            )),
+        );
+        let mut run = AnimationClip::default();
+        run.add_curve_to_path(
+            arm,
+            linear_curve(Keyframes::Translation(vec![Vec3::ZERO, Vec3::Y * 2.0])),
+        );
+        let mut animation_clips = app
+            .world
+            .get_resource_mut::<Assets<AnimationClip>>()
+            .unwrap();
+        let walk = animation_clips.add(walk);
+        let run = animation_clips.add(run);
+
+        let mut player = AnimationPlayer::default();
+        player.play(walk.clone()).set_elapsed(1.0).pause();
+        let mut arm = None;
+        let root = app
+            .world
+            .spawn()
+            .insert(player)
+            .with_children(|parent| {
+                arm = Some(
+                    parent
+                        .spawn()
+                        .insert_bundle((
+                            Name::new("arm"),
+                            Transform::default(),
+                            Intensity::default(),
+                        ))
+                        .id(),
+                );
+            })
+            .id();
+        let arm = arm.unwrap();
+
+        app.update();
+        assert_eq!(
+            app.world.get::<Transform>(arm).unwrap().translation,
+            Vec3::X
+        );
+        assert_eq!(app.world.get::<Intensity>(arm).unwrap().value, 2.0);
+
+        // a looping clip wraps around its duration
+        app.world
+            .get_mut::<AnimationPlayer>(root)
+            .unwrap()
+            .repeat()
+            .set_elapsed(3.5);
+        app.update();
+        assert_eq!(
+            app.world.get::<Transform>(arm).unwrap().translation,
+            Vec3::new(1.5, 0.0, 0.0)
+        );
+
+        // halfway through the cross-fade, both clips have the same weight
+        let mut player = app.world.get_mut::<AnimationPlayer>(root).unwrap();
+        player.set_elapsed(1.0);
+        player
+            .cross_fade(run, Duration::from_secs(1))
+            .set_elapsed(1.0);
+        let transition = player.transition.as_mut().unwrap();
+        transition.progress = 0.5;
+        player.pause();
+        app.update();
+        assert!(app
+            .world
+            .get::<Transform>(arm)
+            .unwrap()
+            .translation
+            .abs_diff_eq(Vec3::new(0.5, 0.5, 0.0), 1e-6));
+
+        // a cross-fade without duration switches to the clip at once, even when paused
+        let mut player = app.world.get_mut::<AnimationPlayer>(root).unwrap();
+        player.cross_fade(walk, Duration::ZERO).set_elapsed(1.0);
+        player.pause();
+        assert!(player.transition.is_none());
+        app.update();
+        assert_eq!(
+            app.world.get::<Transform>(arm).unwrap().translation,
+            Vec3::X
+        );
+    }
+}
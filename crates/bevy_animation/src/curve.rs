@@ -0,0 +1,409 @@
+use bevy_math::{Quat, Vec2, Vec3, Vec4};
+use bevy_reflect::Reflect;
+use bevy_transform::components::Transform;
+
+/// How the values of a curve are interpolated between keyframes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// The value of a keyframe is kept until the next keyframe
+    Step,
+    /// Values are linearly interpolated, and rotations spherically interpolated
+    Linear,
+    /// Values are interpolated with a cubic Hermite spline. Each keyframe stores three values:
+    /// its in-tangent, its value and its out-tangent, in that order.
+    CubicSpline,
+}
+
+/// The values of the keyframes of a curve
+#[derive(Debug, Clone)]
+pub enum Keyframes {
+    Translation(Vec<Vec3>),
+    Rotation(Vec<Quat>),
+    Scale(Vec<Vec3>),
+    /// Values of a field of a reflected component
+    Field(FieldTarget, FieldKeyframes),
+}
+
+/// A field of a reflected component animated by a curve
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldTarget {
+    /// The type name of the component, which must be registered with `ReflectComponent`
+    pub component: String,
+    /// The path of the field in the component, as accepted by [`GetPath`](bevy_reflect::GetPath)
+    pub path: String,
+}
+
+impl FieldTarget {
+    pub fn new(component: impl Into<String>, path: impl Into<String>) -> Self {
+        FieldTarget {
+            component: component.into(),
+            path: path.into(),
+        }
+    }
+}
+
+/// The values of the keyframes of a curve animating a reflected field
+#[derive(Debug, Clone)]
+pub enum FieldKeyframes {
+    F32(Vec<f32>),
+    Vec2(Vec<Vec2>),
+    Vec3(Vec<Vec3>),
+    Vec4(Vec<Vec4>),
+    Quat(Vec<Quat>),
+}
+
+/// A value of a [`VariableCurve`] at a given time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyframeValue {
+    Translation(Vec3),
+    Rotation(Quat),
+    Scale(Vec3),
+    Field(FieldValue),
+}
+
+/// A value of a reflected field sampled from a curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldValue {
+    F32(f32),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Quat(Quat),
+}
+
+impl KeyframeValue {
+    /// Blends the value into `transform`, interpolating from the current value of the transform
+    /// by `weight`. A weight of 1 replaces the current value. Field values are ignored.
+    pub fn blend_into_transform(&self, transform: &mut Transform, weight: f32) {
+        match *self {
+            KeyframeValue::Translation(translation) => {
+                transform.translation = transform.translation.lerp(translation, weight)
+            }
+            KeyframeValue::Rotation(rotation) => {
+                transform.rotation = Interpolate::lerp(transform.rotation, rotation, weight)
+            }
+            KeyframeValue::Scale(scale) => transform.scale = transform.scale.lerp(scale, weight),
+            KeyframeValue::Field(_) => {}
+        }
+    }
+}
+
+impl FieldValue {
+    /// Blends the value into `field`, interpolating from its current value by `weight`. Returns
+    /// `false` if the field doesn't have the type of the value.
+    pub fn blend_into(&self, field: &mut dyn Reflect, weight: f32) -> bool {
+        fn blend<T: Interpolate + Reflect>(field: &mut dyn Reflect, value: T, weight: f32) -> bool {
+            match field.downcast_mut::<T>() {
+                Some(field) => {
+                    *field = field.lerp(value, weight);
+                    true
+                }
+                None => false,
+            }
+        }
+
+        match *self {
+            FieldValue::F32(value) => blend(field, value, weight),
+            FieldValue::Vec2(value) => blend(field, value, weight),
+            FieldValue::Vec3(value) => blend(field, value, weight),
+            FieldValue::Vec4(value) => blend(field, value, weight),
+            FieldValue::Quat(value) => blend(field, value, weight),
+        }
+    }
+}
+
+/// Keyframes of a value at variable timestamps
+#[derive(Debug, Clone)]
+pub struct VariableCurve {
+    /// The timestamps of the keyframes in seconds, in increasing order
+    pub keyframe_timestamps: Vec<f32>,
+    pub keyframes: Keyframes,
+    pub interpolation: Interpolation,
+}
+
+impl VariableCurve {
+    /// The timestamp of the last keyframe
+    pub fn duration(&self) -> f32 {
+        self.keyframe_timestamps.last().copied().unwrap_or(0.0)
+    }
+
+    /// Samples the curve at `time`. Times before the first keyframe or after the last one are
+    /// clamped. Returns `None` if the curve has no keyframe, or if the number of values doesn't
+    /// match the number of timestamps.
+    pub fn sample(&self, time: f32) -> Option<KeyframeValue> {
+        let timestamps = &self.keyframe_timestamps;
+        let interpolation = self.interpolation;
+        Some(match &self.keyframes {
+            Keyframes::Translation(values) => {
+                KeyframeValue::Translation(sample(timestamps, values, interpolation, time)?)
+            }
+            Keyframes::Rotation(values) => {
+                KeyframeValue::Rotation(sample(timestamps, values, interpolation, time)?)
+            }
+            Keyframes::Scale(values) => {
+                KeyframeValue::Scale(sample(timestamps, values, interpolation, time)?)
+            }
+            Keyframes::Field(_, values) => KeyframeValue::Field(match values {
+                FieldKeyframes::F32(values) => {
+                    FieldValue::F32(sample(timestamps, values, interpolation, time)?)
+                }
+                FieldKeyframes::Vec2(values) => {
+                    FieldValue::Vec2(sample(timestamps, values, interpolation, time)?)
+                }
+                FieldKeyframes::Vec3(values) => {
+                    FieldValue::Vec3(sample(timestamps, values, interpolation, time)?)
+                }
+                FieldKeyframes::Vec4(values) => {
+                    FieldValue::Vec4(sample(timestamps, values, interpolation, time)?)
+                }
+                FieldKeyframes::Quat(values) => {
+                    FieldValue::Quat(sample(timestamps, values, interpolation, time)?)
+                }
+            }),
+        })
+    }
+
+    /// The reflected field animated by the curve, if it doesn't animate a [`Transform`]
+    pub fn field_target(&self) -> Option<&FieldTarget> {
+        match &self.keyframes {
+            Keyframes::Field(target, _) => Some(target),
+            _ => None,
+        }
+    }
+
+    /// Samples the curve at `time` and sets the sampled value on `transform`
+    pub fn apply(&self, time: f32, transform: &mut Transform) {
+        if let Some(value) = self.sample(time) {
+            value.blend_into_transform(transform, 1.0);
+        }
+    }
+}
+
+/// A value that can be interpolated between keyframes
+trait Interpolate: Copy {
+    fn lerp(self, other: Self, s: f32) -> Self;
+
+    /// Interpolates with a cubic Hermite spline between `self` and `other`, `dt` seconds apart
+    fn cubic_spline(
+        self,
+        out_tangent: Self,
+        in_tangent: Self,
+        other: Self,
+        dt: f32,
+        s: f32,
+    ) -> Self;
+}
+
+fn hermite<T>(v0: T, out_tangent: T, in_tangent: T, v1: T, dt: f32, s: f32) -> T
+where
+    T: std::ops::Add<Output = T> + std::ops::Mul<f32, Output = T>,
+{
+    let s2 = s * s;
+    let s3 = s2 * s;
+    v0 * (2.0 * s3 - 3.0 * s2 + 1.0)
+        + out_tangent * ((s3 - 2.0 * s2 + s) * dt)
+        + v1 * (-2.0 * s3 + 3.0 * s2)
+        + in_tangent * ((s3 - s2) * dt)
+}
+
+macro_rules! impl_interpolate_vector {
+    ($($ty:ty),*) => {
+        $(
+            impl Interpolate for $ty {
+                fn lerp(self, other: Self, s: f32) -> Self {
+                    self + (other - self) * s
+                }
+
+                fn cubic_spline(
+                    self,
+                    out_tangent: Self,
+                    in_tangent: Self,
+                    other: Self,
+                    dt: f32,
+                    s: f32,
+                ) -> Self {
+                    hermite(self, out_tangent, in_tangent, other, dt, s)
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_vector!(f32, Vec2, Vec3, Vec4);
+
+impl Interpolate for Quat {
+    fn lerp(self, other: Self, s: f32) -> Self {
+        self.slerp(other, s)
+    }
+
+    fn cubic_spline(
+        self,
+        out_tangent: Self,
+        in_tangent: Self,
+        other: Self,
+        dt: f32,
+        s: f32,
+    ) -> Self {
+        let value = hermite(
+            Vec4::from(self),
+            Vec4::from(out_tangent),
+            Vec4::from(in_tangent),
+            Vec4::from(other),
+            dt,
+            s,
+        );
+        Quat::from_vec4(value).normalize()
+    }
+}
+
+fn sample<T: Interpolate>(
+    timestamps: &[f32],
+    values: &[T],
+    interpolation: Interpolation,
+    time: f32,
+) -> Option<T> {
+    let values_per_keyframe = match interpolation {
+        Interpolation::CubicSpline => 3,
+        Interpolation::Step | Interpolation::Linear => 1,
+    };
+    if timestamps.is_empty() || values.len() != timestamps.len() * values_per_keyframe {
+        return None;
+    }
+    let value = |keyframe: usize| match interpolation {
+        Interpolation::CubicSpline => values[keyframe * 3 + 1],
+        Interpolation::Step | Interpolation::Linear => values[keyframe],
+    };
+
+    // index of the last keyframe at or before `time`
+    let keyframe = match timestamps.iter().rposition(|timestamp| *timestamp <= time) {
+        Some(keyframe) => keyframe,
+        None => return Some(value(0)),
+    };
+    if keyframe + 1 == timestamps.len() {
+        return Some(value(keyframe));
+    }
+
+    let dt = timestamps[keyframe + 1] - timestamps[keyframe];
+    let s = if dt > 0.0 {
+        (time - timestamps[keyframe]) / dt
+    } else {
+        0.0
+    };
+    Some(match interpolation {
+        Interpolation::Step => value(keyframe),
+        Interpolation::Linear => value(keyframe).lerp(value(keyframe + 1), s),
+        Interpolation::CubicSpline => value(keyframe).cubic_spline(
+            values[keyframe * 3 + 2],
+            values[(keyframe + 1) * 3],
+            value(keyframe + 1),
+            dt,
+            s,
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translation_curve(interpolation: Interpolation, values: Vec<Vec3>) -> VariableCurve {
+        VariableCurve {
+            keyframe_timestamps: vec![0.0, 1.0, 3.0],
+            keyframes: Keyframes::Translation(values),
+            interpolation,
+        }
+    }
+
+    fn translation_at(curve: &VariableCurve, time: f32) -> Vec3 {
+        match curve.sample(time) {
+            Some(KeyframeValue::Translation(translation)) => translation,
+            value => panic!("unexpected value {:?}", value),
+        }
+    }
+
+    #[test]
+    fn sample_step_and_linear() {
+        let values = vec![Vec3::ZERO, Vec3::X, Vec3::new(3.0, 2.0, 0.0)];
+        let step = translation_curve(Interpolation::Step, values.clone());
+        assert_eq!(translation_at(&step, -1.0), Vec3::ZERO);
+        assert_eq!(translation_at(&step, 0.5), Vec3::ZERO);
+        assert_eq!(translation_at(&step, 2.0), Vec3::X);
+        assert_eq!(translation_at(&step, 5.0), Vec3::new(3.0, 2.0, 0.0));
+
+        let linear = translation_curve(Interpolation::Linear, values);
+        assert_eq!(translation_at(&linear, 0.5), Vec3::new(0.5, 0.0, 0.0));
+        assert_eq!(translation_at(&linear, 2.0), Vec3::new(2.0, 1.0, 0.0));
+        assert_eq!(linear.duration(), 3.0);
+
+        let invalid = translation_curve(Interpolation::Linear, vec![Vec3::ZERO]);
+        assert_eq!(invalid.sample(0.0), None);
+    }
+
+    #[test]
+    fn sample_cubic_spline() {
+        // in-tangent, value, out-tangent for each keyframe
+        let values = vec![
+            Vec3::ZERO,
+            Vec3::ZERO,
+            Vec3::X,
+            Vec3::X,
+            Vec3::X,
+            Vec3::X,
+            Vec3::ZERO,
+            Vec3::new(3.0, 0.0, 0.0),
+            Vec3::ZERO,
+        ];
+        let curve = translation_curve(Interpolation::CubicSpline, values);
+        assert_eq!(translation_at(&curve, 0.0), Vec3::ZERO);
+        assert_eq!(translation_at(&curve, 1.0), Vec3::X);
+        // tangents of 1 between two keyframes 1 apart give a straight line
+        assert!(translation_at(&curve, 0.5).abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-6));
+        let halfway = translation_at(&curve, 2.0);
+        assert!(halfway.x > 1.0 && halfway.x < 3.0);
+    }
+
+    #[test]
+    fn blend_field() {
+        let curve = VariableCurve {
+            keyframe_timestamps: vec![0.0, 2.0],
+            keyframes: Keyframes::Field(
+                FieldTarget::new("Light", "intensity"),
+                FieldKeyframes::F32(vec![0.0, 10.0]),
+            ),
+            interpolation: Interpolation::Linear,
+        };
+        assert_eq!(
+            curve.field_target(),
+            Some(&FieldTarget::new("Light", "intensity"))
+        );
+        let value = match curve.sample(1.0) {
+            Some(KeyframeValue::Field(value)) => value,
+            value => panic!("unexpected value {:?}", value),
+        };
+        assert_eq!(value, FieldValue::F32(5.0));
+
+        let mut intensity = 1.0f32;
+        assert!(value.blend_into(&mut intensity, 0.5));
+        assert_eq!(intensity, 3.0);
+        let mut position = Vec3::ZERO;
+        assert!(!value.blend_into(&mut position, 1.0));
+    }
+
+    #[test]
+    fn apply_rotation() {
+        let curve = VariableCurve {
+            keyframe_timestamps: vec![0.0, 1.0],
+            keyframes: Keyframes::Rotation(vec![
+                Quat::IDENTITY,
+                Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+            ]),
+            interpolation: Interpolation::Linear,
+        };
+        let mut transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        curve.apply(0.5, &mut transform);
+        assert!(transform
+            .rotation
+            .abs_diff_eq(Quat::from_rotation_y(std::f32::consts::FRAC_PI_4), 1e-5));
+        assert_eq!(transform.translation, Vec3::new(1.0, 2.0, 3.0));
+    }
+}
@@ -0,0 +1,32 @@
+mod animation_clip;
+mod animation_player;
+mod curve;
+
+pub use animation_clip::*;
+pub use animation_player::*;
+pub use curve::*;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{AnimationClip, AnimationPlayer, AnimationPlugin, EntityPath};
+}
+
+use bevy_app::prelude::*;
+use bevy_asset::AddAsset;
+use bevy_ecs::{schedule::ExclusiveSystemDescriptorCoercion, system::IntoExclusiveSystem};
+
+/// Adds the [`AnimationClip`] assets and plays the [`AnimationPlayer`]s of an App
+#[derive(Default)]
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<AnimationClip>()
+            .register_type::<AnimationPlayer>()
+            // animations are applied before the transforms are propagated
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                animation_player_system.exclusive_system().at_start(),
+            );
+    }
+}
@@ -837,6 +837,23 @@ impl App {
         self
     }
 
+    /// Adds a migration updating the scene data saved by the current version of the type `T` to
+    /// the next version. `T` must already be registered.
+    #[cfg(feature = "bevy_reflect")]
+    pub fn register_migration<T: bevy_reflect::Reflect>(
+        &mut self,
+        migration: bevy_reflect::Migration,
+    ) -> &mut Self {
+        {
+            let registry = self
+                .world
+                .get_resource_mut::<bevy_reflect::TypeRegistryArc>()
+                .unwrap();
+            registry.write().register_migration::<T>(migration);
+        }
+        self
+    }
+
     pub fn add_sub_app(
         &mut self,
         label: impl AppLabel,
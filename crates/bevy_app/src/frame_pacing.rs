@@ -0,0 +1,63 @@
+use bevy_utils::{Duration, Instant};
+
+/// A duration of the busy-wait ending [`wait_until`] that hits deadlines precisely on most
+/// platforms. The winit runner uses it by default when the frame rate is capped, the schedule
+/// runner only spins when its `spin_duration` is set.
+pub const DEFAULT_SPIN_DURATION: Duration = Duration::from_millis(1);
+
+/// Blocks the current thread until `deadline`.
+///
+/// [`std::thread::sleep`] is only as accurate as the OS scheduler, which can oversleep by a
+/// few milliseconds. To hit the deadline precisely, the thread sleeps until `spin_duration`
+/// before it, then spins for the remaining time. A longer `spin_duration` is more accurate
+/// but uses more CPU time.
+pub fn wait_until(deadline: Instant, spin_duration: Duration) {
+    let now = Instant::now();
+    if deadline <= now {
+        return;
+    }
+    let remaining = deadline - now;
+    if remaining > spin_duration {
+        std::thread::sleep(remaining - spin_duration);
+    }
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+/// Returns the duration of a frame when running at `fps` frames per second, or `None` if
+/// `fps` isn't a positive number.
+pub fn frame_duration(fps: f64) -> Option<Duration> {
+    if fps > 0.0 && fps.is_finite() {
+        Some(Duration::from_secs_f64(1.0 / fps))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait() {
+        let start = Instant::now();
+        wait_until(start + Duration::from_millis(5), DEFAULT_SPIN_DURATION);
+        assert!(start.elapsed() >= Duration::from_millis(5));
+
+        let start = Instant::now();
+        wait_until(start + Duration::from_millis(2), Duration::from_millis(10));
+        assert!(start.elapsed() >= Duration::from_millis(2));
+
+        // deadlines in the past return immediately
+        wait_until(start, DEFAULT_SPIN_DURATION);
+    }
+
+    #[test]
+    fn frame_duration_from_fps() {
+        assert_eq!(frame_duration(50.0), Some(Duration::from_millis(20)));
+        assert_eq!(frame_duration(0.0), None);
+        assert_eq!(frame_duration(-1.0), None);
+        assert_eq!(frame_duration(f64::INFINITY), None);
+    }
+}
@@ -2,6 +2,7 @@
 //! app.
 
 mod app;
+mod frame_pacing;
 mod plugin;
 mod plugin_group;
 mod schedule_runner;
@@ -12,6 +13,7 @@ mod ci_testing;
 pub use app::*;
 pub use bevy_derive::DynamicPlugin;
 pub use bevy_ecs::event::*;
+pub use frame_pacing::*;
 pub use plugin::*;
 pub use plugin_group::*;
 pub use schedule_runner::*;
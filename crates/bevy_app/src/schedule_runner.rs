@@ -1,5 +1,6 @@
 use crate::{
     app::{App, AppExit},
+    frame_pacing::frame_duration,
     plugin::Plugin,
     ManualEventReader,
 };
@@ -14,7 +15,10 @@ use wasm_bindgen::{prelude::*, JsCast};
 /// Determines the method used to run an [App]'s `Schedule`
 #[derive(Copy, Clone, Debug)]
 pub enum RunMode {
-    Loop { wait: Option<Duration> },
+    /// Updates the app in a loop. If `wait` is set, each update lasts at least `wait`.
+    Loop {
+        wait: Option<Duration>,
+    },
     Once,
 }
 
@@ -24,15 +28,32 @@ impl Default for RunMode {
     }
 }
 
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone)]
 pub struct ScheduleRunnerSettings {
     pub run_mode: RunMode,
+    /// How long before the end of a [RunMode::Loop] `wait` the runner stops sleeping and starts
+    /// spinning, see [wait_until](crate::wait_until).
+    ///
+    /// Defaults to zero: the runner only sleeps. Set it to e.g.
+    /// [DEFAULT_SPIN_DURATION](crate::DEFAULT_SPIN_DURATION) for more precise frame pacing, at
+    /// the cost of CPU time.
+    pub spin_duration: Duration,
+}
+
+impl Default for ScheduleRunnerSettings {
+    fn default() -> Self {
+        ScheduleRunnerSettings {
+            run_mode: Default::default(),
+            spin_duration: Duration::ZERO,
+        }
+    }
 }
 
 impl ScheduleRunnerSettings {
     pub fn run_once() -> Self {
         ScheduleRunnerSettings {
             run_mode: RunMode::Once,
+            ..Default::default()
         }
     }
 
@@ -41,6 +62,19 @@ impl ScheduleRunnerSettings {
             run_mode: RunMode::Loop {
                 wait: Some(wait_duration),
             },
+            ..Default::default()
+        }
+    }
+
+    /// Runs the app in a loop, updating it at most `fps` times per second.
+    ///
+    /// If `fps` isn't a positive number, the loop is not capped.
+    pub fn run_loop_fps(fps: f64) -> Self {
+        ScheduleRunnerSettings {
+            run_mode: RunMode::Loop {
+                wait: frame_duration(fps),
+            },
+            ..Default::default()
         }
     }
 }
@@ -65,7 +99,7 @@ impl Plugin for ScheduleRunnerPlugin {
                 RunMode::Loop { wait } => {
                     let mut tick = move |app: &mut App,
                                          wait: Option<Duration>|
-                          -> Result<Option<Duration>, AppExit> {
+                          -> Result<Option<Instant>, AppExit> {
                         let start_time = Instant::now();
 
                         if let Some(app_exit_events) =
@@ -88,23 +122,14 @@ impl Plugin for ScheduleRunnerPlugin {
                             }
                         }
 
-                        let end_time = Instant::now();
-
-                        if let Some(wait) = wait {
-                            let exe_time = end_time - start_time;
-                            if exe_time < wait {
-                                return Ok(Some(wait - exe_time));
-                            }
-                        }
-
-                        Ok(None)
+                        Ok(wait.map(|wait| start_time + wait))
                     };
 
                     #[cfg(not(target_arch = "wasm32"))]
                     {
-                        while let Ok(delay) = tick(&mut app, wait) {
-                            if let Some(delay) = delay {
-                                std::thread::sleep(delay);
+                        while let Ok(deadline) = tick(&mut app, wait) {
+                            if let Some(deadline) = deadline {
+                                crate::wait_until(deadline, settings.spin_duration);
                             }
                         }
                     }
@@ -128,10 +153,15 @@ impl Plugin for ScheduleRunnerPlugin {
 
                         let c = move || {
                             let mut app = Rc::get_mut(&mut rc).unwrap();
-                            let delay = tick(&mut app, wait);
-                            match delay {
-                                Ok(delay) => {
-                                    set_timeout(f.borrow().as_ref().unwrap(), delay.unwrap_or(asap))
+                            let deadline = tick(&mut app, wait);
+                            match deadline {
+                                Ok(deadline) => {
+                                    let now = Instant::now();
+                                    let delay = match deadline {
+                                        Some(deadline) if deadline > now => deadline - now,
+                                        _ => asap,
+                                    };
+                                    set_timeout(f.borrow().as_ref().unwrap(), delay)
                                 }
                                 Err(_) => {}
                             }
@@ -1,17 +1,32 @@
 use crate::{
-    path::{AssetPath, AssetPathId, SourcePathId},
-    Asset, AssetIo, AssetIoError, AssetLifecycle, AssetLifecycleChannel, AssetLifecycleEvent,
-    AssetLoader, Assets, Handle, HandleId, HandleUntyped, LabelId, LoadContext, LoadState,
-    RefChange, RefChangeChannel, SourceInfo, SourceMeta,
+    dependency_graph::{file_path, DependencyGraph},
+    loader::{ErasedAssetLoader, LoaderSettings},
+    path::{get_hasher, AssetPath, AssetPathId, SourcePathId},
+    saver::ErasedAssetSaver,
+    Asset, AssetEvent, AssetIo, AssetIoError, AssetLifecycle, AssetLifecycleChannel,
+    AssetLifecycleEvent, AssetLoader, AssetSaver, Assets, EmbeddedAssetIo, Handle, HandleId,
+    HandleUntyped, LabelId, LoadContext, LoadState, MemoryAssetIo, RefChange, RefChangeChannel,
+    SourceInfo, SourceMeta, EMBEDDED_ASSET_SOURCE, MEMORY_ASSET_SOURCE,
 };
 use anyhow::Result;
+use bevy_app::EventWriter;
 use bevy_ecs::system::{Res, ResMut};
 use bevy_log::warn;
+use bevy_reflect::TypeUuid;
 use bevy_tasks::TaskPool;
-use bevy_utils::{HashMap, Uuid};
+use bevy_utils::{HashMap, HashSet, Uuid};
 use crossbeam_channel::TryRecvError;
 use parking_lot::{Mutex, RwLock};
-use std::{collections::hash_map::Entry, path::Path, sync::Arc};
+use serde::Serialize;
+use std::{
+    collections::hash_map::Entry,
+    hash::Hasher,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 use thiserror::Error;
 
 /// Errors that occur while loading assets with an AssetServer
@@ -23,10 +38,35 @@ pub enum AssetServerError {
     MissingAssetLoader { extensions: Vec<String> },
     #[error("the given type does not match the type of the loaded asset")]
     IncorrectHandleType,
+    #[error(
+        "the given settings do not match the settings of the asset loader, expected {expected}"
+    )]
+    IncorrectSettingsType { expected: &'static str },
     #[error("encountered an error while loading an asset: {0}")]
     AssetLoaderError(anyhow::Error),
     #[error("encountered an error while reading an asset: {0}")]
     AssetIoError(#[from] AssetIoError),
+    #[error("no `AssetSaver` found for this asset type{}", format_missing_asset_ext(.extensions))]
+    MissingAssetSaver { extensions: Vec<String> },
+    #[error("the asset to save is not loaded")]
+    AssetNotLoaded,
+    #[error("encountered an error while saving an asset: {0}")]
+    AssetSaverError(anyhow::Error),
+    #[error("no asset source named {0:?}")]
+    MissingAssetSource(String),
+}
+
+/// Sent when an asset fails to load. The error is retained until the asset is loaded again, see
+/// [`AssetServer::get_load_error`].
+#[derive(Debug)]
+pub struct AssetLoadFailedEvent {
+    pub path: AssetPath<'static>,
+    /// A weak handle to the asset
+    pub handle: HandleUntyped,
+    /// [`AssetServerError::AssetIoError`] when the file couldn't be read, for example with
+    /// [`AssetIoError::NotFound`], [`AssetServerError::MissingAssetLoader`] when no loader
+    /// supports its extension, and [`AssetServerError::AssetLoaderError`] when the loader failed
+    pub error: Arc<AssetServerError>,
 }
 
 fn format_missing_asset_ext(exts: &[String]) -> String {
@@ -49,13 +89,26 @@ pub(crate) struct AssetRefCounter {
 }
 
 pub struct AssetServerInternal {
-    pub(crate) asset_io: Box<dyn AssetIo>,
+    pub(crate) asset_io: Arc<dyn AssetIo>,
+    /// Named asset sources, loaded from paths like `"name://path/to/file.ext"`
+    pub(crate) asset_io_sources: RwLock<HashMap<String, Arc<dyn AssetIo>>>,
+    /// Set by [`AssetServer::watch_for_changes`], so that sources added later are watched too
+    watching_for_changes: AtomicBool,
+    embedded_assets: Arc<EmbeddedAssetIo>,
+    memory_assets: Arc<MemoryAssetIo>,
     pub(crate) asset_ref_counter: AssetRefCounter,
     pub(crate) asset_sources: Arc<RwLock<HashMap<SourcePathId, SourceInfo>>>,
     pub(crate) asset_lifecycles: Arc<RwLock<HashMap<Uuid, Box<dyn AssetLifecycle>>>>,
-    loaders: RwLock<Vec<Arc<dyn AssetLoader>>>,
+    loaders: RwLock<Vec<Arc<dyn ErasedAssetLoader>>>,
     extension_to_loader_index: RwLock<HashMap<String, usize>>,
     handle_to_path: Arc<RwLock<HashMap<HandleId, AssetPath<'static>>>>,
+    savers: RwLock<HashMap<Uuid, Vec<Arc<dyn ErasedAssetSaver>>>>,
+    pending_saves: Mutex<HashMap<Uuid, Vec<(HandleId, AssetPath<'static>)>>>,
+    /// Hashes of the files written when saving assets back to the path they were loaded from,
+    /// used to skip reloading them
+    saved_files: RwLock<HashMap<AssetPath<'static>, u64>>,
+    dependency_graph: RwLock<DependencyGraph>,
+    failed_loads: Mutex<Vec<AssetLoadFailedEvent>>,
     task_pool: TaskPool,
 }
 
@@ -77,7 +130,14 @@ impl AssetServer {
         Self::with_boxed_io(Box::new(source_io), task_pool)
     }
 
+    /// Creates an asset server loading assets from `asset_io` by default. The
+    /// [`EMBEDDED_ASSET_SOURCE`] and [`MEMORY_ASSET_SOURCE`] sources are registered as well.
     pub fn with_boxed_io(asset_io: Box<dyn AssetIo>, task_pool: TaskPool) -> Self {
+        let embedded_assets = Arc::new(EmbeddedAssetIo::default());
+        let memory_assets = Arc::new(MemoryAssetIo::default());
+        let mut asset_io_sources: HashMap<String, Arc<dyn AssetIo>> = HashMap::default();
+        asset_io_sources.insert(EMBEDDED_ASSET_SOURCE.to_string(), embedded_assets.clone());
+        asset_io_sources.insert(MEMORY_ASSET_SOURCE.to_string(), memory_assets.clone());
         AssetServer {
             server: Arc::new(AssetServerInternal {
                 loaders: Default::default(),
@@ -86,8 +146,17 @@ impl AssetServer {
                 asset_ref_counter: Default::default(),
                 handle_to_path: Default::default(),
                 asset_lifecycles: Default::default(),
+                savers: Default::default(),
+                pending_saves: Default::default(),
+                saved_files: Default::default(),
+                dependency_graph: Default::default(),
+                failed_loads: Default::default(),
                 task_pool,
-                asset_io,
+                asset_io: asset_io.into(),
+                asset_io_sources: RwLock::new(asset_io_sources),
+                watching_for_changes: AtomicBool::new(false),
+                embedded_assets,
+                memory_assets,
             }),
         }
     }
@@ -104,6 +173,7 @@ impl AssetServer {
     where
         T: AssetLoader,
     {
+        let loader: Arc<dyn ErasedAssetLoader> = Arc::new(loader);
         let mut loaders = self.server.loaders.write();
         let loader_index = loaders.len();
         for extension in loader.extensions().iter() {
@@ -112,11 +182,72 @@ impl AssetServer {
                 .write()
                 .insert(extension.to_string(), loader_index);
         }
-        loaders.push(Arc::new(loader));
+        loaders.push(loader);
     }
 
+    pub fn add_saver<T>(&self, saver: T)
+    where
+        T: AssetSaver,
+    {
+        self.server
+            .savers
+            .write()
+            .entry(<T::Asset as TypeUuid>::TYPE_UUID)
+            .or_default()
+            .push(Arc::new(saver));
+    }
+
+    /// Registers `asset_io` as the asset source named `name`, replacing the previous source of
+    /// that name. Assets are loaded from it with paths like `"name://path/to/file.ext"`.
+    ///
+    /// If [`AssetServer::watch_for_changes`] was called, the new source is watched as well.
+    pub fn add_source<T: AssetIo>(&self, name: impl Into<String>, asset_io: T) {
+        let name = name.into();
+        if self.server.watching_for_changes.load(Ordering::Relaxed) {
+            if let Err(err) = asset_io.watch_for_changes() {
+                warn!("Failed to watch asset source {} for changes: {}", name, err);
+            }
+        }
+        self.server
+            .asset_io_sources
+            .write()
+            .insert(name, Arc::new(asset_io));
+    }
+
+    /// Returns the asset source named `name`, or the default source for `None`.
+    pub fn get_source(&self, name: Option<&str>) -> Result<Arc<dyn AssetIo>, AssetServerError> {
+        match name {
+            Some(name) => self
+                .server
+                .asset_io_sources
+                .read()
+                .get(name)
+                .cloned()
+                .ok_or_else(|| AssetServerError::MissingAssetSource(name.to_string())),
+            None => Ok(self.server.asset_io.clone()),
+        }
+    }
+
+    /// Returns the assets embedded in the binary, registered as the [`EMBEDDED_ASSET_SOURCE`].
+    pub fn embedded_assets(&self) -> &EmbeddedAssetIo {
+        &self.server.embedded_assets
+    }
+
+    /// Returns the assets stored in memory, registered as the [`MEMORY_ASSET_SOURCE`].
+    pub fn memory_assets(&self) -> &MemoryAssetIo {
+        &self.server.memory_assets
+    }
+
+    /// Watches the default asset source and every named source for changes, reloading the
+    /// assets loaded from files that changed.
     pub fn watch_for_changes(&self) -> Result<(), AssetServerError> {
+        self.server
+            .watching_for_changes
+            .store(true, Ordering::Relaxed);
         self.server.asset_io.watch_for_changes()?;
+        for asset_io in self.server.asset_io_sources.read().values() {
+            asset_io.watch_for_changes()?;
+        }
         Ok(())
     }
 
@@ -130,7 +261,10 @@ impl AssetServer {
         HandleUntyped::strong(id.into(), sender)
     }
 
-    fn get_asset_loader(&self, extension: &str) -> Result<Arc<dyn AssetLoader>, AssetServerError> {
+    fn get_asset_loader(
+        &self,
+        extension: &str,
+    ) -> Result<Arc<dyn ErasedAssetLoader>, AssetServerError> {
         let index = {
             // scope map to drop lock as soon as possible
             let map = self.server.extension_to_loader_index.read();
@@ -146,7 +280,7 @@ impl AssetServer {
     fn get_path_asset_loader<P: AsRef<Path>>(
         &self,
         path: P,
-    ) -> Result<Arc<dyn AssetLoader>, AssetServerError> {
+    ) -> Result<Arc<dyn ErasedAssetLoader>, AssetServerError> {
         let s = path
             .as_ref()
             .file_name()
@@ -173,6 +307,37 @@ impl AssetServer {
         })
     }
 
+    fn get_path_asset_saver(
+        &self,
+        type_uuid: Uuid,
+        path: &Path,
+    ) -> Result<Arc<dyn ErasedAssetSaver>, AssetServerError> {
+        let file_name = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .map(|file_name| file_name.to_lowercase())
+            .ok_or(AssetServerError::MissingAssetSaver {
+                extensions: Vec::new(),
+            })?;
+        let savers = self.server.savers.read();
+        let savers = savers.get(&type_uuid).map_or(&[][..], |savers| &savers[..]);
+
+        let mut exts = Vec::new();
+        let mut ext = file_name.as_str();
+        while let Some(idx) = ext.find('.') {
+            ext = &ext[idx + 1..];
+            exts.push(ext.to_string());
+            if let Some(saver) = savers
+                .iter()
+                .rev()
+                .find(|saver| saver.extensions().contains(&ext))
+            {
+                return Ok(saver.clone());
+            }
+        }
+        Err(AssetServerError::MissingAssetSaver { extensions: exts })
+    }
+
     pub fn get_handle_path<H: Into<HandleId>>(&self, handle: H) -> Option<AssetPath<'_>> {
         self.server
             .handle_to_path
@@ -181,6 +346,18 @@ impl AssetServer {
             .cloned()
     }
 
+    /// Returns the number of strong handles to the asset of `handle`, as of the last
+    /// [`AssetServer::mark_unused_assets`], which runs once per frame.
+    pub fn get_ref_count<H: Into<HandleId>>(&self, handle: H) -> usize {
+        self.server
+            .asset_ref_counter
+            .ref_counts
+            .read()
+            .get(&handle.into())
+            .copied()
+            .unwrap_or(0)
+    }
+
     pub fn get_load_state<H: Into<HandleId>>(&self, handle: H) -> LoadState {
         match handle.into() {
             HandleId::AssetPathId(id) => {
@@ -213,6 +390,52 @@ impl AssetServer {
         load_state
     }
 
+    /// Returns the load state of the asset of `handle` combined with the load states of its
+    /// dependencies, recursively.
+    ///
+    /// The dependencies of an asset are only known once it is loaded, so this is
+    /// [`LoadState::Loaded`] once the asset and all of its direct and indirect dependencies are
+    /// loaded. [`AssetEvent::LoadedWithDependencies`](crate::AssetEvent::LoadedWithDependencies)
+    /// is sent at that point.
+    pub fn get_recursive_dependency_load_state<H: Into<HandleId>>(&self, handle: H) -> LoadState {
+        let root = match handle.into() {
+            HandleId::AssetPathId(id) => id.source_path_id(),
+            HandleId::Id(_, _) => return LoadState::NotLoaded,
+        };
+
+        let asset_sources = self.server.asset_sources.read();
+        let mut load_state = LoadState::Loaded;
+        let mut visited = HashSet::default();
+        let mut stack = vec![root];
+        while let Some(source_path_id) = stack.pop() {
+            if !visited.insert(source_path_id) {
+                continue;
+            }
+            let source_info = match asset_sources.get(&source_path_id) {
+                Some(source_info) => source_info,
+                // dependencies are queued before the asset is loaded, but they may not have
+                // started loading yet
+                None if source_path_id != root => {
+                    load_state = LoadState::Loading;
+                    continue;
+                }
+                None => return LoadState::NotLoaded,
+            };
+            match source_info.load_state {
+                LoadState::Loaded => {}
+                LoadState::Loading => load_state = LoadState::Loading,
+                load_state => return load_state,
+            }
+            if let Some(meta) = &source_info.meta {
+                for dependency in meta.assets.iter().flat_map(|asset| &asset.dependencies) {
+                    stack.push(dependency.get_id().source_path_id());
+                }
+            }
+        }
+
+        load_state
+    }
+
     /// Queue an [`Asset`] at the provided relative path for asynchronous loading.
     ///
     /// The absolute Path to the asset is "ROOT/ASSET_FOLDER_NAME/path".
@@ -235,12 +458,65 @@ impl AssetServer {
         self.load_untyped(path).typed()
     }
 
+    /// Queue an [`Asset`] for asynchronous loading like [`AssetServer::load`], with the
+    /// [`AssetLoader::Settings`] of its loader changed by `settings`, starting from the default
+    /// settings.
+    ///
+    /// Loading the same path with different settings gives different assets, with distinct
+    /// handles.
+    ///
+    /// ```no_run
+    /// # use bevy_asset::{AssetServer, Handle};
+    /// # use bevy_reflect::TypeUuid;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(TypeUuid)]
+    /// # #[uuid = "2ac30437-e2c7-4e31-8c7d-6b3b7e5d2a21"]
+    /// # struct Image;
+    /// # #[derive(Default, Serialize, Deserialize)]
+    /// # struct ImageLoaderSettings { is_srgb: bool }
+    /// # fn example(asset_server: &AssetServer) {
+    /// let normal_map: Handle<Image> =
+    ///     asset_server.load_with_settings("textures/normal.png", |s: &mut ImageLoaderSettings| {
+    ///         s.is_srgb = false;
+    ///     });
+    /// # }
+    /// ```
+    #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
+    pub fn load_with_settings<'a, T, S, P>(
+        &self,
+        path: P,
+        settings: impl FnOnce(&mut S),
+    ) -> Handle<T>
+    where
+        T: Asset,
+        S: Serialize + Default + Send + Sync + 'static,
+        P: Into<AssetPath<'a>>,
+    {
+        self.load_untyped_with_settings(path, settings).typed()
+    }
+
     async fn load_async(
         &self,
         asset_path: AssetPath<'_>,
+        settings: Option<LoaderSettings>,
         force: bool,
     ) -> Result<AssetPathId, AssetServerError> {
-        let asset_path_id: AssetPathId = asset_path.get_id();
+        let asset_path_id = get_asset_path_id(&asset_path, settings.as_ref());
+        let asset_io = self.get_source(asset_path.source());
+
+        // skip reloading files saved by this server, unless they changed since
+        if force {
+            let file_path = file_path(&asset_path);
+            let saved_hash = self.server.saved_files.read().get(&file_path).copied();
+            if let (Some(saved_hash), Ok(asset_io)) = (saved_hash, &asset_io) {
+                if let Ok(bytes) = asset_io.load_path(asset_path.path()).await {
+                    if hash_bytes(&bytes) == saved_hash {
+                        return Ok(asset_path_id);
+                    }
+                }
+                self.server.saved_files.write().remove(&file_path);
+            }
+        }
 
         // load metadata and update source info. this is done in a scope to ensure we release the
         // locks before loading
@@ -253,7 +529,10 @@ impl AssetServer {
                     committed_assets: Default::default(),
                     load_state: LoadState::NotLoaded,
                     meta: None,
+                    source: asset_path.source().map(|source| source.to_string()),
                     path: asset_path.path().to_owned(),
+                    loader_settings: settings.clone(),
+                    error: None,
                     version: 0,
                 }),
             };
@@ -269,6 +548,7 @@ impl AssetServer {
             }
 
             source_info.load_state = LoadState::Loading;
+            source_info.error = None;
             source_info.committed_assets.clear();
             source_info.version += 1;
             source_info.meta = None;
@@ -283,6 +563,14 @@ impl AssetServer {
             source_info.load_state = LoadState::Failed;
         };
 
+        let asset_io = match asset_io {
+            Ok(asset_io) => asset_io,
+            Err(err) => {
+                set_asset_failed();
+                return Err(err);
+            }
+        };
+
         // get the according asset loader
         let asset_loader = match self.get_path_asset_loader(asset_path.path()) {
             Ok(loader) => loader,
@@ -291,27 +579,51 @@ impl AssetServer {
                 return Err(err);
             }
         };
-
-        // load the asset bytes
-        let bytes = match self.server.asset_io.load_path(asset_path.path()).await {
-            Ok(bytes) => bytes,
-            Err(err) => {
+        if let Some(settings) = &settings {
+            if (*settings.value).type_id() != asset_loader.settings_type_id() {
                 set_asset_failed();
-                return Err(AssetServerError::AssetIoError(err));
+                return Err(AssetServerError::IncorrectSettingsType {
+                    expected: asset_loader.settings_type_name(),
+                });
+            }
+        }
+
+        // load the asset bytes, or open the asset file for loaders streaming it
+        let (bytes, reader) = if asset_loader.streams_asset() {
+            match asset_io.read_path(asset_path.path()).await {
+                Ok(reader) => (Vec::new(), Some(reader)),
+                Err(err) => {
+                    set_asset_failed();
+                    return Err(AssetServerError::AssetIoError(err));
+                }
+            }
+        } else {
+            match asset_io.load_path(asset_path.path()).await {
+                Ok(bytes) => (bytes, None),
+                Err(err) => {
+                    set_asset_failed();
+                    return Err(AssetServerError::AssetIoError(err));
+                }
             }
         };
 
         // load the asset source using the corresponding AssetLoader
         let mut load_context = LoadContext::new(
             asset_path.path(),
+            asset_path_id.source_path_id(),
             &self.server.asset_ref_counter.channel,
-            &*self.server.asset_io,
+            &asset_io,
             version,
             &self.server.task_pool,
         );
+        load_context.reader = reader;
 
         if let Err(err) = asset_loader
-            .load(&bytes, &mut load_context)
+            .load(
+                &bytes,
+                settings.map(|settings| settings.value),
+                &mut load_context,
+            )
             .await
             .map_err(AssetServerError::AssetLoaderError)
         {
@@ -343,20 +655,40 @@ impl AssetServer {
             assets: load_context.get_asset_metas(),
         });
 
+        // files read by the loader are dependencies of the asset, to reload it when they change
+        let mut dependencies: HashSet<_> = load_context
+            .read_paths
+            .get_mut()
+            .drain(..)
+            .map(|path| {
+                let path = AssetPath::new(path, None);
+                match asset_path.source() {
+                    Some(source) => path.with_source(source.to_string()),
+                    None => path,
+                }
+            })
+            .collect();
+
         // load asset dependencies and prepare asset type hashmap
         for (label, loaded_asset) in load_context.labeled_assets.iter_mut() {
             let label_id = LabelId::from(label.as_ref().map(|label| label.as_str()));
             let type_uuid = loaded_asset.value.as_ref().unwrap().type_uuid();
             source_info.asset_types.insert(label_id, type_uuid);
             for dependency in loaded_asset.dependencies.iter() {
+                dependencies.insert(file_path(dependency));
                 self.load_untracked(dependency.clone(), false);
             }
         }
 
-        self.server
-            .asset_io
-            .watch_path_for_changes(asset_path.path())
-            .unwrap();
+        let file = file_path(&asset_path);
+        dependencies.remove(&file);
+        self.server.dependency_graph.write().set_dependencies(
+            asset_path_id.source_path_id(),
+            file,
+            dependencies,
+        );
+
+        asset_io.watch_path_for_changes(asset_path.path()).unwrap();
         self.create_assets_in_load_context(&mut load_context);
         Ok(asset_path_id)
     }
@@ -367,50 +699,266 @@ impl AssetServer {
         self.get_handle_untyped(handle_id)
     }
 
+    /// Untyped version of [`AssetServer::load_with_settings`]
+    #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
+    pub fn load_untyped_with_settings<'a, S, P>(
+        &self,
+        path: P,
+        settings: impl FnOnce(&mut S),
+    ) -> HandleUntyped
+    where
+        S: Serialize + Default + Send + Sync + 'static,
+        P: Into<AssetPath<'a>>,
+    {
+        let mut value = S::default();
+        settings(&mut value);
+        let settings = LoaderSettings::new(value);
+        let handle_id = self.load_untracked_with_settings(path.into(), Some(settings), false);
+        self.get_handle_untyped(handle_id)
+    }
+
     pub(crate) fn load_untracked(&self, asset_path: AssetPath<'_>, force: bool) -> HandleId {
+        self.load_untracked_with_settings(asset_path, None, force)
+    }
+
+    fn load_untracked_with_settings(
+        &self,
+        asset_path: AssetPath<'_>,
+        settings: Option<LoaderSettings>,
+        force: bool,
+    ) -> HandleId {
+        let handle_id = get_asset_path_id(&asset_path, settings.as_ref()).into();
         let server = self.clone();
         let owned_path = asset_path.to_owned();
         self.server
             .task_pool
             .spawn(async move {
-                if let Err(err) = server.load_async(owned_path, force).await {
-                    warn!("{}", err);
+                if let Err(err) = server.load_async(owned_path.clone(), settings, force).await {
+                    server.report_load_failure(owned_path, handle_id, err);
                 }
             })
             .detach();
 
-        let handle_id = asset_path.get_id().into();
         self.server
             .handle_to_path
             .write()
             .entry(handle_id)
             .or_insert_with(|| asset_path.to_owned());
 
-        asset_path.into()
+        handle_id
+    }
+
+    fn report_load_failure(
+        &self,
+        path: AssetPath<'static>,
+        handle_id: HandleId,
+        error: AssetServerError,
+    ) {
+        warn!("failed to load asset {:?}: {}", path, error);
+        let error = Arc::new(error);
+        if let HandleId::AssetPathId(id) = handle_id {
+            if let Some(source_info) = self
+                .server
+                .asset_sources
+                .write()
+                .get_mut(&id.source_path_id())
+            {
+                // the asset may be loading again already
+                if source_info.load_state == LoadState::Failed {
+                    source_info.error = Some(error.clone());
+                }
+            }
+        }
+        self.server.failed_loads.lock().push(AssetLoadFailedEvent {
+            path,
+            handle: HandleUntyped::weak(handle_id),
+            error,
+        });
+    }
+
+    /// Returns the error of the last load of the asset of `handle`, if it failed.
+    pub fn get_load_error<H: Into<HandleId>>(&self, handle: H) -> Option<Arc<AssetServerError>> {
+        match handle.into() {
+            HandleId::AssetPathId(id) => self
+                .server
+                .asset_sources
+                .read()
+                .get(&id.source_path_id())
+                .and_then(|source_info| source_info.error.clone()),
+            HandleId::Id(_, _) => None,
+        }
+    }
+
+    pub(crate) fn send_load_failed_events(&self, events: &mut EventWriter<AssetLoadFailedEvent>) {
+        let mut failed_loads = self.server.failed_loads.lock();
+        if !failed_loads.is_empty() {
+            events.send_batch(failed_loads.drain(..));
+        }
+    }
+
+    /// Reloads the assets loaded from the file at `path`, with each of the settings they were
+    /// loaded with. If no asset was loaded from `path`, it is loaded with the default settings.
+    ///
+    /// The assets depending on the file, directly or through other assets, are reloaded as well.
+    /// They depend on it when it is one of their [`LoadedAsset`](crate::LoadedAsset)
+    /// dependencies, or when it was read with [`LoadContext::read_asset_bytes`] while loading
+    /// them.
+    pub fn reload_path<'a, P: Into<AssetPath<'a>>>(&self, path: P) {
+        let file = file_path(&path.into());
+        let dependents = self
+            .server
+            .dependency_graph
+            .read()
+            .recursive_dependents(&file);
+
+        let (loaded_settings, dependents): (Vec<_>, Vec<_>) = {
+            let asset_sources = self.server.asset_sources.read();
+            let loaded_settings = asset_sources
+                .values()
+                .filter(|source_info| {
+                    source_info.source.as_deref() == file.source()
+                        && source_info.path == file.path()
+                })
+                .map(|source_info| source_info.loader_settings.clone())
+                .collect();
+            let dependents = dependents
+                .iter()
+                .filter_map(|source_path_id| asset_sources.get(source_path_id))
+                // skip the assets that were freed since they were loaded
+                .filter(|source_info| {
+                    source_info.load_state != LoadState::Unloaded
+                        && source_info.load_state != LoadState::NotLoaded
+                })
+                .map(|source_info| {
+                    let path = AssetPath::new(source_info.path.clone(), None);
+                    let path = match &source_info.source {
+                        Some(source) => path.with_source(source.clone()),
+                        None => path,
+                    };
+                    (path, source_info.loader_settings.clone())
+                })
+                .collect();
+            (loaded_settings, dependents)
+        };
+
+        if loaded_settings.is_empty() && self.get_path_asset_loader(file.path()).is_ok() {
+            self.load_untracked(file.clone(), true);
+        }
+        for settings in loaded_settings {
+            self.load_untracked_with_settings(file.clone(), settings, true);
+        }
+        for (path, settings) in dependents {
+            self.load_untracked_with_settings(path, settings, true);
+        }
+    }
+
+    /// Queue the asset of `handle` to be saved to `path`, relative to the asset folder or in the
+    /// asset source of `path`, with the [`AssetSaver`] registered for its type and the extension
+    /// of `path`.
+    ///
+    /// The asset is serialized at the end of the frame, and the file is written on a background
+    /// thread. Errors are logged. Saving an asset to the path it was loaded from doesn't reload
+    /// it when hot reloading is enabled.
+    pub fn save<'a, T: Asset, P: Into<AssetPath<'a>>>(&self, handle: &Handle<T>, path: P) {
+        self.server
+            .pending_saves
+            .lock()
+            .entry(T::TYPE_UUID)
+            .or_default()
+            .push((handle.id, path.into().to_owned()));
+    }
+
+    pub(crate) fn save_pending_assets<T: Asset>(&self, assets: &Assets<T>) {
+        let pending_saves = match self.server.pending_saves.lock().remove(&T::TYPE_UUID) {
+            Some(pending_saves) => pending_saves,
+            None => return,
+        };
+        for (handle_id, path) in pending_saves {
+            if let Err(err) = self.save_asset(assets, handle_id, path.clone()) {
+                warn!("failed to save asset to {:?}: {}", path, err);
+            }
+        }
+    }
+
+    fn save_asset<T: Asset>(
+        &self,
+        assets: &Assets<T>,
+        handle_id: HandleId,
+        path: AssetPath<'static>,
+    ) -> Result<(), AssetServerError> {
+        let asset = assets
+            .get(handle_id)
+            .ok_or(AssetServerError::AssetNotLoaded)?;
+        let asset_io = self.get_source(path.source())?;
+        let saver = self.get_path_asset_saver(T::TYPE_UUID, path.path())?;
+        let bytes = saver
+            .save(asset, path.path())
+            .map_err(AssetServerError::AssetSaverError)?;
+
+        if let HandleId::AssetPathId(id) = handle_id {
+            let asset_sources = self.server.asset_sources.read();
+            if asset_sources
+                .get(&id.source_path_id())
+                .map_or(false, |source_info| {
+                    source_info.source.as_deref() == path.source()
+                        && source_info.path == path.path()
+                })
+            {
+                self.server
+                    .saved_files
+                    .write()
+                    .insert(path.clone(), hash_bytes(&bytes));
+            }
+        }
+
+        let server = self.clone();
+        self.server
+            .task_pool
+            .spawn(async move {
+                if let Err(err) = asset_io.write_path(path.path(), &bytes).await {
+                    server.server.saved_files.write().remove(&path);
+                    warn!("failed to save asset to {:?}: {}", path, err);
+                }
+            })
+            .detach();
+        Ok(())
     }
 
     #[must_use = "not using the returned strong handles may result in the unexpected release of the assets"]
-    pub fn load_folder<P: AsRef<Path>>(
+    pub fn load_folder<'a, P: Into<AssetPath<'a>>>(
         &self,
         path: P,
     ) -> Result<Vec<HandleUntyped>, AssetServerError> {
-        let path = path.as_ref();
-        if !self.server.asset_io.is_directory(path) {
+        let path = path.into();
+        let asset_io = self.get_source(path.source())?;
+        self.load_folder_recursive(&*asset_io, path.source(), path.path())
+    }
+
+    fn load_folder_recursive(
+        &self,
+        asset_io: &dyn AssetIo,
+        source: Option<&str>,
+        path: &Path,
+    ) -> Result<Vec<HandleUntyped>, AssetServerError> {
+        if !asset_io.is_directory(path) {
             return Err(AssetServerError::AssetFolderNotADirectory(
                 path.to_str().unwrap().to_string(),
             ));
         }
 
         let mut handles = Vec::new();
-        for child_path in self.server.asset_io.read_directory(path.as_ref())? {
-            if self.server.asset_io.is_directory(&child_path) {
-                handles.extend(self.load_folder(&child_path)?);
+        for child_path in asset_io.read_directory(path)? {
+            if asset_io.is_directory(&child_path) {
+                handles.extend(self.load_folder_recursive(asset_io, source, &child_path)?);
             } else {
                 if self.get_path_asset_loader(&child_path).is_err() {
                     continue;
                 }
-                let handle =
-                    self.load_untyped(child_path.to_str().expect("Path should be a valid string."));
+                let asset_path = AssetPath::new_ref(&child_path, None);
+                let handle = match source {
+                    Some(source) => self.load_untyped(asset_path.with_source(source)),
+                    None => self.load_untyped(asset_path),
+                };
                 handles.push(handle);
             }
         }
@@ -479,9 +1027,15 @@ impl AssetServer {
                 .take()
                 .expect("Asset should exist at this point.");
             if let Some(asset_lifecycle) = asset_lifecycles.get(&asset_value.type_uuid()) {
-                let asset_path =
-                    AssetPath::new_ref(load_context.path, label.as_ref().map(|l| l.as_str()));
-                asset_lifecycle.create_asset(asset_path.into(), asset_value, load_context.version);
+                let asset_path_id = AssetPathId::new(
+                    load_context.source_path_id,
+                    LabelId::from(label.as_ref().map(|l| l.as_str())),
+                );
+                asset_lifecycle.create_asset(
+                    asset_path_id.into(),
+                    asset_value,
+                    load_context.version,
+                );
             } else {
                 panic!(
                     "Failed to find AssetLifecycle for label '{:?}', which has an asset type {} (UUID {:?}). \
@@ -522,6 +1076,9 @@ impl AssetServer {
                     }
 
                     assets.set_untracked(result.id, *result.asset);
+                    if let HandleId::AssetPathId(_) = result.id {
+                        assets.waiting_for_dependencies.insert(result.id);
+                    }
                 }
                 Ok(AssetLifecycleEvent::Free(handle_id)) => {
                     if let HandleId::AssetPathId(id) = handle_id {
@@ -540,9 +1097,45 @@ impl AssetServer {
                 Err(TryRecvError::Disconnected) => panic!("AssetChannel disconnected."),
             }
         }
+        drop(asset_sources_guard);
+
+        if !assets.waiting_for_dependencies.is_empty() {
+            let mut loaded = Vec::new();
+            assets.waiting_for_dependencies.retain(|handle_id| {
+                match self.get_recursive_dependency_load_state(*handle_id) {
+                    LoadState::Loading => true,
+                    LoadState::Loaded => {
+                        loaded.push(*handle_id);
+                        false
+                    }
+                    _ => false,
+                }
+            });
+            for handle_id in loaded {
+                assets.events.send(AssetEvent::LoadedWithDependencies {
+                    handle: Handle::weak(handle_id),
+                });
+            }
+        }
+    }
+}
+
+fn get_asset_path_id(asset_path: &AssetPath, settings: Option<&LoaderSettings>) -> AssetPathId {
+    match settings {
+        Some(settings) => AssetPathId::new(
+            SourcePathId::with_settings(asset_path.source(), asset_path.path(), &settings.key),
+            LabelId::from(asset_path.label()),
+        ),
+        None => asset_path.get_id(),
     }
 }
 
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = get_hasher();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
 fn free_unused_assets_system_impl(asset_server: &AssetServer) {
     asset_server.free_unused_assets();
     asset_server.mark_unused_assets();
@@ -552,13 +1145,22 @@ pub fn free_unused_assets_system(asset_server: Res<AssetServer>) {
     free_unused_assets_system_impl(&asset_server);
 }
 
+pub fn asset_load_failed_event_system(
+    asset_server: Res<AssetServer>,
+    mut events: EventWriter<AssetLoadFailedEvent>,
+) {
+    asset_server.send_load_failed_events(&mut events);
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{loader::LoadedAsset, update_asset_storage_system};
+    use crate::{loader::LoadedAsset, update_asset_storage_system, AssetFile};
+    use bevy_app::Events;
     use bevy_ecs::prelude::*;
     use bevy_reflect::TypeUuid;
     use bevy_utils::BoxedFuture;
+    use futures_lite::AsyncReadExt;
 
     #[derive(Debug, TypeUuid)]
     #[uuid = "a5189b72-0572-4290-a2e0-96f73a491c44"]
@@ -566,6 +1168,8 @@ mod test {
 
     struct FakePngLoader;
     impl AssetLoader for FakePngLoader {
+        type Settings = ();
+
         fn load<'a>(
             &'a self,
             _: &'a [u8],
@@ -582,6 +1186,8 @@ mod test {
 
     struct FailingLoader;
     impl AssetLoader for FailingLoader {
+        type Settings = ();
+
         fn load<'a>(
             &'a self,
             _: &'a [u8],
@@ -595,8 +1201,190 @@ mod test {
         }
     }
 
+    #[derive(Debug, TypeUuid)]
+    #[uuid = "0d3a9b47-54f4-4cb8-9f0e-3a1c9e3f6c0b"]
+    struct SizedAsset(u32);
+
+    #[derive(Default, Serialize, serde::Deserialize)]
+    struct SizeSettings {
+        size: u32,
+    }
+
+    struct SizedLoader;
+    impl AssetLoader for SizedLoader {
+        type Settings = SizeSettings;
+
+        fn load<'a>(
+            &'a self,
+            _: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            let size = ctx.settings::<SizeSettings>().size;
+            ctx.set_default_asset(LoadedAsset::new(SizedAsset(size)));
+            Box::pin(async move { Ok(()) })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["sized"]
+        }
+    }
+
+    #[derive(Debug, TypeUuid)]
+    #[uuid = "6e1c4d64-8f0a-4a3b-b2a1-6d0c5b8e1f2a"]
+    struct TextAsset(String);
+
+    struct TextLoader;
+    impl AssetLoader for TextLoader {
+        type Settings = ();
+
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                let text = String::from_utf8(bytes.to_vec())?;
+                ctx.set_default_asset(LoadedAsset::new(TextAsset(text)));
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+    }
+
+    /// Loads a [`TextAsset`] depending on the paths listed in the file
+    struct ListLoader;
+    impl AssetLoader for ListLoader {
+        type Settings = ();
+
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                let text = String::from_utf8(bytes.to_vec())?;
+                let dependencies = text
+                    .lines()
+                    .map(|line| AssetPath::from(line).to_owned())
+                    .collect();
+                ctx.set_default_asset(
+                    LoadedAsset::new(TextAsset(text)).with_dependencies(dependencies),
+                );
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["list"]
+        }
+    }
+
+    /// Loads a [`TextAsset`] concatenating the files listed in the file
+    struct ConcatLoader;
+    impl AssetLoader for ConcatLoader {
+        type Settings = ();
+
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                let mut text = String::new();
+                for path in std::str::from_utf8(bytes)?.lines() {
+                    text.push_str(std::str::from_utf8(&ctx.read_asset_bytes(path).await?)?);
+                }
+                ctx.set_default_asset(LoadedAsset::new(TextAsset(text)));
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["concat"]
+        }
+    }
+
+    /// Streams the first four bytes of `.header` files
+    struct HeaderLoader;
+    impl AssetLoader for HeaderLoader {
+        type Settings = ();
+
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                assert!(bytes.is_empty());
+                let mut reader = ctx.take_reader().unwrap();
+                let mut header = [0; 4];
+                reader.read_exact(&mut header).await?;
+                ctx.set_default_asset(LoadedAsset::new(TextAsset(
+                    std::str::from_utf8(&header)?.to_string(),
+                )));
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["header"]
+        }
+
+        fn streams_asset(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(TypeUuid)]
+    #[uuid = "0b8f3d58-2a6e-4d1c-9a47-5e3c8b1d7f26"]
+    struct StreamedAsset(AssetFile);
+
+    /// Keeps `.chunks` files to read their chunks after they are loaded
+    struct ChunksLoader;
+    impl AssetLoader for ChunksLoader {
+        type Settings = ();
+
+        fn load<'a>(
+            &'a self,
+            _: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                let file = ctx.asset_file(ctx.path());
+                ctx.set_default_asset(LoadedAsset::new(StreamedAsset(file)));
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["chunks"]
+        }
+
+        fn streams_asset(&self) -> bool {
+            true
+        }
+    }
+
+    struct TextSaver;
+    impl AssetSaver for TextSaver {
+        type Asset = TextAsset;
+
+        fn save(&self, asset: &TextAsset, _path: &Path) -> Result<Vec<u8>, anyhow::Error> {
+            Ok(asset.0.clone().into_bytes())
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+    }
+
     struct FakeMultipleDotLoader;
     impl AssetLoader for FakeMultipleDotLoader {
+        type Settings = ();
+
         fn load<'a>(
             &'a self,
             _: &'a [u8],
@@ -613,18 +1401,7 @@ mod test {
     fn setup(asset_path: impl AsRef<Path>) -> AssetServer {
         use crate::FileAssetIo;
 
-        AssetServer {
-            server: Arc::new(AssetServerInternal {
-                loaders: Default::default(),
-                extension_to_loader_index: Default::default(),
-                asset_sources: Default::default(),
-                asset_ref_counter: Default::default(),
-                handle_to_path: Default::default(),
-                asset_lifecycles: Default::default(),
-                task_pool: Default::default(),
-                asset_io: Box::new(FileAssetIo::new(asset_path)),
-            }),
-        }
+        AssetServer::with_boxed_io(Box::new(FileAssetIo::new(asset_path)), Default::default())
     }
 
     #[test]
@@ -719,7 +1496,7 @@ mod test {
         let path: AssetPath = "file.not-a-real-extension".into();
         let handle = asset_server.get_handle_untyped(path.get_id());
 
-        let err = futures_lite::future::block_on(asset_server.load_async(path.clone(), true))
+        let err = futures_lite::future::block_on(asset_server.load_async(path.clone(), None, true))
             .unwrap_err();
         assert!(match err {
             AssetServerError::MissingAssetLoader { extensions } => {
@@ -739,7 +1516,7 @@ mod test {
         let path: AssetPath = "an/invalid/path.png".into();
         let handle = asset_server.get_handle_untyped(path.get_id());
 
-        let err = futures_lite::future::block_on(asset_server.load_async(path.clone(), true))
+        let err = futures_lite::future::block_on(asset_server.load_async(path.clone(), None, true))
             .unwrap_err();
         assert!(matches!(err, AssetServerError::AssetIoError(_)));
 
@@ -755,7 +1532,7 @@ mod test {
         let path: AssetPath = "fake.fail".into();
         let handle = asset_server.get_handle_untyped(path.get_id());
 
-        let err = futures_lite::future::block_on(asset_server.load_async(path.clone(), true))
+        let err = futures_lite::future::block_on(asset_server.load_async(path.clone(), None, true))
             .unwrap_err();
         assert!(matches!(err, AssetServerError::AssetLoaderError(_)));
 
@@ -787,8 +1564,9 @@ mod test {
 
         fn load_asset(path: AssetPath, world: &World) -> HandleUntyped {
             let asset_server = world.get_resource::<AssetServer>().unwrap();
-            let id = futures_lite::future::block_on(asset_server.load_async(path.clone(), true))
-                .unwrap();
+            let id =
+                futures_lite::future::block_on(asset_server.load_async(path.clone(), None, true))
+                    .unwrap();
             asset_server.get_handle_untyped(id)
         }
 
@@ -873,4 +1651,429 @@ mod test {
         let invalid_path = AssetPath::new("some/path.ext".into(), None);
         assert!(server.get_handle_path(invalid_path).is_none());
     }
+
+    #[test]
+    fn test_loader_settings() {
+        let dir = create_dir_and_file("fake.sized");
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(SizedLoader);
+        let assets = asset_server.register_asset_type::<SizedAsset>();
+
+        let path: AssetPath = "fake.sized".into();
+        let load = |settings: Option<LoaderSettings>| {
+            futures_lite::future::block_on(asset_server.load_async(path.clone(), settings, true))
+        };
+        let default = load(None).unwrap();
+        let small = load(Some(LoaderSettings::new(SizeSettings { size: 4 }))).unwrap();
+        let small_again = load(Some(LoaderSettings::new(SizeSettings { size: 4 }))).unwrap();
+        let large = load(Some(LoaderSettings::new(SizeSettings { size: 8 }))).unwrap();
+        assert_eq!(default, path.get_id());
+        assert_eq!(small, small_again);
+        assert_ne!(small, default);
+        assert_ne!(small, large);
+
+        let err = load(Some(LoaderSettings::new(42u8))).unwrap_err();
+        assert!(matches!(
+            err,
+            AssetServerError::IncorrectSettingsType { .. }
+        ));
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server);
+        let mut update_asset_storage_system = update_asset_storage_system::<SizedAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+        update_asset_storage_system.run((), &mut world);
+
+        let assets = world.get_resource::<Assets<SizedAsset>>().unwrap();
+        assert_eq!(assets.get(default).unwrap().0, 0);
+        assert_eq!(assets.get(small).unwrap().0, 4);
+        assert_eq!(assets.get(large).unwrap().0, 8);
+    }
+
+    #[test]
+    fn test_save() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(TextLoader);
+        asset_server.add_saver(TextSaver);
+        let assets = asset_server.register_asset_type::<TextAsset>();
+
+        let path: AssetPath = "a.txt".into();
+        let id = futures_lite::future::block_on(asset_server.load_async(path.clone(), None, true))
+            .unwrap();
+        let handle: Handle<TextAsset> = asset_server.get_handle(id);
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server.clone());
+        let mut update_asset_storage_system = update_asset_storage_system::<TextAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+        update_asset_storage_system.run((), &mut world);
+        let mut save_assets_system = crate::save_assets_system::<TextAsset>.system();
+        save_assets_system.initialize(&mut world);
+
+        world
+            .get_resource_mut::<Assets<TextAsset>>()
+            .unwrap()
+            .get_mut(&handle)
+            .unwrap()
+            .0 = "changed".to_string();
+        asset_server.save(&handle, "a.txt");
+        asset_server.save(&handle, "copy/b.txt");
+        asset_server.save(&handle, "c.unknown");
+        save_assets_system.run((), &mut world);
+
+        let read = |file: &str| std::fs::read_to_string(dir.path().join(file)).ok();
+        for _ in 0..100 {
+            if read("a.txt").as_deref() == Some("changed") && read("copy/b.txt").is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(read("a.txt").as_deref(), Some("changed"));
+        assert_eq!(read("copy/b.txt").as_deref(), Some("changed"));
+        assert!(read("c.unknown").is_none());
+        assert!(matches!(
+            asset_server.get_path_asset_saver(TextAsset::TYPE_UUID, Path::new("c.unknown")),
+            Err(AssetServerError::MissingAssetSaver { .. })
+        ));
+
+        // reloading the saved file is skipped
+        let version = |asset_server: &AssetServer| {
+            asset_server.server.asset_sources.read()[&id.source_path_id()].version
+        };
+        let saved_version = version(&asset_server);
+        futures_lite::future::block_on(asset_server.load_async(path.clone(), None, true)).unwrap();
+        assert_eq!(version(&asset_server), saved_version);
+
+        // until it is changed by something else
+        std::fs::write(dir.path().join("a.txt"), "external").unwrap();
+        futures_lite::future::block_on(asset_server.load_async(path.clone(), None, true)).unwrap();
+        assert_eq!(version(&asset_server), saved_version + 1);
+    }
+
+    #[test]
+    fn test_asset_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "file").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(TextLoader);
+        asset_server
+            .memory_assets()
+            .insert("a.txt", b"memory".to_vec());
+        asset_server.embedded_assets().insert("a.txt", b"embedded");
+        asset_server.add_source("mods", crate::MemoryAssetIo::default());
+        let assets = asset_server.register_asset_type::<TextAsset>();
+
+        let load = |path: &str| {
+            futures_lite::future::block_on(asset_server.load_async(path.into(), None, false))
+        };
+        let file = load("a.txt").unwrap();
+        let memory = load("memory://a.txt").unwrap();
+        let embedded = load("embedded://a.txt").unwrap();
+        assert!(matches!(
+            load("mods://a.txt"),
+            Err(AssetServerError::AssetIoError(AssetIoError::NotFound(_)))
+        ));
+        assert!(matches!(
+            load("missing://a.txt"),
+            Err(AssetServerError::MissingAssetSource(_))
+        ));
+        assert_eq!(
+            asset_server.get_load_state(AssetPathId::from("missing://a.txt")),
+            LoadState::Failed
+        );
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server);
+        let mut update_asset_storage_system = update_asset_storage_system::<TextAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+        update_asset_storage_system.run((), &mut world);
+
+        let assets = world.get_resource::<Assets<TextAsset>>().unwrap();
+        assert_eq!(assets.get(file).unwrap().0, "file");
+        assert_eq!(assets.get(memory).unwrap().0, "memory");
+        assert_eq!(assets.get(embedded).unwrap().0, "embedded");
+    }
+
+    #[test]
+    fn test_recursive_dependency_load_state() {
+        let dir = tempfile::tempdir().unwrap();
+        for (file, content) in [
+            ("root.list", "a.txt\nchild.list"),
+            ("child.list", "b.txt\nroot.list"),
+            ("broken.list", "a.txt\nmissing.txt"),
+            ("a.txt", "a"),
+            ("b.txt", "b"),
+        ] {
+            std::fs::write(dir.path().join(file), content).unwrap();
+        }
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(TextLoader);
+        asset_server.add_loader(ListLoader);
+        let assets = asset_server.register_asset_type::<TextAsset>();
+
+        let load = |path: &str| {
+            futures_lite::future::block_on(asset_server.load_async(path.into(), None, false))
+                .unwrap()
+        };
+        let root = load("root.list");
+        let broken = load("broken.list");
+        assert_eq!(
+            asset_server.get_recursive_dependency_load_state(root),
+            LoadState::Loading
+        );
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server.clone());
+        let mut update_asset_storage_system = update_asset_storage_system::<TextAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+
+        let mut loaded_with_dependencies = Vec::new();
+        for _ in 0..100 {
+            update_asset_storage_system.run((), &mut world);
+            let mut assets = world.get_resource_mut::<Assets<TextAsset>>().unwrap();
+            for event in assets.events.drain() {
+                if let AssetEvent::LoadedWithDependencies { handle } = event {
+                    loaded_with_dependencies.push(handle.id);
+                }
+            }
+            if asset_server.get_recursive_dependency_load_state(root) == LoadState::Loaded
+                && asset_server.get_load_state(AssetPathId::from("missing.txt"))
+                    == LoadState::Failed
+            {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        update_asset_storage_system.run((), &mut world);
+        let mut assets = world.get_resource_mut::<Assets<TextAsset>>().unwrap();
+        for event in assets.events.drain() {
+            if let AssetEvent::LoadedWithDependencies { handle } = event {
+                loaded_with_dependencies.push(handle.id);
+            }
+        }
+
+        assert_eq!(
+            asset_server.get_recursive_dependency_load_state(root),
+            LoadState::Loaded
+        );
+        assert_eq!(asset_server.get_load_state(broken), LoadState::Loaded);
+        assert_eq!(
+            asset_server.get_recursive_dependency_load_state(broken),
+            LoadState::Failed
+        );
+        assert_eq!(
+            asset_server.get_recursive_dependency_load_state(HandleId::from("unknown.list")),
+            LoadState::NotLoaded
+        );
+
+        let count = |id: AssetPathId| {
+            loaded_with_dependencies
+                .iter()
+                .filter(|handle_id| **handle_id == HandleId::from(id))
+                .count()
+        };
+        assert_eq!(count(root), 1);
+        assert_eq!(count(AssetPathId::from("child.list")), 1);
+        assert_eq!(count(AssetPathId::from("a.txt")), 1);
+        assert_eq!(count(broken), 0);
+    }
+
+    #[test]
+    fn test_reload_dependents() {
+        let dir = tempfile::tempdir().unwrap();
+        for (file, content) in [
+            ("root.list", "child.list"),
+            ("child.list", "a.txt"),
+            ("both.concat", "a.txt\nb.txt"),
+            ("a.txt", "a"),
+            ("b.txt", "b"),
+        ] {
+            std::fs::write(dir.path().join(file), content).unwrap();
+        }
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(TextLoader);
+        asset_server.add_loader(ListLoader);
+        asset_server.add_loader(ConcatLoader);
+        let assets = asset_server.register_asset_type::<TextAsset>();
+
+        let load = |path: &str| {
+            futures_lite::future::block_on(asset_server.load_async(path.into(), None, false))
+                .unwrap()
+        };
+        let root = load("root.list");
+        let both = load("both.concat");
+        let b = load("b.txt");
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server.clone());
+        let mut update_asset_storage_system = update_asset_storage_system::<TextAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+        for _ in 0..100 {
+            update_asset_storage_system.run((), &mut world);
+            if asset_server.get_recursive_dependency_load_state(root) == LoadState::Loaded {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(
+            asset_server.get_recursive_dependency_load_state(root),
+            LoadState::Loaded
+        );
+
+        let version = |id: AssetPathId| {
+            asset_server.server.asset_sources.read()[&id.source_path_id()].version
+        };
+        let ids = [
+            root,
+            AssetPathId::from("child.list"),
+            AssetPathId::from("a.txt"),
+            both,
+        ];
+        let versions: Vec<_> = ids.iter().map(|id| version(*id)).collect();
+        let b_version = version(b);
+
+        // a.txt is a dependency of child.list, itself a dependency of root.list, and is read
+        // while loading both.concat
+        asset_server.reload_path(Path::new("a.txt"));
+        let reloaded = || {
+            ids.iter()
+                .zip(versions.iter())
+                .all(|(id, old_version)| version(*id) > *old_version)
+        };
+        for _ in 0..100 {
+            if reloaded() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(reloaded());
+        assert_eq!(version(b), b_version);
+    }
+
+    #[test]
+    fn test_streaming_loader() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.header"), "HEADlarge body").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(HeaderLoader);
+        let assets = asset_server.register_asset_type::<TextAsset>();
+
+        let handle: Handle<TextAsset> = asset_server.load("a.header");
+        let missing: Handle<TextAsset> = asset_server.load("missing.header");
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server.clone());
+        let mut update_asset_storage_system = update_asset_storage_system::<TextAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+        for _ in 0..100 {
+            update_asset_storage_system.run((), &mut world);
+            if asset_server.get_load_state(&handle) == LoadState::Loaded
+                && asset_server.get_load_state(&missing) == LoadState::Failed
+            {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let assets = world.get_resource::<Assets<TextAsset>>().unwrap();
+        assert_eq!(assets.get(&handle).unwrap().0, "HEAD");
+        assert!(matches!(
+            *asset_server.get_load_error(&missing).unwrap(),
+            AssetServerError::AssetIoError(AssetIoError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn read_asset_file_after_load() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.chunks"), "HEADlarge body").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(ChunksLoader);
+        let assets = asset_server.register_asset_type::<StreamedAsset>();
+
+        let handle: Handle<StreamedAsset> = asset_server.load("a.chunks");
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server.clone());
+        let mut update_asset_storage_system = update_asset_storage_system::<StreamedAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+        for _ in 0..100 {
+            update_asset_storage_system.run((), &mut world);
+            if asset_server.get_load_state(&handle) == LoadState::Loaded {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let assets = world.get_resource::<Assets<StreamedAsset>>().unwrap();
+        let file = &assets.get(&handle).unwrap().0;
+        assert_eq!(file.path(), Path::new("a.chunks"));
+        let chunk = futures_lite::future::block_on(file.read_range(4..9)).unwrap();
+        assert_eq!(chunk, b"large");
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.unknown"), "").unwrap();
+        std::fs::write(dir.path().join("bad.concat"), "missing.txt").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(TextLoader);
+        asset_server.add_loader(ConcatLoader);
+        asset_server.register_asset_type::<TextAsset>();
+
+        let missing_file = asset_server.load_untyped("missing.txt");
+        let missing_loader = asset_server.load_untyped("a.unknown");
+        let failing_loader = asset_server.load_untyped("bad.concat");
+
+        let mut world = World::new();
+        world.insert_resource(Events::<AssetLoadFailedEvent>::default());
+        world.insert_resource(asset_server.clone());
+        let mut failed_event_system = asset_load_failed_event_system.system();
+        failed_event_system.initialize(&mut world);
+
+        let mut events = Vec::new();
+        for _ in 0..100 {
+            failed_event_system.run((), &mut world);
+            events.extend(
+                world
+                    .get_resource_mut::<Events<AssetLoadFailedEvent>>()
+                    .unwrap()
+                    .drain(),
+            );
+            if events.len() == 3 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(events.len(), 3);
+        for handle in [&missing_file, &missing_loader, &failing_loader] {
+            let event = events.iter().find(|event| event.handle.id == handle.id);
+            assert!(Arc::ptr_eq(
+                &event.unwrap().error,
+                &asset_server.get_load_error(handle).unwrap()
+            ));
+            assert_eq!(asset_server.get_load_state(handle), LoadState::Failed);
+        }
+
+        assert!(matches!(
+            *asset_server.get_load_error(&missing_file).unwrap(),
+            AssetServerError::AssetIoError(AssetIoError::NotFound(_))
+        ));
+        assert!(matches!(
+            *asset_server.get_load_error(&missing_loader).unwrap(),
+            AssetServerError::MissingAssetLoader { .. }
+        ));
+        assert!(matches!(
+            *asset_server.get_load_error(&failing_loader).unwrap(),
+            AssetServerError::AssetLoaderError(_)
+        ));
+    }
 }
@@ -1,18 +1,32 @@
 use crate::{
-    update_asset_storage_system, Asset, AssetLoader, AssetServer, AssetStage, Handle, HandleId,
-    RefChange,
+    save_assets_system, update_asset_storage_system, Asset, AssetIo, AssetLoader,
+    AssetProcessingMode, AssetProcessor, AssetSaver, AssetServer, AssetServerSettings, AssetStage,
+    Handle, HandleId, Processor, RefChange,
 };
 use bevy_app::{App, EventWriter, Events};
 use bevy_ecs::{system::ResMut, world::FromWorld};
-use bevy_utils::HashMap;
+use bevy_log::warn;
+use bevy_utils::{HashMap, HashSet};
 use crossbeam_channel::Sender;
 use std::fmt::Debug;
 
 /// Events that happen on assets of type `T`
 pub enum AssetEvent<T: Asset> {
-    Created { handle: Handle<T> },
-    Modified { handle: Handle<T> },
-    Removed { handle: Handle<T> },
+    Created {
+        handle: Handle<T>,
+    },
+    Modified {
+        handle: Handle<T>,
+    },
+    Removed {
+        handle: Handle<T>,
+    },
+    /// Sent after an asset is loaded by the [`AssetServer`], once all its dependencies, and
+    /// their own dependencies, are loaded too.
+    /// See [`AssetServer::get_recursive_dependency_load_state`].
+    LoadedWithDependencies {
+        handle: Handle<T>,
+    },
 }
 
 impl<T: Asset> Debug for AssetEvent<T> {
@@ -39,6 +53,13 @@ impl<T: Asset> Debug for AssetEvent<T> {
                 ))
                 .field("handle", &handle.id)
                 .finish(),
+            AssetEvent::LoadedWithDependencies { handle } => f
+                .debug_struct(&format!(
+                    "AssetEvent<{}>::LoadedWithDependencies",
+                    std::any::type_name::<T>()
+                ))
+                .field("handle", &handle.id)
+                .finish(),
         }
     }
 }
@@ -47,8 +68,11 @@ impl<T: Asset> Debug for AssetEvent<T> {
 #[derive(Debug)]
 pub struct Assets<T: Asset> {
     assets: HashMap<HandleId, T>,
-    events: Events<AssetEvent<T>>,
+    pub(crate) events: Events<AssetEvent<T>>,
     pub(crate) ref_change_sender: Sender<RefChange>,
+    /// Loaded assets waiting for their dependencies to send
+    /// [`AssetEvent::LoadedWithDependencies`]
+    pub(crate) waiting_for_dependencies: HashSet<HandleId>,
 }
 
 impl<T: Asset> Assets<T> {
@@ -57,6 +81,7 @@ impl<T: Asset> Assets<T> {
             assets: HashMap::default(),
             events: Events::default(),
             ref_change_sender,
+            waiting_for_dependencies: HashSet::default(),
         }
     }
 
@@ -179,6 +204,14 @@ impl<T: Asset> Assets<T> {
         self.assets.shrink_to_fit()
     }
 
+    /// Returns the number of bytes allocated by the inner asset map, including unused capacity.
+    ///
+    /// Heap allocations owned by the assets themselves are not taken into account.
+    pub fn allocated_bytes(&self) -> usize {
+        self.assets.capacity() * std::mem::size_of::<(HandleId, T)>()
+            + self.events.allocated_bytes()
+    }
+
     pub fn asset_event_system(
         mut events: EventWriter<AssetEvent<T>>,
         mut assets: ResMut<Assets<T>>,
@@ -210,6 +243,19 @@ pub trait AddAsset {
     fn add_asset_loader<T>(&mut self, loader: T) -> &mut Self
     where
         T: AssetLoader;
+    fn add_asset_processor<T>(&mut self, processor: T) -> &mut Self
+    where
+        T: Processor;
+    fn init_asset_saver<T>(&mut self) -> &mut Self
+    where
+        T: AssetSaver + FromWorld;
+    fn add_asset_saver<T>(&mut self, saver: T) -> &mut Self
+    where
+        T: AssetSaver;
+    fn add_asset_source<T>(&mut self, name: impl Into<String>, asset_io: T) -> &mut Self
+    where
+        T: AssetIo;
+    fn add_embedded_asset(&mut self, path: &str, bytes: &'static [u8]) -> &mut Self;
 }
 
 impl AddAsset for App {
@@ -225,6 +271,7 @@ impl AddAsset for App {
         self.insert_resource(assets)
             .add_system_to_stage(AssetStage::AssetEvents, Assets::<T>::asset_event_system)
             .add_system_to_stage(AssetStage::LoadAssets, update_asset_storage_system::<T>)
+            .add_system_to_stage(AssetStage::AssetEvents, save_assets_system::<T>)
             .register_type::<Handle<T>>()
             .add_event::<AssetEvent<T>>()
     }
@@ -247,4 +294,72 @@ impl AddAsset for App {
             .add_loader(loader);
         self
     }
+
+    /// Registers an asset [`Processor`]. It is only used when the [`AssetProcessor`] resource
+    /// exists, see [`AssetProcessingMode::ProcessOnStartup`]. In the other modes, processors
+    /// are ignored.
+    fn add_asset_processor<T>(&mut self, processor: T) -> &mut Self
+    where
+        T: Processor,
+    {
+        if let Some(mut asset_processor) = self.world.get_resource_mut::<AssetProcessor>() {
+            asset_processor.add_processor(processor);
+            return self;
+        }
+        self.world
+            .get_resource::<AssetServer>()
+            .expect("AssetServer does not exist. Consider adding it as a resource.");
+        let processing_mode = self
+            .world
+            .get_resource::<AssetServerSettings>()
+            .map(|settings| settings.processing_mode);
+        if processing_mode == Some(AssetProcessingMode::ProcessOnStartup) {
+            warn!(
+                "Asset processor {} ignored: assets can't be processed on this platform",
+                processor.name()
+            );
+        }
+        self
+    }
+
+    fn init_asset_saver<T>(&mut self) -> &mut Self
+    where
+        T: AssetSaver + FromWorld,
+    {
+        let result = T::from_world(&mut self.world);
+        self.add_asset_saver(result)
+    }
+
+    fn add_asset_saver<T>(&mut self, saver: T) -> &mut Self
+    where
+        T: AssetSaver,
+    {
+        self.world
+            .get_resource_mut::<AssetServer>()
+            .expect("AssetServer does not exist. Consider adding it as a resource.")
+            .add_saver(saver);
+        self
+    }
+
+    fn add_asset_source<T>(&mut self, name: impl Into<String>, asset_io: T) -> &mut Self
+    where
+        T: AssetIo,
+    {
+        self.world
+            .get_resource::<AssetServer>()
+            .expect("AssetServer does not exist. Consider adding it as a resource.")
+            .add_source(name, asset_io);
+        self
+    }
+
+    /// Adds a file to the [`EmbeddedAssetIo`](crate::EmbeddedAssetIo) of the asset server.
+    /// See [`embedded_asset!`](crate::embedded_asset).
+    fn add_embedded_asset(&mut self, path: &str, bytes: &'static [u8]) -> &mut Self {
+        self.world
+            .get_resource::<AssetServer>()
+            .expect("AssetServer does not exist. Consider adding it as a resource.")
+            .embedded_assets()
+            .insert(path, bytes);
+        self
+    }
 }
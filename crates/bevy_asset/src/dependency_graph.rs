@@ -0,0 +1,117 @@
+use crate::{AssetPath, SourcePathId};
+use bevy_utils::{HashMap, HashSet};
+
+/// Tracks the files each loaded asset source depends on, to reload the assets depending on a
+/// file when it changes.
+///
+/// Files are identified by an [`AssetPath`] without label. The dependencies of an asset source
+/// are the files of the dependencies of its assets, and the files read while loading it.
+#[derive(Default)]
+pub(crate) struct DependencyGraph {
+    /// The file of each asset source
+    files: HashMap<SourcePathId, AssetPath<'static>>,
+    dependencies: HashMap<SourcePathId, HashSet<AssetPath<'static>>>,
+    dependents: HashMap<AssetPath<'static>, HashSet<SourcePathId>>,
+}
+
+impl DependencyGraph {
+    /// Replaces the dependencies of the asset source loaded from `file`.
+    pub(crate) fn set_dependencies(
+        &mut self,
+        source_path_id: SourcePathId,
+        file: AssetPath<'static>,
+        dependencies: HashSet<AssetPath<'static>>,
+    ) {
+        self.remove(source_path_id);
+        for dependency in dependencies.iter() {
+            self.dependents
+                .entry(dependency.clone())
+                .or_default()
+                .insert(source_path_id);
+        }
+        self.files.insert(source_path_id, file);
+        self.dependencies.insert(source_path_id, dependencies);
+    }
+
+    pub(crate) fn remove(&mut self, source_path_id: SourcePathId) {
+        self.files.remove(&source_path_id);
+        for dependency in self
+            .dependencies
+            .remove(&source_path_id)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(dependents) = self.dependents.get_mut(&dependency) {
+                dependents.remove(&source_path_id);
+                if dependents.is_empty() {
+                    self.dependents.remove(&dependency);
+                }
+            }
+        }
+    }
+
+    /// Returns the asset sources depending on `file`, directly or through other asset sources.
+    pub(crate) fn recursive_dependents(&self, file: &AssetPath) -> Vec<SourcePathId> {
+        let mut visited = HashSet::default();
+        let mut dependents = Vec::new();
+        let mut stack = vec![file];
+        while let Some(file) = stack.pop() {
+            for dependent in self.dependents.get(file).into_iter().flatten() {
+                if visited.insert(*dependent) {
+                    dependents.push(*dependent);
+                    stack.extend(self.files.get(dependent));
+                }
+            }
+        }
+        dependents
+    }
+}
+
+/// Returns the path of the file of `asset_path`, without its label.
+pub(crate) fn file_path(asset_path: &AssetPath) -> AssetPath<'static> {
+    let file = AssetPath::new(asset_path.path().to_owned(), None);
+    match asset_path.source() {
+        Some(source) => file.with_source(source.to_string()),
+        None => file,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recursive_dependents() {
+        let file = |path: &str| file_path(&AssetPath::from(path));
+        let id = |path: &str| file(path).get_id().source_path_id();
+        let mut graph = DependencyGraph::default();
+        let mut set = |path: &str, dependencies: &[&str]| {
+            graph.set_dependencies(
+                id(path),
+                file(path),
+                dependencies.iter().map(|path| file(path)).collect(),
+            )
+        };
+        set("scene.scn", &["model.gltf"]);
+        set("model.gltf", &["model.bin", "texture.png"]);
+        set("other.gltf", &["texture.png"]);
+        set("a.list", &["b.list"]);
+        set("b.list", &["a.list"]);
+
+        let mut dependents = graph.recursive_dependents(&file("texture.png"));
+        dependents.sort();
+        let mut expected = vec![id("scene.scn"), id("model.gltf"), id("other.gltf")];
+        expected.sort();
+        assert_eq!(dependents, expected);
+        assert_eq!(graph.recursive_dependents(&file("a.list")).len(), 2);
+
+        // reloading an asset replaces its dependencies
+        graph.set_dependencies(id("model.gltf"), file("model.gltf"), HashSet::default());
+        assert_eq!(
+            graph.recursive_dependents(&file("texture.png")),
+            vec![id("other.gltf")]
+        );
+        graph.remove(id("other.gltf"));
+        assert!(graph.recursive_dependents(&file("texture.png")).is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{Asset, Assets};
+use bevy_app::prelude::*;
+use bevy_diagnostic::{
+    memory_diagnostic_name, Diagnostic, DiagnosticId, Diagnostics, MemoryGrowthThreshold,
+    DEFAULT_MEMORY_GROWTH_STEP,
+};
+use bevy_ecs::system::{Res, ResMut};
+
+/// Adds "asset bytes" diagnostic to an App, tracking the memory used by [`Assets<T>`]
+///
+/// By default only the asset collection itself is measured. Use
+/// [`with_size`](AssetMemoryDiagnosticsPlugin::with_size) to also count the heap memory owned
+/// by each asset, like the pixel data of a texture.
+pub struct AssetMemoryDiagnosticsPlugin<T: Asset> {
+    /// If set, a warning is logged every time the assets grow past a multiple of this many bytes
+    pub growth_log_step: Option<usize>,
+    /// Returns the number of heap bytes owned by an asset
+    pub size: Option<fn(&T) -> usize>,
+}
+
+impl<T: Asset> Default for AssetMemoryDiagnosticsPlugin<T> {
+    fn default() -> Self {
+        Self {
+            growth_log_step: Some(DEFAULT_MEMORY_GROWTH_STEP),
+            size: None,
+        }
+    }
+}
+
+/// State used by the [AssetMemoryDiagnosticsPlugin]
+struct AssetMemoryDiagnosticsState<T: Asset> {
+    size: Option<fn(&T) -> usize>,
+    threshold: Option<MemoryGrowthThreshold>,
+}
+
+impl<T: Asset> Plugin for AssetMemoryDiagnosticsPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AssetMemoryDiagnosticsState::<T> {
+            size: self.size,
+            threshold: self.growth_log_step.map(MemoryGrowthThreshold::new),
+        })
+        .add_startup_system(Self::setup_system)
+        .add_system(Self::diagnostic_system);
+    }
+}
+
+impl<T: Asset> AssetMemoryDiagnosticsPlugin<T> {
+    pub fn with_size(size: fn(&T) -> usize) -> Self {
+        Self {
+            size: Some(size),
+            ..Default::default()
+        }
+    }
+
+    pub fn diagnostic_id() -> DiagnosticId {
+        // distinct from the id used by the asset count diagnostic, which is the type uuid itself
+        DiagnosticId::from_u128(T::TYPE_UUID.as_u128() ^ 0x6173_7365_745f_6279_7465_7300_0000_0000)
+    }
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(
+            Diagnostic::new(
+                Self::diagnostic_id(),
+                memory_diagnostic_name("asset_bytes", std::any::type_name::<T>()),
+                20,
+            )
+            .with_suffix("B"),
+        );
+    }
+
+    fn diagnostic_system(
+        mut diagnostics: ResMut<Diagnostics>,
+        mut state: ResMut<AssetMemoryDiagnosticsState<T>>,
+        assets: Res<Assets<T>>,
+    ) {
+        let mut bytes = assets.allocated_bytes();
+        if let Some(size) = state.size {
+            bytes += assets.iter().map(|(_, asset)| size(asset)).sum::<usize>();
+        }
+        if let Some(threshold) = &mut state.threshold {
+            threshold.update(
+                format_args!("Assets<{}>", std::any::type_name::<T>()),
+                bytes,
+            );
+        }
+        diagnostics.add_measurement(Self::diagnostic_id(), bytes as f64);
+    }
+}
@@ -1,2 +1,4 @@
 mod asset_count_diagnostics_plugin;
+mod asset_memory_diagnostics_plugin;
 pub use asset_count_diagnostics_plugin::AssetCountDiagnosticsPlugin;
+pub use asset_memory_diagnostics_plugin::AssetMemoryDiagnosticsPlugin;
@@ -1,12 +1,18 @@
+use bevy_utils::HashMap;
 use crossbeam_channel::Receiver;
 use notify::{Event, RecommendedWatcher, RecursiveMode, Result, Watcher};
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 /// Watches for changes to assets on the filesystem. This is used by the `AssetServer` to reload
 /// them
 pub struct FilesystemWatcher {
     pub watcher: RecommendedWatcher,
     pub receiver: Receiver<Result<Event>>,
+    /// Changed paths waiting to be reloaded, with the time of their last change
+    pub(crate) pending_changes: HashMap<PathBuf, Instant>,
 }
 
 impl Default for FilesystemWatcher {
@@ -16,7 +22,11 @@ impl Default for FilesystemWatcher {
             sender.send(res).expect("Watch event send failure.");
         })
         .expect("Failed to create filesystem watcher.");
-        FilesystemWatcher { watcher, receiver }
+        FilesystemWatcher {
+            watcher,
+            receiver,
+            pending_changes: Default::default(),
+        }
     }
 }
 
@@ -10,7 +10,7 @@ use crate::{
     Asset, Assets,
 };
 use bevy_ecs::{component::Component, reflect::ReflectComponent};
-use bevy_reflect::{FromReflect, Reflect, ReflectDeserialize};
+use bevy_reflect::{FromReflect, FromType, Reflect, ReflectDeserialize};
 use bevy_utils::Uuid;
 use crossbeam_channel::{Receiver, Sender};
 use serde::{Deserialize, Serialize};
@@ -70,7 +70,7 @@ impl HandleId {
 /// Handles contain a unique id that corresponds to a specific asset in the [Assets](crate::Assets)
 /// collection.
 #[derive(Component, Reflect, FromReflect)]
-#[reflect(Component)]
+#[reflect(Component, Handle)]
 pub struct Handle<T>
 where
     T: Asset,
@@ -165,6 +165,35 @@ impl<T: Asset> Handle<T> {
     }
 }
 
+/// Type data to read the id of a reflected [`Handle`] and whether it is strong, registered for
+/// the handles of the assets added with [`AddAsset::add_asset`](crate::AddAsset::add_asset)
+#[derive(Clone)]
+pub struct ReflectHandle {
+    strong_handle_id: fn(&dyn Reflect) -> Option<HandleId>,
+}
+
+impl ReflectHandle {
+    /// Returns the id of `handle` if it is a strong handle
+    pub fn strong_handle_id(&self, handle: &dyn Reflect) -> Option<HandleId> {
+        (self.strong_handle_id)(handle)
+    }
+}
+
+impl<T: Asset> FromType<Handle<T>> for ReflectHandle {
+    fn from_type() -> Self {
+        ReflectHandle {
+            strong_handle_id: |handle| {
+                let handle = handle.any().downcast_ref::<Handle<T>>()?;
+                if handle.is_strong() {
+                    Some(handle.id)
+                } else {
+                    None
+                }
+            },
+        }
+    }
+}
+
 impl<T: Asset> Drop for Handle<T> {
     fn drop(&mut self) {
         match self.handle_type {
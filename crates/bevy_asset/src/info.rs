@@ -1,7 +1,7 @@
-use crate::{path::AssetPath, LabelId};
+use crate::{loader::LoaderSettings, path::AssetPath, AssetServerError, LabelId};
 use bevy_utils::{HashMap, HashSet, Uuid};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SourceMeta {
@@ -19,7 +19,12 @@ pub struct AssetMeta {
 #[derive(Clone, Debug)]
 pub struct SourceInfo {
     pub meta: Option<SourceMeta>,
+    /// The name of the asset source the asset is loaded from, `None` for the default source
+    pub source: Option<String>,
     pub path: PathBuf,
+    pub(crate) loader_settings: Option<LoaderSettings>,
+    /// The error of the last load, if it failed
+    pub error: Option<Arc<AssetServerError>>,
     pub asset_types: HashMap<LabelId, Uuid>,
     pub load_state: LoadState,
     pub committed_assets: HashSet<LabelId>,
@@ -0,0 +1,547 @@
+use crate::{AssetIo, AssetIoError, Reader};
+use anyhow::Result;
+use bevy_utils::{BoxedFuture, HashMap, HashSet};
+use futures_lite::{AsyncRead, AsyncSeek};
+use parking_lot::Mutex;
+use std::{
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use thiserror::Error;
+
+const ARCHIVE_MAGIC: &[u8; 8] = b"BEVYPAK1";
+
+/// How a file is stored in an asset archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveCompression {
+    /// Files are stored as is
+    None,
+    /// Files are compressed with deflate, unless it doesn't make them smaller
+    Deflate,
+}
+
+impl ArchiveCompression {
+    fn to_byte(self) -> u8 {
+        match self {
+            ArchiveCompression::None => 0,
+            ArchiveCompression::Deflate => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(ArchiveCompression::None),
+            1 => Some(ArchiveCompression::Deflate),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that occur while reading or writing asset archives
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("encountered an io error while accessing an asset archive: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid asset archive: {0}")]
+    InvalidArchive(&'static str),
+    #[error("path {0:?} can't be stored in an asset archive")]
+    InvalidPath(PathBuf),
+    #[error("failed to decompress {0:?}")]
+    Decompression(PathBuf),
+}
+
+/// The location of a file in an asset archive
+#[derive(Debug, Clone)]
+struct ArchiveEntry {
+    offset: u64,
+    size: u64,
+    compression: ArchiveCompression,
+}
+
+/// Returns the path used to store `path` in archives: its components separated by `/`.
+fn archive_path(path: &Path) -> Option<String> {
+    let mut archive_path = String::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => {
+                if !archive_path.is_empty() {
+                    archive_path.push('/');
+                }
+                archive_path.push_str(name.to_str()?);
+            }
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(archive_path)
+}
+
+/// Builds an asset archive read by [`ArchiveAssetIo`].
+///
+/// Archives start with a header, followed by the content of the files, and end with an index
+/// of the files, so they can be written without keeping the files in memory.
+/// ```no_run
+/// # use bevy_asset::{ArchiveCompression, ArchiveWriter};
+/// # use std::fs::File;
+/// let file = File::create("assets.pak").unwrap();
+/// let mut writer = ArchiveWriter::new(file, ArchiveCompression::Deflate).unwrap();
+/// writer.add_folder("assets").unwrap();
+/// writer.finish().unwrap();
+/// ```
+pub struct ArchiveWriter<W: Write + Seek> {
+    writer: W,
+    compression: ArchiveCompression,
+    index: Vec<(String, ArchiveEntry)>,
+    offset: u64,
+}
+
+impl<W: Write + Seek> ArchiveWriter<W> {
+    pub fn new(mut writer: W, compression: ArchiveCompression) -> Result<Self, ArchiveError> {
+        writer.write_all(ARCHIVE_MAGIC)?;
+        // offset of the index, written by `finish`
+        writer.write_all(&0u64.to_le_bytes())?;
+        Ok(ArchiveWriter {
+            writer,
+            compression,
+            index: Vec::new(),
+            offset: ARCHIVE_MAGIC.len() as u64 + 8,
+        })
+    }
+
+    /// Adds a file to the archive. A file added at the same path as a previous one replaces it,
+    /// but the content of the replaced file is still stored in the archive, as files are written
+    /// as they are added.
+    pub fn add_file(&mut self, path: impl AsRef<Path>, bytes: &[u8]) -> Result<(), ArchiveError> {
+        let path = path.as_ref();
+        let archive_path =
+            archive_path(path).ok_or_else(|| ArchiveError::InvalidPath(path.to_owned()))?;
+
+        let compressed = match self.compression {
+            ArchiveCompression::None => None,
+            ArchiveCompression::Deflate => {
+                let compressed = miniz_oxide::deflate::compress_to_vec(bytes, 6);
+                if compressed.len() < bytes.len() {
+                    Some(compressed)
+                } else {
+                    None
+                }
+            }
+        };
+        let (stored, compression) = match &compressed {
+            Some(compressed) => (&compressed[..], ArchiveCompression::Deflate),
+            None => (bytes, ArchiveCompression::None),
+        };
+        self.writer.write_all(stored)?;
+
+        let entry = ArchiveEntry {
+            offset: self.offset,
+            size: stored.len() as u64,
+            compression,
+        };
+        self.offset += entry.size;
+        self.index.retain(|(path, _)| *path != archive_path);
+        self.index.push((archive_path, entry));
+        Ok(())
+    }
+
+    /// Adds all files of `folder` to the archive, with paths relative to `folder`.
+    pub fn add_folder(&mut self, folder: impl AsRef<Path>) -> Result<(), ArchiveError> {
+        self.add_folder_recursive(folder.as_ref(), Path::new(""))
+    }
+
+    fn add_folder_recursive(&mut self, root: &Path, folder: &Path) -> Result<(), ArchiveError> {
+        let mut entries = fs::read_dir(root.join(folder))?.collect::<Result<Vec<_>, _>>()?;
+        // sort entries to build the same archive on every platform
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let path = folder.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                self.add_folder_recursive(root, &path)?;
+            } else {
+                self.add_file(&path, &fs::read(root.join(&path))?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the index of the archive, and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, ArchiveError> {
+        let index_offset = self.offset;
+        self.writer
+            .write_all(&(self.index.len() as u32).to_le_bytes())?;
+        for (path, entry) in self.index.iter() {
+            self.writer.write_all(&(path.len() as u32).to_le_bytes())?;
+            self.writer.write_all(path.as_bytes())?;
+            self.writer.write_all(&entry.offset.to_le_bytes())?;
+            self.writer.write_all(&entry.size.to_le_bytes())?;
+            self.writer.write_all(&[entry.compression.to_byte()])?;
+        }
+        self.writer
+            .seek(SeekFrom::Start(ARCHIVE_MAGIC.len() as u64))?;
+        self.writer.write_all(&index_offset.to_le_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+trait ArchiveSource: Read + Seek + Send + 'static {}
+
+impl<T: Read + Seek + Send + 'static> ArchiveSource for T {}
+
+/// Loads assets from an archive built with an [`ArchiveWriter`].
+///
+/// Archives are read-only. To override some of their assets, for example with the content of a
+/// DLC or a mod, layer them with a [`LayeredAssetIo`](crate::LayeredAssetIo).
+pub struct ArchiveAssetIo {
+    source: Mutex<Box<dyn ArchiveSource>>,
+    entries: HashMap<String, ArchiveEntry>,
+    directories: HashMap<String, Vec<PathBuf>>,
+}
+
+impl ArchiveAssetIo {
+    /// Opens the archive file at `path`. Only the index of the archive is read, files are read
+    /// when they are loaded.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ArchiveError> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Reads an archive from memory, for example on platforms without a filesystem.
+    pub fn from_bytes(bytes: impl Into<Arc<[u8]>>) -> Result<Self, ArchiveError> {
+        Self::from_reader(io::Cursor::new(ArcBytes(bytes.into())))
+    }
+
+    fn from_reader<R: Read + Seek + Send + 'static>(mut reader: R) -> Result<Self, ArchiveError> {
+        let invalid = |_| ArchiveError::InvalidArchive("unexpected end of file");
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic).map_err(invalid)?;
+        if &magic != ARCHIVE_MAGIC {
+            return Err(ArchiveError::InvalidArchive("not an asset archive"));
+        }
+        let index_offset = read_u64(&mut reader).map_err(invalid)?;
+        let len = reader.seek(SeekFrom::End(0))?;
+        if index_offset > len {
+            return Err(ArchiveError::InvalidArchive("index out of bounds"));
+        }
+        reader.seek(SeekFrom::Start(index_offset))?;
+
+        let count = read_u32(&mut reader).map_err(invalid)?;
+        let mut entries = HashMap::default();
+        let mut directories: HashMap<String, HashSet<PathBuf>> = HashMap::default();
+        for _ in 0..count {
+            let path_len = read_u32(&mut reader).map_err(invalid)?;
+            if path_len as u64 > len - index_offset {
+                return Err(ArchiveError::InvalidArchive("unexpected end of file"));
+            }
+            let mut path = vec![0; path_len as usize];
+            reader.read_exact(&mut path).map_err(invalid)?;
+            let path = String::from_utf8(path)
+                .map_err(|_| ArchiveError::InvalidArchive("invalid file path"))?;
+            let offset = read_u64(&mut reader).map_err(invalid)?;
+            let size = read_u64(&mut reader).map_err(invalid)?;
+            let mut compression = [0];
+            reader.read_exact(&mut compression).map_err(invalid)?;
+            let compression = ArchiveCompression::from_byte(compression[0])
+                .ok_or(ArchiveError::InvalidArchive("unknown compression"))?;
+            // files are stored between the header and the index
+            let header_len = ARCHIVE_MAGIC.len() as u64 + 8;
+            let end = offset.checked_add(size);
+            if offset < header_len || end.filter(|end| *end <= index_offset).is_none() {
+                return Err(ArchiveError::InvalidArchive("file out of bounds"));
+            }
+
+            // register the file in all its parent directories
+            let mut child = path.as_str();
+            while let Some(index) = child.rfind('/') {
+                let parent = &child[..index];
+                directories
+                    .entry(parent.to_string())
+                    .or_default()
+                    .insert(PathBuf::from(child));
+                child = parent;
+            }
+            directories
+                .entry(String::new())
+                .or_default()
+                .insert(PathBuf::from(child));
+
+            entries.insert(
+                path,
+                ArchiveEntry {
+                    offset,
+                    size,
+                    compression,
+                },
+            );
+        }
+
+        Ok(ArchiveAssetIo {
+            source: Mutex::new(Box::new(reader)),
+            entries,
+            directories: directories
+                .into_iter()
+                .map(|(path, children)| (path, children.into_iter().collect()))
+                .collect(),
+        })
+    }
+
+    /// Returns the paths of all files in the archive.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.entries.keys().map(Path::new)
+    }
+
+    fn read_entry(&self, path: &Path, entry: &ArchiveEntry) -> Result<Vec<u8>, AssetIoError> {
+        let mut stored = vec![0; entry.size as usize];
+        {
+            let mut source = self.source.lock();
+            source.seek(SeekFrom::Start(entry.offset))?;
+            source.read_exact(&mut stored)?;
+        }
+        match entry.compression {
+            ArchiveCompression::None => Ok(stored),
+            ArchiveCompression::Deflate => miniz_oxide::inflate::decompress_to_vec(&stored)
+                .map_err(|_| {
+                    AssetIoError::Io(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        ArchiveError::Decompression(path.to_owned()),
+                    ))
+                }),
+        }
+    }
+}
+
+impl AssetIo for ArchiveAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            match archive_path(path).and_then(|archive_path| self.entries.get(&archive_path)) {
+                Some(entry) => self.read_entry(path, entry),
+                None => Err(AssetIoError::NotFound(path.to_owned())),
+            }
+        })
+    }
+
+    /// Files stored without compression are read from the archive as they are needed, others
+    /// are decompressed in memory.
+    fn read_path<'a>(
+        &'a self,
+        path: &Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetIoError>> {
+        let path = path.to_owned();
+        Box::pin(async move {
+            let entry = archive_path(&path)
+                .and_then(|archive_path| self.entries.get(&archive_path))
+                .ok_or_else(|| AssetIoError::NotFound(path.clone()))?;
+            match entry.compression {
+                ArchiveCompression::None => Ok(Box::new(ArchiveEntryReader {
+                    archive: self,
+                    offset: entry.offset,
+                    size: entry.size,
+                    position: 0,
+                }) as Box<Reader>),
+                ArchiveCompression::Deflate => Ok(Box::new(futures_lite::io::Cursor::new(
+                    self.read_entry(&path, entry)?,
+                )) as Box<Reader>),
+            }
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        match archive_path(path).and_then(|archive_path| self.directories.get(&archive_path)) {
+            Some(children) => Ok(Box::new(children.clone().into_iter())),
+            None => Err(AssetIoError::NotFound(path.to_owned())),
+        }
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        archive_path(path).map_or(false, |archive_path| {
+            self.directories.contains_key(&archive_path)
+        })
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+/// Reads a file stored without compression in an archive
+struct ArchiveEntryReader<'a> {
+    archive: &'a ArchiveAssetIo,
+    offset: u64,
+    size: u64,
+    position: u64,
+}
+
+impl<'a> AsyncRead for ArchiveEntryReader<'a> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let reader = self.get_mut();
+        let len = (buf.len() as u64).min(reader.size.saturating_sub(reader.position)) as usize;
+        if len == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let mut source = reader.archive.source.lock();
+        let result = source
+            .seek(SeekFrom::Start(reader.offset + reader.position))
+            .and_then(|_| source.read(&mut buf[..len]));
+        if let Ok(read) = result {
+            reader.position += read as u64;
+        }
+        Poll::Ready(result)
+    }
+}
+
+impl<'a> AsyncSeek for ArchiveEntryReader<'a> {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let reader = self.get_mut();
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => checked_add_signed(reader.size, offset),
+            SeekFrom::Current(offset) => checked_add_signed(reader.position, offset),
+        };
+        Poll::Ready(match position {
+            Some(position) => {
+                reader.position = position;
+                Ok(position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            )),
+        })
+    }
+}
+
+fn checked_add_signed(value: u64, offset: i64) -> Option<u64> {
+    if offset >= 0 {
+        value.checked_add(offset as u64)
+    } else {
+        value.checked_sub(offset.unsigned_abs())
+    }
+}
+
+/// Shared bytes of an archive loaded in memory
+struct ArcBytes(Arc<[u8]>);
+
+impl AsRef<[u8]> for ArcBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::{future::block_on, AsyncReadExt, AsyncSeekExt};
+
+    fn build_archive(compression: ArchiveCompression) -> ArchiveAssetIo {
+        let mut writer = ArchiveWriter::new(io::Cursor::new(Vec::new()), compression).unwrap();
+        writer.add_file("a.txt", b"hello").unwrap();
+        writer.add_file("textures/grass.png", &[42; 1024]).unwrap();
+        writer.add_file("textures/rpg/chars.png", b"chars").unwrap();
+        writer.add_file("a.txt", b"replaced").unwrap();
+        assert!(writer.add_file("../outside.txt", b"").is_err());
+        let bytes = writer.finish().unwrap().into_inner();
+        ArchiveAssetIo::from_bytes(bytes).unwrap()
+    }
+
+    #[test]
+    fn read_archive() {
+        for compression in [ArchiveCompression::None, ArchiveCompression::Deflate] {
+            let archive = build_archive(compression);
+            let load = |path: &str| block_on(archive.load_path(Path::new(path)));
+
+            assert_eq!(load("a.txt").unwrap(), b"replaced");
+            assert_eq!(load("./textures/grass.png").unwrap(), vec![42; 1024]);
+            assert_eq!(load("textures/rpg/chars.png").unwrap(), b"chars");
+            assert!(matches!(
+                load("missing.txt"),
+                Err(AssetIoError::NotFound(_))
+            ));
+
+            let range = block_on(archive.read_range(Path::new("textures/rpg/chars.png"), 1..3));
+            assert_eq!(range.unwrap(), b"ha");
+            let mut reader = block_on(archive.read_path(Path::new("a.txt"))).unwrap();
+            let mut end = Vec::new();
+            block_on(reader.seek(SeekFrom::End(-3))).unwrap();
+            block_on(reader.read_to_end(&mut end)).unwrap();
+            assert_eq!(end, b"ced");
+            assert!(block_on(reader.seek(SeekFrom::Current(-10))).is_err());
+
+            assert!(archive.is_directory(Path::new("textures/rpg")));
+            assert!(!archive.is_directory(Path::new("a.txt")));
+            let mut children: Vec<_> = archive
+                .read_directory(Path::new("textures"))
+                .unwrap()
+                .collect();
+            children.sort();
+            assert_eq!(
+                children,
+                vec![
+                    PathBuf::from("textures/grass.png"),
+                    PathBuf::from("textures/rpg")
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_archive() {
+        assert!(matches!(
+            ArchiveAssetIo::from_bytes(&b"not an archive"[..]),
+            Err(ArchiveError::InvalidArchive(_))
+        ));
+
+        let mut writer =
+            ArchiveWriter::new(io::Cursor::new(Vec::new()), ArchiveCompression::None).unwrap();
+        writer.add_file("a.txt", b"hello").unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+        // the size of the file is stored before the last byte of the archive
+        let size_position = bytes.len() - 9;
+        let mut corrupt = bytes.clone();
+        corrupt[size_position..size_position + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            ArchiveAssetIo::from_bytes(corrupt),
+            Err(ArchiveError::InvalidArchive(_))
+        ));
+        let truncated = bytes[..bytes.len() - 1].to_vec();
+        assert!(matches!(
+            ArchiveAssetIo::from_bytes(truncated),
+            Err(ArchiveError::InvalidArchive(_))
+        ));
+        let mut corrupt = bytes;
+        corrupt[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            ArchiveAssetIo::from_bytes(corrupt),
+            Err(ArchiveError::InvalidArchive(_))
+        ));
+    }
+}
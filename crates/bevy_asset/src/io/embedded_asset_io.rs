@@ -0,0 +1,99 @@
+use crate::{AssetIo, AssetIoError, MemoryAssetIo};
+use anyhow::Result;
+use bevy_utils::BoxedFuture;
+use std::path::{Path, PathBuf};
+
+/// Name of the asset source registered by default with an [`EmbeddedAssetIo`]
+pub const EMBEDDED_ASSET_SOURCE: &str = "embedded";
+
+/// Serves assets compiled into the binary, loaded from paths like
+/// `"embedded://path/to/file.ext"`.
+///
+/// Plugins use it to ship their default assets without an asset folder on disk. Assets are
+/// usually added with the [`embedded_asset!`](crate::embedded_asset) macro.
+#[derive(Default)]
+pub struct EmbeddedAssetIo {
+    files: MemoryAssetIo,
+}
+
+impl EmbeddedAssetIo {
+    /// Inserts the file at `path`, replacing it if it exists.
+    pub fn insert(&self, path: impl Into<PathBuf>, bytes: &'static [u8]) {
+        self.files.insert(path, bytes);
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.contains(path)
+    }
+}
+
+impl AssetIo for EmbeddedAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        self.files.load_path(path)
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        self.files.read_directory(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.files.is_directory(path)
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+/// Embeds the file at `path`, relative to the current source file, in the binary and adds it
+/// to the [`EmbeddedAssetIo`] of the app, at `"embedded://<crate name>/<path>"`.
+///
+/// ```ignore
+/// # use bevy_app::App;
+/// # use bevy_asset::embedded_asset;
+/// # let mut app = App::new();
+/// // loaded with `asset_server.load("embedded://my_plugin/shaders/custom.wgsl")`
+/// embedded_asset!(&mut app, "shaders/custom.wgsl");
+/// ```
+#[macro_export]
+macro_rules! embedded_asset {
+    ($app: expr, $path: literal) => {
+        $crate::AddAsset::add_embedded_asset(
+            $app,
+            concat!(env!("CARGO_PKG_NAME"), "/", $path),
+            include_bytes!($path),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AssetServer, MemoryAssetIo, EMBEDDED_ASSET_SOURCE};
+    use bevy_app::App;
+    use bevy_tasks::TaskPool;
+    use futures_lite::future::block_on;
+    use std::path::Path;
+
+    #[test]
+    fn embed_asset() {
+        let mut app = App::new();
+        app.insert_resource(AssetServer::new(MemoryAssetIo::default(), TaskPool::new()));
+        embedded_asset!(&mut app, "embedded_asset_io.rs");
+
+        let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+        let asset_io = asset_server
+            .get_source(Some(EMBEDDED_ASSET_SOURCE))
+            .unwrap();
+        let bytes =
+            block_on(asset_io.load_path(Path::new("bevy_asset/embedded_asset_io.rs"))).unwrap();
+        assert_eq!(bytes, include_bytes!("embedded_asset_io.rs"));
+        assert!(asset_io.is_directory(Path::new("bevy_asset")));
+    }
+}
@@ -1,23 +1,30 @@
 #[cfg(feature = "filesystem_watcher")]
-use crate::{filesystem_watcher::FilesystemWatcher, AssetServer};
-use crate::{AssetIo, AssetIoError};
+use crate::{
+    filesystem_watcher::FilesystemWatcher, AssetPath, AssetServer, AssetServerSettings,
+    DEFAULT_RELOAD_DEBOUNCE,
+};
+use crate::{AssetIo, AssetIoError, Reader};
 use anyhow::Result;
 #[cfg(feature = "filesystem_watcher")]
 use bevy_ecs::system::Res;
 use bevy_utils::BoxedFuture;
 #[cfg(feature = "filesystem_watcher")]
-use bevy_utils::HashSet;
-#[cfg(feature = "filesystem_watcher")]
 use crossbeam_channel::TryRecvError;
 use fs::File;
+use futures_lite::{AsyncRead, AsyncSeek};
 #[cfg(feature = "filesystem_watcher")]
 use parking_lot::RwLock;
-#[cfg(feature = "filesystem_watcher")]
-use std::sync::Arc;
 use std::{
     env, fs,
-    io::Read,
+    io::{Read, Seek, SeekFrom},
     path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(feature = "filesystem_watcher")]
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 pub struct FileAssetIo {
@@ -48,6 +55,49 @@ impl FileAssetIo {
                 .unwrap()
         }
     }
+
+    /// Returns the paths relative to the root path of the files that changed at least
+    /// `debounce` before `now`
+    #[cfg(all(
+        feature = "filesystem_watcher",
+        all(not(target_arch = "wasm32"), not(target_os = "android"))
+    ))]
+    fn take_changed_paths(&self, now: Instant, debounce: Duration) -> Vec<PathBuf> {
+        let mut watcher = self.filesystem_watcher.write();
+        let watcher = if let Some(ref mut watcher) = *watcher {
+            watcher
+        } else {
+            return Vec::new();
+        };
+        loop {
+            let event = match watcher.receiver.try_recv() {
+                Ok(result) => result.unwrap(),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => panic!("FilesystemWatcher disconnected."),
+            };
+            if let notify::event::Event {
+                kind: notify::event::EventKind::Modify(_),
+                paths,
+                ..
+            } = event
+            {
+                for path in paths {
+                    watcher.pending_changes.insert(path, now);
+                }
+            }
+        }
+        let mut changed = Vec::new();
+        watcher.pending_changes.retain(|path, last_change| {
+            if now.duration_since(*last_change) < debounce {
+                return true;
+            }
+            if let Ok(relative_path) = path.strip_prefix(&self.root_path) {
+                changed.push(relative_path.to_path_buf());
+            }
+            false
+        });
+        changed
+    }
 }
 
 impl AssetIo for FileAssetIo {
@@ -71,6 +121,22 @@ impl AssetIo for FileAssetIo {
         })
     }
 
+    fn read_path<'a>(
+        &'a self,
+        path: &Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetIoError>> {
+        let full_path = self.root_path.join(path);
+        Box::pin(async move {
+            match File::open(&full_path) {
+                Ok(file) => Ok(Box::new(FileReader(file)) as Box<Reader>),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    Err(AssetIoError::NotFound(full_path))
+                }
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
     fn read_directory(
         &self,
         path: &Path,
@@ -111,42 +177,171 @@ impl AssetIo for FileAssetIo {
     fn is_directory(&self, path: &Path) -> bool {
         self.root_path.join(path).is_dir()
     }
+
+    fn write_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            let full_path = self.root_path.join(path);
+            if let Some(parent) = full_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // write to a temporary file first, so that the asset is never read half-written
+            let mut temp_path = full_path.clone().into_os_string();
+            temp_path.push(".tmp");
+            fs::write(&temp_path, bytes)?;
+            fs::rename(&temp_path, &full_path)?;
+            Ok(())
+        })
+    }
+
+    fn rename_path<'a>(
+        &'a self,
+        from: &'a Path,
+        to: &'a Path,
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            let from = self.root_path.join(from);
+            let to = self.root_path.join(to);
+            if !from.exists() {
+                return Err(AssetIoError::NotFound(from));
+            }
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(from, to)?;
+            Ok(())
+        })
+    }
+
+    fn remove_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            let full_path = self.root_path.join(path);
+            match fs::remove_file(&full_path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    Err(AssetIoError::NotFound(full_path))
+                }
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
 }
 
+/// Reloads the assets whose files changed, once they haven't changed for
+/// [`AssetServerSettings::reload_debounce`], as editors often write files several times in a row.
 #[cfg(all(
     feature = "filesystem_watcher",
     all(not(target_arch = "wasm32"), not(target_os = "android"))
 ))]
-pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
-    let mut changed = HashSet::default();
-    let asset_io =
-        if let Some(asset_io) = asset_server.server.asset_io.downcast_ref::<FileAssetIo>() {
+pub fn filesystem_watcher_system(
+    asset_server: Res<AssetServer>,
+    settings: Option<Res<AssetServerSettings>>,
+) {
+    let debounce = settings.map_or(DEFAULT_RELOAD_DEBOUNCE, |settings| settings.reload_debounce);
+    let now = Instant::now();
+
+    let mut changed = Vec::new();
+    if let Some(asset_io) = asset_server.server.asset_io.downcast_ref::<FileAssetIo>() {
+        changed.extend(
             asset_io
-        } else {
-            return;
-        };
-    let watcher = asset_io.filesystem_watcher.read();
-    if let Some(ref watcher) = *watcher {
-        loop {
-            let event = match watcher.receiver.try_recv() {
-                Ok(result) => result.unwrap(),
-                Err(TryRecvError::Empty) => break,
-                Err(TryRecvError::Disconnected) => panic!("FilesystemWatcher disconnected."),
-            };
-            if let notify::event::Event {
-                kind: notify::event::EventKind::Modify(_),
-                paths,
-                ..
-            } = event
-            {
-                for path in paths.iter() {
-                    if !changed.contains(path) {
-                        let relative_path = path.strip_prefix(&asset_io.root_path).unwrap();
-                        let _ = asset_server.load_untracked(relative_path.into(), true);
-                    }
-                }
-                changed.extend(paths);
-            }
+                .take_changed_paths(now, debounce)
+                .into_iter()
+                .map(|path| AssetPath::new(path, None)),
+        );
+    }
+    for (name, asset_io) in asset_server.server.asset_io_sources.read().iter() {
+        if let Some(asset_io) = asset_io.downcast_ref::<FileAssetIo>() {
+            changed.extend(
+                asset_io
+                    .take_changed_paths(now, debounce)
+                    .into_iter()
+                    .map(|path| AssetPath::new(path, None).with_source(name.clone())),
+            );
         }
     }
+
+    // the watchers and sources are released first, as loading assets watches their paths
+    for path in changed {
+        asset_server.reload_path(path);
+    }
+}
+
+/// Reads a file, blocking like [`FileAssetIo::load_path`]
+struct FileReader(File);
+
+impl AsyncRead for FileReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(self.get_mut().0.read(buf))
+    }
+}
+
+impl AsyncSeek for FileReader {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<std::io::Result<u64>> {
+        Poll::Ready(self.get_mut().0.seek(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future::block_on;
+
+    #[test]
+    fn write_rename_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let asset_io = FileAssetIo::new(dir.path());
+
+        block_on(asset_io.write_path(Path::new("sub/a.txt"), b"hello")).unwrap();
+        assert_eq!(
+            block_on(asset_io.load_path(Path::new("sub/a.txt"))).unwrap(),
+            b"hello"
+        );
+
+        block_on(asset_io.rename_path(Path::new("sub/a.txt"), Path::new("b.txt"))).unwrap();
+        assert!(block_on(asset_io.load_path(Path::new("sub/a.txt"))).is_err());
+        assert_eq!(
+            block_on(asset_io.load_path(Path::new("b.txt"))).unwrap(),
+            b"hello"
+        );
+
+        block_on(asset_io.remove_path(Path::new("b.txt"))).unwrap();
+        assert!(matches!(
+            block_on(asset_io.remove_path(Path::new("b.txt"))),
+            Err(AssetIoError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn read_ranges() {
+        use futures_lite::{AsyncReadExt, AsyncSeekExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.bin"), b"0123456789").unwrap();
+        let asset_io = FileAssetIo::new(dir.path());
+
+        let mut reader = block_on(asset_io.read_path(Path::new("a.bin"))).unwrap();
+        let mut bytes = [0; 3];
+        block_on(reader.seek(SeekFrom::End(-3))).unwrap();
+        block_on(reader.read_exact(&mut bytes)).unwrap();
+        assert_eq!(&bytes, b"789");
+
+        let range = block_on(asset_io.read_range(Path::new("a.bin"), 2..5)).unwrap();
+        assert_eq!(range, b"234");
+        assert!(block_on(asset_io.read_range(Path::new("a.bin"), 8..12)).is_err());
+        assert!(matches!(
+            block_on(asset_io.read_path(Path::new("missing.bin"))),
+            Err(AssetIoError::NotFound(_))
+        ));
+    }
 }
@@ -0,0 +1,207 @@
+use crate::{AssetIo, AssetIoError, Reader};
+use anyhow::Result;
+use bevy_utils::{BoxedFuture, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Combines several [`AssetIo`] layers, where files of a layer shadow the files at the same
+/// path in the layers below it.
+///
+/// This lets DLC or mod archives override base assets through the usual `AssetServer::load`
+/// paths:
+/// ```no_run
+/// # use bevy_asset::{ArchiveAssetIo, FileAssetIo, LayeredAssetIo};
+/// let asset_io = LayeredAssetIo::new(FileAssetIo::new("assets"))
+///     .with_layer(ArchiveAssetIo::open("dlc.pak").unwrap())
+///     .with_layer(ArchiveAssetIo::open("mod.pak").unwrap());
+/// ```
+/// Files are written to the top-most layer that isn't read-only.
+pub struct LayeredAssetIo {
+    /// Layers from the bottom to the top
+    layers: Vec<Box<dyn AssetIo>>,
+}
+
+impl LayeredAssetIo {
+    pub fn new(base: impl AssetIo) -> Self {
+        Self::from_boxed(Box::new(base))
+    }
+
+    pub fn from_boxed(base: Box<dyn AssetIo>) -> Self {
+        LayeredAssetIo { layers: vec![base] }
+    }
+
+    /// Adds a layer on top of the existing ones.
+    pub fn with_layer(mut self, layer: impl AssetIo) -> Self {
+        self.add_layer(Box::new(layer));
+        self
+    }
+
+    /// Adds a layer on top of the existing ones.
+    pub fn add_layer(&mut self, layer: Box<dyn AssetIo>) {
+        self.layers.push(layer);
+    }
+
+    /// Returns the layers, from the bottom to the top.
+    pub fn layers(&self) -> impl Iterator<Item = &dyn AssetIo> {
+        self.layers.iter().map(|layer| &**layer)
+    }
+}
+
+impl AssetIo for LayeredAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            for layer in self.layers.iter().rev() {
+                match layer.load_path(path).await {
+                    Err(AssetIoError::NotFound(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn read_path<'a>(
+        &'a self,
+        path: &Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetIoError>> {
+        let path = path.to_owned();
+        Box::pin(async move {
+            for layer in self.layers.iter().rev() {
+                match layer.read_path(&path).await {
+                    Err(AssetIoError::NotFound(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::NotFound(path))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let mut found = false;
+        let mut children = HashSet::default();
+        for layer in self.layers.iter() {
+            if !layer.is_directory(path) {
+                continue;
+            }
+            children.extend(layer.read_directory(path)?);
+            found = true;
+        }
+        if found {
+            Ok(Box::new(children.into_iter()))
+        } else {
+            Err(AssetIoError::NotFound(path.to_owned()))
+        }
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.layers.iter().any(|layer| layer.is_directory(path))
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        for layer in self.layers.iter() {
+            layer.watch_path_for_changes(path)?;
+        }
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        for layer in self.layers.iter() {
+            layer.watch_for_changes()?;
+        }
+        Ok(())
+    }
+
+    fn write_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            for layer in self.layers.iter().rev() {
+                match layer.write_path(path, bytes).await {
+                    Err(AssetIoError::ReadOnly(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::ReadOnly(path.to_owned()))
+        })
+    }
+
+    fn rename_path<'a>(
+        &'a self,
+        from: &'a Path,
+        to: &'a Path,
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            for layer in self.layers.iter().rev() {
+                match layer.rename_path(from, to).await {
+                    Err(AssetIoError::ReadOnly(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::ReadOnly(from.to_owned()))
+        })
+    }
+
+    fn remove_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            for layer in self.layers.iter().rev() {
+                match layer.remove_path(path).await {
+                    Err(AssetIoError::ReadOnly(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::ReadOnly(path.to_owned()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArchiveAssetIo, ArchiveCompression, ArchiveWriter, FileAssetIo};
+    use futures_lite::future::block_on;
+    use std::{fs, io::Cursor};
+
+    fn archive(files: &[(&str, &[u8])]) -> ArchiveAssetIo {
+        let mut writer =
+            ArchiveWriter::new(Cursor::new(Vec::new()), ArchiveCompression::None).unwrap();
+        for (path, bytes) in files {
+            writer.add_file(path, bytes).unwrap();
+        }
+        ArchiveAssetIo::from_bytes(writer.finish().unwrap().into_inner()).unwrap()
+    }
+
+    #[test]
+    fn layers_override() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("data")).unwrap();
+        fs::write(dir.path().join("data/a.txt"), "base").unwrap();
+        fs::write(dir.path().join("data/b.txt"), "base").unwrap();
+
+        let asset_io = LayeredAssetIo::new(FileAssetIo::new(dir.path()))
+            .with_layer(archive(&[("data/b.txt", b"dlc"), ("data/c.txt", b"dlc")]))
+            .with_layer(archive(&[("data/c.txt", b"mod")]));
+        let load = |path: &str| block_on(asset_io.load_path(Path::new(path)));
+
+        assert_eq!(load("data/a.txt").unwrap(), b"base");
+        assert_eq!(load("data/b.txt").unwrap(), b"dlc");
+        assert_eq!(load("data/c.txt").unwrap(), b"mod");
+        assert!(matches!(load("data/d.txt"), Err(AssetIoError::NotFound(_))));
+
+        let mut children: Vec<_> = asset_io
+            .read_directory(Path::new("data"))
+            .unwrap()
+            .map(|path| path.file_name().unwrap().to_owned())
+            .collect();
+        children.sort();
+        assert_eq!(children, vec!["a.txt", "b.txt", "c.txt"]);
+
+        // archives are read-only, writes go to the base folder
+        block_on(asset_io.write_path(Path::new("data/c.txt"), b"saved")).unwrap();
+        assert_eq!(fs::read(dir.path().join("data/c.txt")).unwrap(), b"saved");
+        assert_eq!(load("data/c.txt").unwrap(), b"mod");
+    }
+}
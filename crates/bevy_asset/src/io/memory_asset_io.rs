@@ -0,0 +1,118 @@
+use crate::{AssetIo, AssetIoError};
+use anyhow::Result;
+use bevy_utils::{BoxedFuture, HashMap, HashSet};
+use parking_lot::RwLock;
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+/// Name of the asset source registered by default with a [`MemoryAssetIo`]
+pub const MEMORY_ASSET_SOURCE: &str = "memory";
+
+/// Stores assets in memory.
+///
+/// Assets are inserted at runtime, or written with [`AssetServer::save`](crate::AssetServer::save),
+/// and loaded from paths like `"memory://path/to/file.ext"`.
+#[derive(Default)]
+pub struct MemoryAssetIo {
+    files: RwLock<HashMap<PathBuf, Cow<'static, [u8]>>>,
+}
+
+impl MemoryAssetIo {
+    /// Inserts the file at `path`, replacing it if it exists.
+    pub fn insert(&self, path: impl Into<PathBuf>, bytes: impl Into<Cow<'static, [u8]>>) {
+        self.files.write().insert(path.into(), bytes.into());
+    }
+
+    /// Removes the file at `path`, returning whether it existed.
+    pub fn remove(&self, path: &Path) -> bool {
+        self.files.write().remove(path).is_some()
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.read().contains_key(path)
+    }
+}
+
+impl AssetIo for MemoryAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            self.files
+                .read()
+                .get(path)
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let children: HashSet<PathBuf> = self
+            .files
+            .read()
+            .keys()
+            .filter_map(|file| {
+                let child = file.strip_prefix(path).ok()?.components().next()?;
+                Some(path.join(child))
+            })
+            .collect();
+        if children.is_empty() {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        Ok(Box::new(children.into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.files
+            .read()
+            .keys()
+            .any(|file| file != path && file.starts_with(path))
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn write_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            self.insert(path, bytes.to_vec());
+            Ok(())
+        })
+    }
+
+    fn rename_path<'a>(
+        &'a self,
+        from: &'a Path,
+        to: &'a Path,
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            let mut files = self.files.write();
+            let bytes = files
+                .remove(from)
+                .ok_or_else(|| AssetIoError::NotFound(from.to_owned()))?;
+            files.insert(to.to_owned(), bytes);
+            Ok(())
+        })
+    }
+
+    fn remove_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            if self.remove(path) {
+                Ok(())
+            } else {
+                Err(AssetIoError::NotFound(path.to_owned()))
+            }
+        })
+    }
+}
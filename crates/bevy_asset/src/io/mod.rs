@@ -1,23 +1,36 @@
 #[cfg(target_os = "android")]
 mod android_asset_io;
+mod archive_asset_io;
+mod embedded_asset_io;
 #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
 mod file_asset_io;
+mod layered_asset_io;
+mod memory_asset_io;
+mod processed_asset_io;
 #[cfg(target_arch = "wasm32")]
 mod wasm_asset_io;
 
 #[cfg(target_os = "android")]
 pub use android_asset_io::*;
+pub use archive_asset_io::*;
+pub use embedded_asset_io::*;
 #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
 pub use file_asset_io::*;
+pub use layered_asset_io::*;
+pub use memory_asset_io::*;
+pub use processed_asset_io::*;
 #[cfg(target_arch = "wasm32")]
 pub use wasm_asset_io::*;
 
 use anyhow::Result;
 use bevy_utils::BoxedFuture;
 use downcast_rs::{impl_downcast, Downcast};
+use futures_lite::{io::Cursor, AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 use std::{
-    io,
+    io::{self, SeekFrom},
+    ops::Range,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 use thiserror::Error;
 
@@ -30,11 +43,53 @@ pub enum AssetIoError {
     Io(#[from] io::Error),
     #[error("failed to watch path: {0}")]
     PathWatchError(PathBuf),
+    #[error("cannot write to {0}, the asset io is read-only")]
+    ReadOnly(PathBuf),
 }
 
+/// An async reader that can seek
+pub trait AsyncReadAndSeek: AsyncRead + AsyncSeek + Unpin + Send + Sync {}
+
+impl<T: AsyncRead + AsyncSeek + Unpin + Send + Sync> AsyncReadAndSeek for T {}
+
+/// A reader of a file opened with [`AssetIo::read_path`]
+pub type Reader<'a> = dyn AsyncReadAndSeek + 'a;
+
 /// Handles load requests from an AssetServer
 pub trait AssetIo: Downcast + Send + Sync + 'static {
     fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>>;
+
+    /// Opens the file at `path` to read it progressively, or only read parts of it.
+    ///
+    /// The default implementation loads the whole file with [`AssetIo::load_path`]. Backends
+    /// able to read files in parts override it, so that large files don't have to be resident
+    /// in memory.
+    fn read_path<'a>(
+        &'a self,
+        path: &Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetIoError>> {
+        let path = path.to_owned();
+        Box::pin(async move {
+            let bytes = self.load_path(&path).await?;
+            Ok(Box::new(Cursor::new(bytes)) as Box<Reader>)
+        })
+    }
+
+    /// Reads the bytes of the file at `path` in `range`. Fails with an
+    /// [`io::ErrorKind::UnexpectedEof`] error if the file ends before the end of the range.
+    fn read_range<'a>(
+        &'a self,
+        path: &'a Path,
+        range: Range<u64>,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            let mut reader = self.read_path(path).await?;
+            reader.seek(SeekFrom::Start(range.start)).await?;
+            let mut bytes = vec![0; range.end.saturating_sub(range.start) as usize];
+            reader.read_exact(&mut bytes).await?;
+            Ok(bytes)
+        })
+    }
     fn read_directory(
         &self,
         path: &Path,
@@ -42,6 +97,60 @@ pub trait AssetIo: Downcast + Send + Sync + 'static {
     fn is_directory(&self, path: &Path) -> bool;
     fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError>;
     fn watch_for_changes(&self) -> Result<(), AssetIoError>;
+
+    /// Writes `bytes` to the file at `path`, replacing it if it exists.
+    fn write_path<'a>(
+        &'a self,
+        path: &'a Path,
+        _bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move { Err(AssetIoError::ReadOnly(path.to_owned())) })
+    }
+
+    /// Renames the file at `from` to `to`, replacing `to` if it exists.
+    fn rename_path<'a>(
+        &'a self,
+        from: &'a Path,
+        _to: &'a Path,
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move { Err(AssetIoError::ReadOnly(from.to_owned())) })
+    }
+
+    /// Removes the file at `path`.
+    fn remove_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move { Err(AssetIoError::ReadOnly(path.to_owned())) })
+    }
 }
 
 impl_downcast!(AssetIo);
+
+/// A file of an asset source that assets can keep, to read parts of the file after they are
+/// loaded, like the sounds of an audio bank that are only decoded when they are played.
+/// Returned by [`LoadContext::asset_file`](crate::LoadContext::asset_file).
+#[derive(Clone)]
+pub struct AssetFile {
+    pub(crate) asset_io: Arc<dyn AssetIo>,
+    pub(crate) path: PathBuf,
+}
+
+impl AssetFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Opens the file to read it progressively, see [`AssetIo::read_path`]
+    pub async fn reader(&self) -> Result<Box<Reader<'_>>, AssetIoError> {
+        self.asset_io.read_path(&self.path).await
+    }
+
+    /// Reads the bytes of the file in `range`, see [`AssetIo::read_range`]
+    pub async fn read_range(&self, range: Range<u64>) -> Result<Vec<u8>, AssetIoError> {
+        self.asset_io.read_range(&self.path, range).await
+    }
+}
+
+impl std::fmt::Debug for AssetFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AssetFile").field(&self.path).finish()
+    }
+}
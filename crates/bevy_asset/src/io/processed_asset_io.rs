@@ -0,0 +1,165 @@
+use crate::{AssetIo, AssetIoError, ImportedAssetIndex, Reader, IMPORTED_ASSET_INDEX};
+use anyhow::Result;
+use bevy_log::warn;
+use bevy_utils::BoxedFuture;
+use parking_lot::RwLock;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Loads assets processed by an [`AssetProcessor`](crate::AssetProcessor).
+///
+/// Assets listed in the index of the imported assets folder are loaded from their processed
+/// file, other assets are loaded from the source folder. Directories are read, changes are
+/// watched and files are written in the source folder.
+pub struct ProcessedAssetIo {
+    source: Box<dyn AssetIo>,
+    imported: Box<dyn AssetIo>,
+    index: RwLock<Option<Arc<ImportedAssetIndex>>>,
+}
+
+impl ProcessedAssetIo {
+    pub fn new(source: Box<dyn AssetIo>, imported: Box<dyn AssetIo>) -> Self {
+        ProcessedAssetIo {
+            source,
+            imported,
+            index: RwLock::new(None),
+        }
+    }
+
+    /// Discards the cached index of the imported assets folder, so that it is read again on
+    /// the next load. Call this after processing assets while the app is running.
+    pub fn reload_index(&self) {
+        *self.index.write() = None;
+    }
+
+    async fn index(&self) -> Arc<ImportedAssetIndex> {
+        if let Some(index) = self.index.read().as_ref() {
+            return index.clone();
+        }
+        let index = match self
+            .imported
+            .load_path(Path::new(IMPORTED_ASSET_INDEX))
+            .await
+        {
+            Ok(bytes) => ron::de::from_bytes(&bytes).unwrap_or_else(|err| {
+                warn!(
+                    "invalid imported asset index, loading source assets: {}",
+                    err
+                );
+                ImportedAssetIndex::default()
+            }),
+            Err(_) => ImportedAssetIndex::default(),
+        };
+        let index = Arc::new(index);
+        *self.index.write() = Some(index.clone());
+        index
+    }
+
+    /// Returns the path of the processed file of the asset at `path`, relative to the imported
+    /// assets folder, if it was processed.
+    pub async fn imported_path(&self, path: &Path) -> Option<PathBuf> {
+        self.index()
+            .await
+            .assets
+            .get(path)
+            .map(|imported| imported.file.clone())
+    }
+}
+
+impl AssetIo for ProcessedAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            match self.imported_path(path).await {
+                Some(imported) => self.imported.load_path(&imported).await,
+                None => self.source.load_path(path).await,
+            }
+        })
+    }
+
+    fn read_path<'a>(
+        &'a self,
+        path: &Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetIoError>> {
+        let path = path.to_owned();
+        Box::pin(async move {
+            match self.imported_path(&path).await {
+                Some(imported) => self.imported.read_path(&imported).await,
+                None => self.source.read_path(&path).await,
+            }
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        self.source.read_directory(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.source.is_directory(path)
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        self.source.watch_path_for_changes(path)
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        self.source.watch_for_changes()
+    }
+
+    fn write_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        self.source.write_path(path, bytes)
+    }
+
+    fn rename_path<'a>(
+        &'a self,
+        from: &'a Path,
+        to: &'a Path,
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        self.source.rename_path(from, to)
+    }
+
+    fn remove_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        self.source.remove_path(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetProcessor, FileAssetIo};
+    use bevy_tasks::TaskPool;
+    use futures_lite::future::block_on;
+    use std::fs;
+
+    #[test]
+    fn load_processed() {
+        let source = tempfile::tempdir().unwrap();
+        let imported = tempfile::tempdir().unwrap();
+        fs::write(source.path().join("a.txt"), "source").unwrap();
+
+        let asset_io = ProcessedAssetIo::new(
+            Box::new(FileAssetIo::new(source.path())),
+            Box::new(FileAssetIo::new(imported.path())),
+        );
+        // nothing was processed yet, the source file is loaded
+        let bytes = block_on(asset_io.load_path(Path::new("a.txt"))).unwrap();
+        assert_eq!(bytes, b"source");
+
+        AssetProcessor::new(source.path(), imported.path())
+            .process(&TaskPool::new())
+            .unwrap();
+        fs::write(source.path().join("a.txt"), "changed").unwrap();
+        asset_io.reload_index();
+        let bytes = block_on(asset_io.load_path(Path::new("a.txt"))).unwrap();
+        assert_eq!(bytes, b"source");
+        assert!(block_on(asset_io.load_path(Path::new("missing.txt"))).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 mod asset_server;
 mod assets;
+mod dependency_graph;
 pub mod diagnostic;
 #[cfg(all(
     feature = "filesystem_watcher",
@@ -11,6 +12,9 @@ mod info;
 mod io;
 mod loader;
 mod path;
+mod processor;
+mod reference_report;
+mod saver;
 
 pub mod prelude {
     #[doc(hidden)]
@@ -25,10 +29,14 @@ pub use info::*;
 pub use io::*;
 pub use loader::*;
 pub use path::*;
+pub use processor::*;
+pub use reference_report::*;
+pub use saver::*;
 
 use bevy_app::{prelude::Plugin, App};
 use bevy_ecs::schedule::{StageLabel, SystemStage};
 use bevy_tasks::IoTaskPool;
+use std::time::Duration;
 
 /// The names of asset stages in an App Schedule
 #[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
@@ -44,16 +52,41 @@ pub struct AssetPlugin;
 
 pub struct AssetServerSettings {
     pub asset_folder: String,
+    /// The folder storing the assets processed by the [`AssetProcessor`]
+    pub imported_asset_folder: String,
+    pub processing_mode: AssetProcessingMode,
+    /// How long a changed file has to stay unchanged before its assets are hot reloaded
+    pub reload_debounce: Duration,
 }
 
+/// The default [`AssetServerSettings::reload_debounce`]
+pub const DEFAULT_RELOAD_DEBOUNCE: Duration = Duration::from_millis(50);
+
 impl Default for AssetServerSettings {
     fn default() -> Self {
         Self {
             asset_folder: "assets".to_string(),
+            imported_asset_folder: "imported_assets".to_string(),
+            processing_mode: AssetProcessingMode::Unprocessed,
+            reload_debounce: DEFAULT_RELOAD_DEBOUNCE,
         }
     }
 }
 
+/// Where the [`AssetServer`] loads assets from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetProcessingMode {
+    /// Assets are loaded from the asset folder
+    Unprocessed,
+    /// Assets are loaded from the imported asset folder when they were processed, and from the
+    /// asset folder otherwise. This is the mode to use when shipping processed assets.
+    Processed,
+    /// Like [`AssetProcessingMode::Processed`], but the assets are processed on startup. The
+    /// [`AssetProcessor`] resource is added, and processors registered with
+    /// [`AddAsset::add_asset_processor`] are used. Not supported on wasm and android.
+    ProcessOnStartup,
+}
+
 /// Create an instance of the platform default `AssetIo`
 ///
 /// This is useful when providing a custom `AssetIo` instance that needs to
@@ -63,16 +96,51 @@ pub fn create_platform_default_asset_io(app: &mut App) -> Box<dyn AssetIo> {
         .world
         .get_resource_or_insert_with(AssetServerSettings::default);
 
+    create_platform_asset_io(&settings.asset_folder)
+}
+
+fn create_platform_asset_io(folder: &str) -> Box<dyn AssetIo> {
     #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
-    let source = FileAssetIo::new(&settings.asset_folder);
+    let source = FileAssetIo::new(folder);
     #[cfg(target_arch = "wasm32")]
-    let source = WasmAssetIo::new(&settings.asset_folder);
+    let source = WasmAssetIo::new(folder);
     #[cfg(target_os = "android")]
-    let source = AndroidAssetIo::new(&settings.asset_folder);
+    let source = AndroidAssetIo::new(folder);
 
     Box::new(source)
 }
 
+/// Processes the assets on startup, see [`AssetProcessingMode::ProcessOnStartup`]
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+fn process_assets_system(
+    asset_processor: bevy_ecs::system::Res<AssetProcessor>,
+    asset_server: bevy_ecs::system::Res<AssetServer>,
+    task_pool: bevy_ecs::system::Res<IoTaskPool>,
+) {
+    use bevy_log::{info, warn};
+
+    match asset_processor.process(&task_pool) {
+        Ok(summary) => {
+            info!(
+                "processed {} assets, {} up to date",
+                summary.processed.len(),
+                summary.up_to_date.len()
+            );
+            for (path, err) in summary.failed.iter() {
+                warn!("failed to process asset {:?}: {}", path, err);
+            }
+        }
+        Err(err) => warn!("failed to process assets: {}", err),
+    }
+    if let Some(asset_io) = asset_server
+        .server
+        .asset_io
+        .downcast_ref::<ProcessedAssetIo>()
+    {
+        asset_io.reload_index();
+    }
+}
+
 impl Plugin for AssetPlugin {
     fn build(&self, app: &mut App) {
         if app.world.get_resource::<AssetServer>().is_none() {
@@ -83,7 +151,28 @@ impl Plugin for AssetPlugin {
                 .0
                 .clone();
 
-            let source = create_platform_default_asset_io(app);
+            let mut source = create_platform_default_asset_io(app);
+
+            let settings = app.world.get_resource::<AssetServerSettings>().unwrap();
+            let processing_mode = settings.processing_mode;
+            if processing_mode != AssetProcessingMode::Unprocessed {
+                let imported = create_platform_asset_io(&settings.imported_asset_folder);
+                source = Box::new(ProcessedAssetIo::new(source, imported));
+            }
+
+            #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+            if processing_mode == AssetProcessingMode::ProcessOnStartup {
+                let root_path = FileAssetIo::get_root_path();
+                let asset_processor = AssetProcessor::new(
+                    root_path.join(&settings.asset_folder),
+                    root_path.join(&settings.imported_asset_folder),
+                );
+                app.insert_resource(asset_processor)
+                    .add_startup_system_to_stage(
+                        bevy_app::StartupStage::PreStartup,
+                        process_assets_system,
+                    );
+            }
 
             let asset_server = AssetServer::with_boxed_io(source, task_pool);
 
@@ -101,9 +190,14 @@ impl Plugin for AssetPlugin {
             SystemStage::parallel(),
         )
         .register_type::<HandleId>()
+        .add_event::<AssetLoadFailedEvent>()
         .add_system_to_stage(
             bevy_app::CoreStage::PreUpdate,
             asset_server::free_unused_assets_system,
+        )
+        .add_system_to_stage(
+            AssetStage::LoadAssets,
+            asset_server::asset_load_failed_event_system,
         );
 
         #[cfg(all(
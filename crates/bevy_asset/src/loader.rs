@@ -1,5 +1,6 @@
 use crate::{
-    path::AssetPath, AssetIo, AssetIoError, AssetMeta, AssetServer, Assets, Handle, HandleId,
+    path::{AssetPath, AssetPathId, LabelId, SourcePathId},
+    AssetFile, AssetIo, AssetIoError, AssetMeta, AssetServer, Assets, Handle, HandleId, Reader,
     RefChangeChannel,
 };
 use anyhow::Result;
@@ -9,16 +10,104 @@ use bevy_tasks::TaskPool;
 use bevy_utils::{BoxedFuture, HashMap};
 use crossbeam_channel::{Receiver, Sender};
 use downcast_rs::{impl_downcast, Downcast};
-use std::path::Path;
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 /// A loader for an asset source
 pub trait AssetLoader: Send + Sync + 'static {
+    /// Settings changing how assets are loaded, set with
+    /// [`AssetServer::load_with_settings`] and read with [`LoadContext::settings`]. Loaders
+    /// without settings use `()`.
+    type Settings: Serialize + DeserializeOwned + Default + Send + Sync + 'static;
+
     fn load<'a>(
         &'a self,
         bytes: &'a [u8],
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<(), anyhow::Error>>;
     fn extensions(&self) -> &[&str];
+
+    /// Whether the loader streams the asset file with [`LoadContext::take_reader`] instead of
+    /// reading it from the `bytes` given to [`AssetLoader::load`], which are then empty.
+    ///
+    /// Streaming loaders don't need the whole file to be resident in memory, which matters for
+    /// large assets like audio banks or terrain heightmaps. Assets keeping an [`AssetFile`] from
+    /// [`LoadContext::asset_file`] can also read the rest of the file after they are loaded.
+    fn streams_asset(&self) -> bool {
+        false
+    }
+}
+
+/// An [`AssetLoader`] with its settings type erased
+pub(crate) trait ErasedAssetLoader: Send + Sync + 'static {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        settings: Option<Arc<dyn Any + Send + Sync>>,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>>;
+    fn extensions(&self) -> &[&str];
+    fn streams_asset(&self) -> bool;
+    fn settings_type_id(&self) -> TypeId;
+    fn settings_type_name(&self) -> &'static str;
+}
+
+impl<L: AssetLoader> ErasedAssetLoader for L {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        settings: Option<Arc<dyn Any + Send + Sync>>,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        load_context.settings = Some(settings.unwrap_or_else(|| Arc::new(L::Settings::default())));
+        AssetLoader::load(self, bytes, load_context)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        AssetLoader::extensions(self)
+    }
+
+    fn streams_asset(&self) -> bool {
+        AssetLoader::streams_asset(self)
+    }
+
+    fn settings_type_id(&self) -> TypeId {
+        TypeId::of::<L::Settings>()
+    }
+
+    fn settings_type_name(&self) -> &'static str {
+        std::any::type_name::<L::Settings>()
+    }
+}
+
+/// Settings passed to a loader by [`AssetServer::load_with_settings`]
+#[derive(Clone)]
+pub(crate) struct LoaderSettings {
+    /// The serialized settings, identifying the loaded assets along with their path
+    pub(crate) key: String,
+    pub(crate) value: Arc<dyn Any + Send + Sync>,
+}
+
+impl LoaderSettings {
+    pub(crate) fn new<S: Serialize + Send + Sync + 'static>(value: S) -> Self {
+        LoaderSettings {
+            key: ron::ser::to_string(&value).expect("Loader settings should be serializable."),
+            value: Arc::new(value),
+        }
+    }
+}
+
+impl fmt::Debug for LoaderSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LoaderSettings").field(&self.key).finish()
+    }
 }
 
 pub trait Asset: TypeUuid + AssetDynamic {}
@@ -78,9 +167,15 @@ impl<T: Asset> From<LoadedAsset<T>> for BoxedLoadedAsset {
 
 pub struct LoadContext<'a> {
     pub(crate) ref_change_channel: &'a RefChangeChannel,
-    pub(crate) asset_io: &'a dyn AssetIo,
+    pub(crate) asset_io: &'a Arc<dyn AssetIo>,
     pub(crate) labeled_assets: HashMap<Option<String>, BoxedLoadedAsset>,
     pub(crate) path: &'a Path,
+    pub(crate) source_path_id: SourcePathId,
+    pub(crate) settings: Option<Arc<dyn Any + Send + Sync>>,
+    /// Paths read with [`LoadContext::read_asset_bytes`]
+    pub(crate) read_paths: Mutex<Vec<PathBuf>>,
+    /// The reader of the asset file, for loaders streaming it
+    pub(crate) reader: Option<Box<Reader<'a>>>,
     pub(crate) version: usize,
     pub(crate) task_pool: &'a TaskPool,
 }
@@ -88,8 +183,9 @@ pub struct LoadContext<'a> {
 impl<'a> LoadContext<'a> {
     pub(crate) fn new(
         path: &'a Path,
+        source_path_id: SourcePathId,
         ref_change_channel: &'a RefChangeChannel,
-        asset_io: &'a dyn AssetIo,
+        asset_io: &'a Arc<dyn AssetIo>,
         version: usize,
         task_pool: &'a TaskPool,
     ) -> Self {
@@ -99,6 +195,10 @@ impl<'a> LoadContext<'a> {
             labeled_assets: Default::default(),
             version,
             path,
+            source_path_id,
+            settings: None,
+            read_paths: Default::default(),
+            reader: Default::default(),
             task_pool,
         }
     }
@@ -107,6 +207,28 @@ impl<'a> LoadContext<'a> {
         self.path
     }
 
+    /// Returns the settings of the loader, either the default settings or the ones given to
+    /// [`AssetServer::load_with_settings`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` is not the [`AssetLoader::Settings`] type of the loader.
+    pub fn settings<S: Any>(&self) -> &S {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.downcast_ref::<S>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "the settings of the loader are not of type {}",
+                    std::any::type_name::<S>()
+                )
+            })
+    }
+
+    pub(crate) fn get_asset_path_id(&self, label: Option<&str>) -> AssetPathId {
+        AssetPathId::new(self.source_path_id, LabelId::from(label))
+    }
+
     pub fn has_labeled_asset(&self, label: &str) -> bool {
         self.labeled_assets.contains_key(&Some(label.to_string()))
     }
@@ -119,17 +241,67 @@ impl<'a> LoadContext<'a> {
         assert!(!label.is_empty());
         self.labeled_assets
             .insert(Some(label.to_string()), asset.into());
-        self.get_handle(AssetPath::new_ref(self.path(), Some(label)))
+        self.get_labeled_handle(label)
+    }
+
+    /// Returns a handle to the asset of the file being loaded with the given label, from the
+    /// same asset source and with the same settings.
+    pub fn get_labeled_handle<T: Asset>(&self, label: &str) -> Handle<T> {
+        self.get_handle(self.get_asset_path_id(Some(label)))
     }
 
     pub fn get_handle<I: Into<HandleId>, T: Asset>(&self, id: I) -> Handle<T> {
         Handle::strong(id.into(), self.ref_change_channel.sender.clone())
     }
 
+    /// Reads the file at `path` from the asset source of the asset being loaded. The asset is
+    /// reloaded when the file changes.
     pub async fn read_asset_bytes<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u8>, AssetIoError> {
+        self.read_paths.lock().push(path.as_ref().to_owned());
         self.asset_io.load_path(path.as_ref()).await
     }
 
+    /// Takes the reader of the file being loaded, if the loader
+    /// [streams it](AssetLoader::streams_asset). Returns `None` if the reader was already taken.
+    ///
+    /// The reader can't outlive the load: assets reading the file after they are loaded keep an
+    /// [`AssetFile`] instead, see [`LoadContext::asset_file`].
+    pub fn take_reader(&mut self) -> Option<Box<Reader<'a>>> {
+        self.reader.take()
+    }
+
+    /// Opens the file at `path` from the asset source of the asset being loaded, to read it
+    /// progressively or only read parts of it. The asset is reloaded when the file changes.
+    pub async fn asset_reader<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<Box<Reader<'a>>, AssetIoError> {
+        self.read_paths.lock().push(path.as_ref().to_owned());
+        self.asset_io.read_path(path.as_ref()).await
+    }
+
+    /// Reads the bytes in `range` of the file at `path` from the asset source of the asset
+    /// being loaded. The asset is reloaded when the file changes.
+    pub async fn read_asset_range<P: AsRef<Path>>(
+        &self,
+        path: P,
+        range: Range<u64>,
+    ) -> Result<Vec<u8>, AssetIoError> {
+        self.read_paths.lock().push(path.as_ref().to_owned());
+        self.asset_io.read_range(path.as_ref(), range).await
+    }
+
+    /// Returns the file at `path` of the asset source of the asset being loaded, that the loaded
+    /// assets can keep to read parts of the file on demand, so that it is only partially
+    /// resident. The asset is reloaded when the file changes.
+    pub fn asset_file<P: AsRef<Path>>(&self, path: P) -> AssetFile {
+        self.read_paths.lock().push(path.as_ref().to_owned());
+        AssetFile {
+            asset_io: self.asset_io.clone(),
+            path: path.as_ref().to_owned(),
+        }
+    }
+
     pub fn get_asset_metas(&self) -> Vec<AssetMeta> {
         let mut asset_metas = Vec::new();
         for (label, asset) in self.labeled_assets.iter() {
@@ -7,8 +7,15 @@ use std::{
     path::{Path, PathBuf},
 };
 
-#[derive(Debug, Hash, Clone, Serialize, Deserialize)]
+/// The path of an asset: a file path, an optional label of an asset in the file, and an
+/// optional name of the asset source to load the file from.
+///
+/// Asset paths are parsed from strings like `"source://path/to/file.ext#label"`. Without a
+/// source, files are loaded from the default asset source, usually the asset folder.
+#[derive(Debug, Hash, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AssetPath<'a> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<Cow<'a, str>>,
     path: Cow<'a, Path>,
     label: Option<Cow<'a, str>>,
 }
@@ -17,6 +24,7 @@ impl<'a> AssetPath<'a> {
     #[inline]
     pub fn new_ref(path: &'a Path, label: Option<&'a str>) -> AssetPath<'a> {
         AssetPath {
+            source: None,
             path: Cow::Borrowed(path),
             label: label.map(Cow::Borrowed),
         }
@@ -25,11 +33,19 @@ impl<'a> AssetPath<'a> {
     #[inline]
     pub fn new(path: PathBuf, label: Option<String>) -> AssetPath<'a> {
         AssetPath {
+            source: None,
             path: Cow::Owned(path),
             label: label.map(Cow::Owned),
         }
     }
 
+    /// Returns this path in the asset source named `source`.
+    #[inline]
+    pub fn with_source(mut self, source: impl Into<Cow<'a, str>>) -> AssetPath<'a> {
+        self.source = Some(source.into());
+        self
+    }
+
     #[inline]
     pub fn get_id(&self) -> AssetPathId {
         AssetPathId::from(self)
@@ -40,6 +56,12 @@ impl<'a> AssetPath<'a> {
         self.label.as_ref().map(|label| label.as_ref())
     }
 
+    /// Returns the name of the asset source of this path, `None` for the default source.
+    #[inline]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_ref().map(|source| source.as_ref())
+    }
+
     #[inline]
     pub fn path(&self) -> &Path {
         &self.path
@@ -48,6 +70,10 @@ impl<'a> AssetPath<'a> {
     #[inline]
     pub fn to_owned(&self) -> AssetPath<'static> {
         AssetPath {
+            source: self
+                .source
+                .as_ref()
+                .map(|value| Cow::Owned(value.to_string())),
             path: Cow::Owned(self.path.to_path_buf()),
             label: self
                 .label
@@ -103,7 +129,35 @@ impl<'a> From<Option<&'a str>> for LabelId {
     }
 }
 
+impl SourcePathId {
+    /// The id of the assets loaded from `path` in the given asset source
+    pub(crate) fn new(source: Option<&str>, path: &Path) -> Self {
+        let mut hasher = get_hasher();
+        // the default source isn't hashed to keep the ids of its paths stable
+        if let Some(source) = source {
+            source.hash(&mut hasher);
+        }
+        path.hash(&mut hasher);
+        SourcePathId(hasher.finish())
+    }
+
+    /// The id of the assets loaded from `path` with the given serialized loader settings
+    pub(crate) fn with_settings(source: Option<&str>, path: &Path, settings: &str) -> Self {
+        let mut hasher = get_hasher();
+        if let Some(source) = source {
+            source.hash(&mut hasher);
+        }
+        path.hash(&mut hasher);
+        settings.hash(&mut hasher);
+        SourcePathId(hasher.finish())
+    }
+}
+
 impl AssetPathId {
+    pub(crate) fn new(source_path_id: SourcePathId, label_id: LabelId) -> Self {
+        AssetPathId(source_path_id, label_id)
+    }
+
     pub fn source_path_id(&self) -> SourcePathId {
         self.0
     }
@@ -125,7 +179,7 @@ where
     fn from(value: T) -> Self {
         let asset_path: AssetPath = value.into();
         AssetPathId(
-            SourcePathId::from(asset_path.path()),
+            SourcePathId::new(asset_path.source(), asset_path.path()),
             LabelId::from(asset_path.label()),
         )
     }
@@ -134,7 +188,7 @@ where
 impl<'a, 'b> From<&'a AssetPath<'b>> for AssetPathId {
     fn from(asset_path: &'a AssetPath<'b>) -> Self {
         AssetPathId(
-            SourcePathId::from(asset_path.path()),
+            SourcePathId::new(asset_path.source(), asset_path.path()),
             LabelId::from(asset_path.label()),
         )
     }
@@ -142,10 +196,15 @@ impl<'a, 'b> From<&'a AssetPath<'b>> for AssetPathId {
 
 impl<'a> From<&'a str> for AssetPath<'a> {
     fn from(asset_path: &'a str) -> Self {
+        let (source, asset_path) = match asset_path.split_once("://") {
+            Some((source, asset_path)) => (Some(source), asset_path),
+            None => (None, asset_path),
+        };
         let mut parts = asset_path.split('#');
         let path = Path::new(parts.next().expect("Path must be set."));
         let label = parts.next();
         AssetPath {
+            source: source.map(Cow::Borrowed),
             path: Cow::Borrowed(path),
             label: label.map(Cow::Borrowed),
         }
@@ -161,6 +220,7 @@ impl<'a> From<&'a String> for AssetPath<'a> {
 impl<'a> From<&'a Path> for AssetPath<'a> {
     fn from(path: &'a Path) -> Self {
         AssetPath {
+            source: None,
             path: Cow::Borrowed(path),
             label: None,
         }
@@ -170,8 +230,37 @@ impl<'a> From<&'a Path> for AssetPath<'a> {
 impl<'a> From<PathBuf> for AssetPath<'a> {
     fn from(path: PathBuf) -> Self {
         AssetPath {
+            source: None,
             path: Cow::Owned(path),
             label: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_asset_path() {
+        let path = AssetPath::from("models/cube.gltf#Mesh0");
+        assert_eq!(path.source(), None);
+        assert_eq!(path.path(), Path::new("models/cube.gltf"));
+        assert_eq!(path.label(), Some("Mesh0"));
+
+        let path = AssetPath::from("embedded://shaders/pbr.wgsl");
+        assert_eq!(path.source(), Some("embedded"));
+        assert_eq!(path.path(), Path::new("shaders/pbr.wgsl"));
+        assert_eq!(path.label(), None);
+
+        // the default source keeps the ids of paths without a source
+        assert_eq!(
+            AssetPathId::from("a.png").source_path_id(),
+            SourcePathId::from(Path::new("a.png"))
+        );
+        assert_ne!(
+            AssetPathId::from("memory://a.png"),
+            AssetPathId::from("a.png")
+        );
+    }
+}
@@ -0,0 +1,518 @@
+use crate::path::get_hasher;
+use anyhow::Result;
+use bevy_tasks::TaskPool;
+use bevy_utils::BoxedFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    hash::Hasher,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
+
+/// The extension of the files storing the import settings of an asset. The settings of
+/// `textures/grass.png` are stored in `textures/grass.png.meta`.
+pub const META_EXTENSION: &str = "meta";
+
+/// The name of the file listing the imported assets, at the root of the imported assets folder
+pub const IMPORTED_ASSET_INDEX: &str = "index.ron";
+
+/// Returns the path of the `.meta` file storing the import settings of the asset at `path`.
+pub fn get_meta_path(path: &Path) -> PathBuf {
+    let mut meta_path = path.as_os_str().to_owned();
+    meta_path.push(".");
+    meta_path.push(META_EXTENSION);
+    meta_path.into()
+}
+
+/// Transforms the source file of an asset into the file loaded at runtime, for example to
+/// compress a texture or to validate a shader.
+///
+/// The output of a processor is loaded by the [`AssetLoader`](crate::AssetLoader) handling the
+/// extension of the source file, so it must be in a format supported by that loader.
+pub trait Processor: Send + Sync + 'static {
+    /// Settings of the processor, read from the `settings` field of the `.meta` file of the
+    /// asset. When there is no `.meta` file, or it has no settings, the default settings are used.
+    type Settings: DeserializeOwned + Default + Send + Sync + 'static;
+
+    fn process<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        settings: &'a Self::Settings,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, anyhow::Error>>;
+
+    /// The extensions of the files this processor is used for, unless their `.meta` file
+    /// selects another processor.
+    fn extensions(&self) -> &[&str];
+
+    /// The name used to select this processor in `.meta` files.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// The version of the processor. Changing it processes the assets again.
+    fn version(&self) -> u32 {
+        0
+    }
+}
+
+/// The fields of a `.meta` file used to pick a processor
+#[derive(Deserialize, Default)]
+struct MetaHeader {
+    #[serde(default)]
+    processor: Option<String>,
+}
+
+/// The fields of a `.meta` file used by a processor
+#[derive(Deserialize)]
+struct ProcessorMeta<S: Default> {
+    #[serde(default)]
+    settings: S,
+}
+
+/// A [`Processor`] with its settings type erased
+pub(crate) trait ErasedProcessor: Send + Sync + 'static {
+    fn process<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        meta: Option<&'a str>,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, anyhow::Error>>;
+    fn extensions(&self) -> &[&str];
+    fn name(&self) -> &str;
+    fn version(&self) -> u32;
+}
+
+impl<P: Processor> ErasedProcessor for P {
+    fn process<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        meta: Option<&'a str>,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, anyhow::Error>> {
+        Box::pin(async move {
+            let settings = match meta {
+                Some(meta) => ron::de::from_str::<ProcessorMeta<P::Settings>>(meta)?.settings,
+                None => Default::default(),
+            };
+            Processor::process(self, bytes, &settings, path).await
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        Processor::extensions(self)
+    }
+
+    fn name(&self) -> &str {
+        Processor::name(self)
+    }
+
+    fn version(&self) -> u32 {
+        Processor::version(self)
+    }
+}
+
+/// An asset of the imported assets folder
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportedAsset {
+    /// Hash of the source file, its `.meta` file and the processor used
+    pub hash: u64,
+    /// Path of the processed file, relative to the imported assets folder
+    pub file: PathBuf,
+}
+
+/// The index of the imported assets folder, mapping the path of each source asset to its
+/// processed file.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImportedAssetIndex {
+    pub assets: BTreeMap<PathBuf, ImportedAsset>,
+}
+
+/// Errors that occur while processing assets
+#[derive(Error, Debug)]
+pub enum AssetProcessorError {
+    #[error("encountered an io error while processing assets: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid meta file for {path:?}: {error}")]
+    InvalidMeta { path: PathBuf, error: ron::Error },
+    #[error("unknown processor {name:?} in the meta file of {path:?}")]
+    UnknownProcessor { path: PathBuf, name: String },
+    #[error("failed to process {path:?}: {error}")]
+    ProcessorFailed { path: PathBuf, error: anyhow::Error },
+    #[error("invalid imported asset index: {0}")]
+    InvalidIndex(ron::Error),
+}
+
+/// The outcome of [`AssetProcessor::process`]
+#[derive(Debug, Default)]
+pub struct ProcessSummary {
+    /// Assets that were processed
+    pub processed: Vec<PathBuf>,
+    /// Assets whose processed file was already up to date
+    pub up_to_date: Vec<PathBuf>,
+    /// Assets that failed to process. If a previous version of the asset was processed
+    /// successfully, it is kept in the imported assets folder.
+    pub failed: Vec<(PathBuf, AssetProcessorError)>,
+}
+
+/// Processes the assets of a source folder into an imported assets folder.
+///
+/// Each source file is processed by the [`Processor`] selected in its `.meta` file, or by the
+/// processor registered for its extension. Files without a processor are copied as is.
+/// Processed files are named after a hash of their inputs, so only assets whose source file,
+/// settings or processor changed are processed again.
+///
+/// At runtime, the imported assets are loaded through a
+/// [`ProcessedAssetIo`](crate::ProcessedAssetIo).
+///
+/// `bevy_render` registers a processor validating shaders. Texture compression and mesh
+/// optimization need encoders that Bevy doesn't depend on, so no processor is provided for them:
+/// they can be added by plugins with [`AddAsset::add_asset_processor`](crate::AddAsset).
+///
+/// A `.meta` file looks like this:
+/// ```ron
+/// (
+///     processor: Some("shader_validation"),
+///     settings: (
+///         validate: true,
+///     ),
+/// )
+/// ```
+pub struct AssetProcessor {
+    source_folder: PathBuf,
+    imported_folder: PathBuf,
+    processors: Vec<Arc<dyn ErasedProcessor>>,
+}
+
+impl AssetProcessor {
+    pub fn new(source_folder: impl Into<PathBuf>, imported_folder: impl Into<PathBuf>) -> Self {
+        AssetProcessor {
+            source_folder: source_folder.into(),
+            imported_folder: imported_folder.into(),
+            processors: Vec::new(),
+        }
+    }
+
+    pub fn add_processor<P: Processor>(&mut self, processor: P) {
+        self.processors.push(Arc::new(processor));
+    }
+
+    pub fn source_folder(&self) -> &Path {
+        &self.source_folder
+    }
+
+    pub fn imported_folder(&self) -> &Path {
+        &self.imported_folder
+    }
+
+    fn get_processor_by_name(&self, name: &str) -> Option<Arc<dyn ErasedProcessor>> {
+        self.processors
+            .iter()
+            .rev()
+            .find(|processor| processor.name() == name)
+            .cloned()
+    }
+
+    fn get_processor_by_extension(&self, path: &Path) -> Option<Arc<dyn ErasedProcessor>> {
+        let file_name = path.file_name()?.to_str()?.to_lowercase();
+        let mut ext = file_name.as_str();
+        while let Some(index) = ext.find('.') {
+            ext = &ext[index + 1..];
+            if let Some(processor) = self
+                .processors
+                .iter()
+                .rev()
+                .find(|processor| processor.extensions().contains(&ext))
+            {
+                return Some(processor.clone());
+            }
+        }
+        None
+    }
+
+    /// Reads the index of the imported assets folder, or returns an empty index if there is none.
+    pub fn read_index(&self) -> Result<ImportedAssetIndex, AssetProcessorError> {
+        match fs::read_to_string(self.imported_folder.join(IMPORTED_ASSET_INDEX)) {
+            Ok(index) => ron::de::from_str(&index).map_err(AssetProcessorError::InvalidIndex),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Default::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Processes all assets of the source folder that changed since the last run, and removes
+    /// processed files of assets that don't exist anymore.
+    ///
+    /// Files are processed in parallel on the given task pool.
+    pub fn process(&self, task_pool: &TaskPool) -> Result<ProcessSummary, AssetProcessorError> {
+        let previous_index = self.read_index()?;
+        let mut source_files = Vec::new();
+        collect_source_files(&self.source_folder, Path::new(""), &mut source_files)?;
+        fs::create_dir_all(&self.imported_folder)?;
+
+        let results = task_pool.scope(|scope| {
+            for (i, path) in source_files.iter().enumerate() {
+                let previous = previous_index.assets.get(path);
+                scope.spawn(async move { (i, self.process_file(path, previous).await) });
+            }
+        });
+
+        let mut summary = ProcessSummary::default();
+        let mut index = ImportedAssetIndex::default();
+        for (i, result) in results {
+            let path = &source_files[i];
+            let previous = previous_index.assets.get(path);
+            match result {
+                Ok((imported, processed)) => {
+                    index.assets.insert(path.clone(), imported);
+                    if processed {
+                        summary.processed.push(path.clone());
+                    } else {
+                        summary.up_to_date.push(path.clone());
+                    }
+                }
+                Err(err) => {
+                    if let Some(previous) = previous {
+                        index.assets.insert(path.clone(), previous.clone());
+                    }
+                    summary.failed.push((path.clone(), err));
+                }
+            }
+        }
+
+        let serialized_index = ron::ser::to_string_pretty(&index, Default::default())
+            .map_err(AssetProcessorError::InvalidIndex)?;
+        fs::write(
+            self.imported_folder.join(IMPORTED_ASSET_INDEX),
+            serialized_index,
+        )?;
+
+        // remove processed files that are not referenced anymore
+        for previous in previous_index.assets.values() {
+            if !index
+                .assets
+                .values()
+                .any(|asset| asset.file == previous.file)
+            {
+                let _ = fs::remove_file(self.imported_folder.join(&previous.file));
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Processes a single file, unless `previous` is up to date. Returns the imported asset and
+    /// whether it was processed.
+    async fn process_file(
+        &self,
+        path: &Path,
+        previous: Option<&ImportedAsset>,
+    ) -> Result<(ImportedAsset, bool), AssetProcessorError> {
+        let bytes = fs::read(self.source_folder.join(path))?;
+        let meta = match fs::read_to_string(self.source_folder.join(get_meta_path(path))) {
+            Ok(meta) => Some(meta),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        let processor = match &meta {
+            Some(meta) => {
+                let header: MetaHeader =
+                    ron::de::from_str(meta).map_err(|error| AssetProcessorError::InvalidMeta {
+                        path: path.to_owned(),
+                        error,
+                    })?;
+                match header.processor {
+                    Some(name) => Some(self.get_processor_by_name(&name).ok_or(
+                        AssetProcessorError::UnknownProcessor {
+                            path: path.to_owned(),
+                            name,
+                        },
+                    )?),
+                    None => self.get_processor_by_extension(path),
+                }
+            }
+            None => self.get_processor_by_extension(path),
+        };
+
+        let mut hasher = get_hasher();
+        hasher.write(&bytes);
+        if let Some(meta) = &meta {
+            hasher.write(meta.as_bytes());
+        }
+        if let Some(processor) = &processor {
+            hasher.write(processor.name().as_bytes());
+            hasher.write_u32(processor.version());
+        }
+        let hash = hasher.finish();
+
+        let mut file = PathBuf::from(format!("{:016x}", hash));
+        if let Some(extension) = path.extension() {
+            file.set_extension(extension);
+        }
+        let imported = ImportedAsset { hash, file };
+        if previous == Some(&imported) && self.imported_folder.join(&imported.file).exists() {
+            return Ok((imported, false));
+        }
+
+        let processed = match &processor {
+            Some(processor) => processor
+                .process(&bytes, meta.as_deref(), path)
+                .await
+                .map_err(|error| AssetProcessorError::ProcessorFailed {
+                    path: path.to_owned(),
+                    error,
+                })?,
+            None => bytes,
+        };
+        fs::write(self.imported_folder.join(&imported.file), processed)?;
+        Ok((imported, true))
+    }
+}
+
+/// Collects the paths of all files in `folder`, except `.meta` files, relative to `root`.
+fn collect_source_files(
+    root: &Path,
+    folder: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), io::Error> {
+    for entry in fs::read_dir(root.join(folder))? {
+        let entry = entry?;
+        let path = folder.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            collect_source_files(root, &path, files)?;
+        } else if path.extension().map_or(true, |ext| ext != META_EXTENSION) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct UppercaseProcessor;
+
+    #[derive(Deserialize, Default)]
+    struct UppercaseSettings {
+        suffix: String,
+    }
+
+    impl Processor for UppercaseProcessor {
+        type Settings = UppercaseSettings;
+
+        fn process<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            settings: &'a Self::Settings,
+            _path: &'a Path,
+        ) -> BoxedFuture<'a, Result<Vec<u8>, anyhow::Error>> {
+            Box::pin(async move {
+                let text = String::from_utf8(bytes.to_vec())?;
+                if text.is_empty() {
+                    anyhow::bail!("empty file");
+                }
+                Ok(format!("{}{}", text.to_uppercase(), settings.suffix).into_bytes())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn name(&self) -> &str {
+            "uppercase"
+        }
+    }
+
+    fn read_imported(processor: &AssetProcessor, path: &str) -> String {
+        let index = processor.read_index().unwrap();
+        let imported = &index.assets[Path::new(path)];
+        fs::read_to_string(processor.imported_folder().join(&imported.file)).unwrap()
+    }
+
+    #[test]
+    fn meta_path() {
+        assert_eq!(
+            get_meta_path(Path::new("textures/grass.png")),
+            Path::new("textures/grass.png.meta")
+        );
+    }
+
+    #[test]
+    fn process_folder() {
+        let source = tempfile::tempdir().unwrap();
+        let imported = tempfile::tempdir().unwrap();
+        fs::create_dir(source.path().join("sub")).unwrap();
+        fs::write(source.path().join("a.txt"), "hello").unwrap();
+        fs::write(source.path().join("sub/b.txt"), "world").unwrap();
+        fs::write(
+            source.path().join("sub/b.txt.meta"),
+            "(settings: (suffix: \"!\"))",
+        )
+        .unwrap();
+        fs::write(source.path().join("c.bin"), "raw").unwrap();
+
+        let mut processor = AssetProcessor::new(source.path(), imported.path());
+        processor.add_processor(UppercaseProcessor);
+        let task_pool = TaskPool::new();
+
+        let summary = processor.process(&task_pool).unwrap();
+        assert_eq!(summary.processed.len(), 3);
+        assert!(summary.failed.is_empty());
+        assert_eq!(read_imported(&processor, "a.txt"), "HELLO");
+        assert_eq!(read_imported(&processor, "sub/b.txt"), "WORLD!");
+        assert_eq!(read_imported(&processor, "c.bin"), "raw");
+
+        // nothing changed
+        let summary = processor.process(&task_pool).unwrap();
+        assert!(summary.processed.is_empty());
+        assert_eq!(summary.up_to_date.len(), 3);
+
+        // changing settings processes the asset again, and removes the old processed file
+        let old_file = processor.read_index().unwrap().assets[Path::new("sub/b.txt")]
+            .file
+            .clone();
+        fs::write(
+            source.path().join("sub/b.txt.meta"),
+            "(processor: Some(\"uppercase\"), settings: (suffix: \"?\"))",
+        )
+        .unwrap();
+        let summary = processor.process(&task_pool).unwrap();
+        assert_eq!(summary.processed, vec![PathBuf::from("sub/b.txt")]);
+        assert_eq!(read_imported(&processor, "sub/b.txt"), "WORLD?");
+        assert!(!imported.path().join(old_file).exists());
+
+        // a failing asset keeps its previous processed file
+        fs::write(source.path().join("a.txt"), "").unwrap();
+        let summary = processor.process(&task_pool).unwrap();
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(read_imported(&processor, "a.txt"), "HELLO");
+
+        // unknown processors are reported
+        fs::write(
+            source.path().join("c.bin.meta"),
+            "(processor: Some(\"unknown\"))",
+        )
+        .unwrap();
+        let summary = processor.process(&task_pool).unwrap();
+        assert!(summary
+            .failed
+            .iter()
+            .any(|(path, err)| path == Path::new("c.bin")
+                && matches!(err, AssetProcessorError::UnknownProcessor { .. })));
+
+        // removed assets are removed from the index
+        fs::remove_file(source.path().join("sub/b.txt")).unwrap();
+        processor.process(&task_pool).unwrap();
+        assert!(!processor
+            .read_index()
+            .unwrap()
+            .assets
+            .contains_key(Path::new("sub/b.txt")));
+    }
+}
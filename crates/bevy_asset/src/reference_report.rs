@@ -0,0 +1,347 @@
+use crate::{AssetPath, AssetServer, HandleId, ReflectHandle};
+use bevy_ecs::{
+    entity::Entity,
+    reflect::{ReflectComponent, ReflectResource},
+    world::World,
+};
+use bevy_reflect::{Reflect, ReflectRef, TypeRegistry, TypeRegistryArc};
+use bevy_utils::HashMap;
+use std::fmt;
+
+/// A component or resource holding a strong handle to an asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandleHolder {
+    Component {
+        entity: Entity,
+        component: &'static str,
+    },
+    Resource {
+        resource: &'static str,
+    },
+}
+
+impl fmt::Display for HandleHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandleHolder::Component { entity, component } => {
+                write!(f, "component {} of entity {:?}", component, entity)
+            }
+            HandleHolder::Resource { resource } => write!(f, "resource {}", resource),
+        }
+    }
+}
+
+/// The strong handles to an asset, and who holds them
+#[derive(Debug)]
+pub struct AssetReferences {
+    pub handle_id: HandleId,
+    /// The path of the asset, if it was loaded by the [`AssetServer`]
+    pub path: Option<AssetPath<'static>>,
+    /// The number of strong handles to the asset, as given by [`AssetServer::get_ref_count`]
+    pub ref_count: usize,
+    /// The components and resources holding a strong handle to the asset, once per handle
+    pub holders: Vec<HandleHolder>,
+}
+
+impl AssetReferences {
+    /// The number of strong handles that weren't found in the reflected components and
+    /// resources of the world: `ref_count` minus the number of `holders`. They are held by
+    /// other assets, by the `Local`s of systems, by types not registered for reflection, or
+    /// leaked, and the report can't tell which.
+    pub fn untracked(&self) -> usize {
+        self.ref_count.saturating_sub(self.holders.len())
+    }
+}
+
+/// A report of the assets kept alive by strong handles, and of the entities, components and
+/// resources holding these handles.
+///
+/// The report is a snapshot of the world rather than a record of where each strong handle was
+/// cloned: handles are found by walking the reflected components and resources of the world, so
+/// only types registered with [`ReflectComponent`] or [`ReflectResource`] type data are
+/// inspected. Handles held by non-reflected types, by the `Local`s of systems or by other assets
+/// can't be found, and are only counted by [`AssetReferences::untracked`], so that leaks outside
+/// of reflected data still show up in the report.
+///
+/// This is meant for debugging, for example to find the assets still referenced after a level
+/// is unloaded:
+/// ```no_run
+/// # use bevy_asset::AssetReferenceReport;
+/// # use bevy_ecs::world::World;
+/// fn unload_level(world: &mut World) {
+///     // despawn the level entities, then
+///     let report = AssetReferenceReport::new(world);
+///     bevy_log::info!("assets still referenced:\n{}", report);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct AssetReferenceReport {
+    assets: Vec<AssetReferences>,
+}
+
+impl AssetReferenceReport {
+    /// Builds the report of the assets of the [`AssetServer`] of `world`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `world` doesn't have the [`AssetServer`] and [`TypeRegistryArc`] resources.
+    pub fn new(world: &World) -> Self {
+        let asset_server = world
+            .get_resource::<AssetServer>()
+            .expect("the world should have an `AssetServer`");
+        let type_registry = world
+            .get_resource::<TypeRegistryArc>()
+            .expect("the world should have a `TypeRegistryArc`");
+        let type_registry = type_registry.read();
+
+        // apply the pending reference count changes
+        asset_server.mark_unused_assets();
+        let mut holders = HashMap::<HandleId, Vec<HandleHolder>>::default();
+
+        for archetype in world.archetypes().iter() {
+            for component_id in archetype.components() {
+                let registration = world
+                    .components()
+                    .get_info(component_id)
+                    .and_then(|info| info.type_id())
+                    .and_then(|type_id| type_registry.get(type_id));
+                let registration = match registration {
+                    Some(registration) => registration,
+                    None => continue,
+                };
+                let reflect_component = match registration.data::<ReflectComponent>() {
+                    Some(reflect_component) => reflect_component,
+                    None => continue,
+                };
+                for &entity in archetype.entities() {
+                    if let Some(component) = reflect_component.reflect_component(world, entity) {
+                        let holder = HandleHolder::Component {
+                            entity,
+                            component: registration.name(),
+                        };
+                        find_strong_handles(component, &type_registry, &mut |handle_id| {
+                            holders.entry(handle_id).or_default().push(holder.clone());
+                        });
+                    }
+                }
+            }
+        }
+
+        for component_id in world.archetypes().resource().unique_components().indices() {
+            let registration = world
+                .components()
+                .get_info(component_id)
+                .and_then(|info| info.type_id())
+                .and_then(|type_id| type_registry.get(type_id));
+            let resource = registration.and_then(|registration| {
+                let reflect_resource = registration.data::<ReflectResource>()?;
+                Some((registration, reflect_resource.reflect_resource(world)?))
+            });
+            if let Some((registration, resource)) = resource {
+                let holder = HandleHolder::Resource {
+                    resource: registration.name(),
+                };
+                find_strong_handles(resource, &type_registry, &mut |handle_id| {
+                    holders.entry(handle_id).or_default().push(holder.clone());
+                });
+            }
+        }
+
+        let ref_counts = asset_server.server.asset_ref_counter.ref_counts.read();
+        let mut assets: Vec<_> = ref_counts
+            .iter()
+            .filter(|(_, ref_count)| **ref_count > 0)
+            .map(|(handle_id, ref_count)| AssetReferences {
+                handle_id: *handle_id,
+                path: asset_server
+                    .get_handle_path(*handle_id)
+                    .map(|path| path.to_owned()),
+                ref_count: *ref_count,
+                holders: holders.remove(handle_id).unwrap_or_default(),
+            })
+            .collect();
+        assets.sort_by_key(|asset| asset.handle_id);
+        AssetReferenceReport { assets }
+    }
+
+    /// Returns the references to the asset of `handle`, if it has strong handles
+    pub fn get<H: Into<HandleId>>(&self, handle: H) -> Option<&AssetReferences> {
+        let handle_id = handle.into();
+        self.assets
+            .iter()
+            .find(|asset| asset.handle_id == handle_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &AssetReferences> {
+        self.assets.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+impl fmt::Display for AssetReferenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for asset in self.assets.iter() {
+            match &asset.path {
+                Some(path) => write!(f, "{:?}", path)?,
+                None => write!(f, "{:?}", asset.handle_id)?,
+            }
+            writeln!(f, ": {} strong handle(s)", asset.ref_count)?;
+            for holder in asset.holders.iter() {
+                writeln!(f, "    held by {}", holder)?;
+            }
+            let untracked = asset.untracked();
+            if untracked > 0 {
+                writeln!(
+                    f,
+                    "    {} held outside of reflected components and resources",
+                    untracked
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Calls `found` with the id of each strong handle in `value` and its fields
+fn find_strong_handles(
+    value: &dyn Reflect,
+    type_registry: &TypeRegistry,
+    found: &mut dyn FnMut(HandleId),
+) {
+    if let Some(reflect_handle) =
+        type_registry.get_type_data::<ReflectHandle>(value.any().type_id())
+    {
+        if let Some(handle_id) = reflect_handle.strong_handle_id(value) {
+            found(handle_id);
+        }
+        return;
+    }
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for field in value.iter_fields() {
+                find_strong_handles(field, type_registry, found);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for field in value.iter_fields() {
+                find_strong_handles(field, type_registry, found);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for field in value.iter_fields() {
+                find_strong_handles(field, type_registry, found);
+            }
+        }
+        ReflectRef::List(value) => {
+            for item in value.iter() {
+                find_strong_handles(item, type_registry, found);
+            }
+        }
+        ReflectRef::Map(value) => {
+            for (key, value) in value.iter() {
+                find_strong_handles(key, type_registry, found);
+                find_strong_handles(value, type_registry, found);
+            }
+        }
+        ReflectRef::Value(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Handle, MemoryAssetIo};
+    use bevy_ecs::prelude::*;
+    use bevy_reflect::TypeUuid;
+    use bevy_tasks::TaskPool;
+
+    #[derive(Debug, TypeUuid)]
+    #[uuid = "f7c6e8b4-b5d2-4a7e-9a79-2c2b0e6d3c11"]
+    struct TestAsset;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Holder {
+        handles: Vec<Handle<TestAsset>>,
+    }
+
+    #[derive(Reflect, Default)]
+    #[reflect(Resource)]
+    struct HolderResource {
+        handle: Handle<TestAsset>,
+    }
+
+    #[test]
+    fn find_handle_holders() {
+        let mut world = World::new();
+        let asset_server = AssetServer::new(MemoryAssetIo::default(), TaskPool::new());
+        let mut assets = asset_server.register_asset_type::<TestAsset>();
+        let type_registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Handle<TestAsset>>();
+            type_registry.register::<Holder>();
+            type_registry.register::<HolderResource>();
+        }
+        world.insert_resource(asset_server);
+        world.insert_resource(type_registry);
+
+        let a = assets.add(TestAsset);
+        let b = assets.add(TestAsset);
+        let entity = world
+            .spawn()
+            .insert(Holder {
+                handles: vec![a.clone(), a.clone_weak(), b.clone()],
+            })
+            .id();
+        let other = world.spawn().insert(b.clone()).id();
+        world.insert_resource(HolderResource { handle: b.clone() });
+        let leaked = std::mem::ManuallyDrop::new(a.clone());
+
+        let report = AssetReferenceReport::new(&world);
+        let a_references = report.get(&a).unwrap();
+        // held by `a`, the holder and the leaked handle
+        assert_eq!(a_references.ref_count, 3);
+        assert_eq!(
+            a_references.holders,
+            vec![HandleHolder::Component {
+                entity,
+                component: std::any::type_name::<Holder>(),
+            }]
+        );
+        assert_eq!(a_references.untracked(), 2);
+        let b_references = report.get(&b).unwrap();
+        assert_eq!(b_references.ref_count, 4);
+        assert_eq!(b_references.holders.len(), 3);
+        assert!(b_references.holders.contains(&HandleHolder::Component {
+            entity: other,
+            component: std::any::type_name::<Handle<TestAsset>>(),
+        }));
+        assert!(b_references.holders.contains(&HandleHolder::Resource {
+            resource: std::any::type_name::<HolderResource>(),
+        }));
+        assert_eq!(b_references.untracked(), 1);
+        assert!(report.to_string().contains("held by component"));
+        assert!(report
+            .to_string()
+            .contains("2 held outside of reflected components and resources"));
+
+        drop((a, b));
+        world.despawn(entity);
+        world.despawn(other);
+        world.remove_resource::<HolderResource>();
+        let report = AssetReferenceReport::new(&world);
+        assert_eq!(report.len(), 1);
+        let leaked_references = report.iter().next().unwrap();
+        assert_eq!(leaked_references.handle_id, leaked.id);
+        assert!(leaked_references.holders.is_empty());
+        assert_eq!(leaked_references.untracked(), 1);
+    }
+}
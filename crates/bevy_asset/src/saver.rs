@@ -0,0 +1,40 @@
+use crate::{Asset, AssetDynamic, AssetServer, Assets};
+use anyhow::Result;
+use bevy_ecs::system::Res;
+use std::path::Path;
+
+/// Serializes assets of type [`AssetSaver::Asset`], so that they can be written to the asset
+/// folder with [`AssetServer::save`](crate::AssetServer::save).
+///
+/// The output of a saver should be readable by the [`AssetLoader`](crate::AssetLoader) of the
+/// same extensions.
+pub trait AssetSaver: Send + Sync + 'static {
+    type Asset: Asset;
+
+    fn save(&self, asset: &Self::Asset, path: &Path) -> Result<Vec<u8>, anyhow::Error>;
+    fn extensions(&self) -> &[&str];
+}
+
+/// An [`AssetSaver`] with its asset type erased
+pub(crate) trait ErasedAssetSaver: Send + Sync + 'static {
+    fn save(&self, asset: &dyn AssetDynamic, path: &Path) -> Result<Vec<u8>, anyhow::Error>;
+    fn extensions(&self) -> &[&str];
+}
+
+impl<S: AssetSaver> ErasedAssetSaver for S {
+    fn save(&self, asset: &dyn AssetDynamic, path: &Path) -> Result<Vec<u8>, anyhow::Error> {
+        let asset = asset
+            .downcast_ref::<S::Asset>()
+            .expect("Asset should be of the type of the saver.");
+        AssetSaver::save(self, asset, path)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        AssetSaver::extensions(self)
+    }
+}
+
+/// Saves the assets of type `T` queued with [`AssetServer::save`](crate::AssetServer::save).
+pub fn save_assets_system<T: Asset>(asset_server: Res<AssetServer>, assets: Res<Assets<T>>) {
+    asset_server.save_pending_assets(&assets);
+}
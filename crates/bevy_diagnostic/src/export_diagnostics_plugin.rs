@@ -0,0 +1,459 @@
+use super::{DiagnosticId, Diagnostics};
+use bevy_app::{prelude::*, AppExit};
+use bevy_ecs::{
+    event::EventReader,
+    system::{Res, ResMut},
+};
+use bevy_log::{error, info, warn};
+use bevy_utils::{HashMap, Instant};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+/// The file format used by the [DiagnosticsExportPlugin]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticsExportFormat {
+    /// Comma separated values with a `frame,time,diagnostic,value` header
+    Csv,
+    /// One JSON object per line, with `frame`, `time`, `diagnostic` and `value` keys
+    JsonLines,
+}
+
+/// An App Plugin that streams every diagnostic measurement to a file.
+///
+/// Each measurement is written with the frame it was exported in and the time in seconds
+/// since the plugin was built. Optionally, a [DiagnosticsSummarySettings] can be provided to
+/// write a summary of the run when the app exits, and compare it against a baseline.
+pub struct DiagnosticsExportPlugin {
+    pub path: PathBuf,
+    pub format: DiagnosticsExportFormat,
+    pub filter: Option<Vec<DiagnosticId>>,
+    pub summary: Option<DiagnosticsSummarySettings>,
+}
+
+impl Default for DiagnosticsExportPlugin {
+    fn default() -> Self {
+        DiagnosticsExportPlugin {
+            path: PathBuf::from("diagnostics.csv"),
+            format: DiagnosticsExportFormat::Csv,
+            filter: None,
+            summary: None,
+        }
+    }
+}
+
+/// Configures the summary written by the [DiagnosticsExportPlugin] when the app exits.
+///
+/// If a `baseline` is set, the summary is compared against it using `checks`. When any check
+/// fails, the regressions are logged and the process exits with a non-zero status code.
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticsSummarySettings {
+    pub path: PathBuf,
+    pub baseline: Option<PathBuf>,
+    pub checks: Vec<RegressionCheck>,
+}
+
+/// A comparison between the average of a diagnostic and its baseline average
+#[derive(Debug, Clone)]
+pub struct RegressionCheck {
+    pub id: DiagnosticId,
+    /// The relative difference to the baseline that is tolerated, `0.1` meaning 10%
+    pub tolerance: f64,
+    /// If set, a decrease is a regression. Otherwise an increase is.
+    pub higher_is_better: bool,
+}
+
+impl RegressionCheck {
+    pub fn new(id: DiagnosticId) -> Self {
+        RegressionCheck {
+            id,
+            tolerance: 0.1,
+            higher_is_better: false,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn higher_is_better(mut self) -> Self {
+        self.higher_is_better = true;
+        self
+    }
+
+    /// Returns a description of the regression if `current` regressed compared to `baseline`
+    pub fn check(
+        &self,
+        name: &str,
+        current: &DiagnosticSummary,
+        baseline: &DiagnosticSummary,
+    ) -> Option<String> {
+        let change = if baseline.mean == 0.0 {
+            0.0
+        } else {
+            (current.mean - baseline.mean) / baseline.mean.abs()
+        };
+        let regressed = if self.higher_is_better {
+            change < -self.tolerance
+        } else {
+            change > self.tolerance
+        };
+        if regressed {
+            Some(format!(
+                "{}: average went from {:.6} to {:.6} ({:+.2}%, tolerance {:.2}%)",
+                name,
+                baseline.mean,
+                current.mean,
+                change * 100.0,
+                self.tolerance * 100.0
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Aggregated measurements of a single diagnostic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticSummary {
+    pub count: u64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl DiagnosticSummary {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.mean += (value - self.mean) / self.count as f64;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+}
+
+impl Default for DiagnosticSummary {
+    fn default() -> Self {
+        DiagnosticSummary {
+            count: 0,
+            mean: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+/// The summary of a run, keyed by diagnostic name. This is also the format of baseline files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticsSummary {
+    pub frames: u64,
+    pub diagnostics: BTreeMap<String, DiagnosticSummary>,
+}
+
+impl DiagnosticsSummary {
+    /// Compares this summary against `baseline`, returning a description of every regression.
+    ///
+    /// Checks whose diagnostic is missing from either summary are skipped with a warning.
+    pub fn regressions(
+        &self,
+        baseline: &DiagnosticsSummary,
+        checks: &[(String, RegressionCheck)],
+    ) -> Vec<String> {
+        checks
+            .iter()
+            .filter_map(|(name, check)| {
+                match (self.diagnostics.get(name), baseline.diagnostics.get(name)) {
+                    (Some(current), Some(baseline)) => check.check(name, current, baseline),
+                    _ => {
+                        warn!(
+                            "Diagnostic {:?} is missing from the summary or the baseline, skipping its regression check",
+                            name
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// State used by the [DiagnosticsExportPlugin]
+struct DiagnosticsExportState {
+    writer: BufWriter<File>,
+    format: DiagnosticsExportFormat,
+    filter: Option<Vec<DiagnosticId>>,
+    summary_settings: Option<DiagnosticsSummarySettings>,
+    start: Instant,
+    frame: u64,
+    last_exported: HashMap<DiagnosticId, Instant>,
+    summaries: HashMap<DiagnosticId, DiagnosticSummary>,
+    failed: bool,
+}
+
+impl Plugin for DiagnosticsExportPlugin {
+    fn build(&self, app: &mut App) {
+        let file = File::create(&self.path).unwrap_or_else(|err| {
+            panic!(
+                "error creating diagnostics export file {:?}: {}",
+                self.path, err
+            )
+        });
+        let mut state = DiagnosticsExportState {
+            writer: BufWriter::new(file),
+            format: self.format,
+            filter: self.filter.clone(),
+            summary_settings: self.summary.clone(),
+            start: Instant::now(),
+            frame: 0,
+            last_exported: Default::default(),
+            summaries: Default::default(),
+            failed: false,
+        };
+        if self.format == DiagnosticsExportFormat::Csv {
+            let result = writeln!(state.writer, "frame,time,diagnostic,value");
+            state.handle_result(result);
+        }
+        app.insert_resource(state)
+            .add_system_to_stage(CoreStage::Last, Self::export_diagnostics_system);
+    }
+}
+
+impl DiagnosticsExportState {
+    fn handle_result(&mut self, result: std::io::Result<()>) {
+        if let Err(err) = result {
+            if !self.failed {
+                error!("failed to export diagnostics: {}", err);
+                self.failed = true;
+            }
+        }
+    }
+
+    /// Writes the summary of the run and exits the process if it regressed from the baseline
+    fn finish(&self, settings: &DiagnosticsSummarySettings, diagnostics: &Diagnostics) {
+        let summary = DiagnosticsSummary {
+            frames: self.frame,
+            diagnostics: self
+                .summaries
+                .iter()
+                .filter(|(_, summary)| summary.count > 0)
+                .filter_map(|(id, summary)| {
+                    diagnostics
+                        .get(*id)
+                        .map(|diagnostic| (diagnostic.name.to_string(), summary.clone()))
+                })
+                .collect(),
+        };
+        match ron::ser::to_string_pretty(&summary, Default::default()) {
+            Ok(serialized) => {
+                if let Err(err) = std::fs::write(&settings.path, serialized) {
+                    error!(
+                        "failed to write diagnostics summary {:?}: {}",
+                        settings.path, err
+                    );
+                }
+            }
+            Err(err) => error!("failed to serialize diagnostics summary: {}", err),
+        }
+
+        let baseline_path = match settings.baseline {
+            Some(ref baseline_path) => baseline_path,
+            None => return,
+        };
+        let baseline: DiagnosticsSummary = match std::fs::read_to_string(baseline_path)
+            .map_err(|err| err.to_string())
+            .and_then(|baseline| ron::from_str(&baseline).map_err(|err| err.to_string()))
+        {
+            Ok(baseline) => baseline,
+            Err(err) => {
+                error!(
+                    "failed to read diagnostics baseline {:?}: {}",
+                    baseline_path, err
+                );
+                std::process::exit(1);
+            }
+        };
+        let checks: Vec<_> = settings
+            .checks
+            .iter()
+            .filter_map(|check| {
+                diagnostics
+                    .get(check.id)
+                    .map(|diagnostic| (diagnostic.name.to_string(), check.clone()))
+            })
+            .collect();
+        let regressions = summary.regressions(&baseline, &checks);
+        if regressions.is_empty() {
+            info!(
+                "No diagnostics regressions compared to baseline {:?}",
+                baseline_path
+            );
+        } else {
+            for regression in &regressions {
+                error!("Diagnostics regression: {}", regression);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+impl DiagnosticsExportPlugin {
+    pub fn filtered(filter: Vec<DiagnosticId>) -> Self {
+        DiagnosticsExportPlugin {
+            filter: Some(filter),
+            ..Default::default()
+        }
+    }
+
+    fn export_diagnostics_system(
+        mut state: ResMut<DiagnosticsExportState>,
+        diagnostics: Res<Diagnostics>,
+        mut app_exit_events: EventReader<AppExit>,
+    ) {
+        let state = &mut *state;
+        state.frame += 1;
+        let exported: Vec<_> = match state.filter {
+            Some(ref filter) => filter
+                .iter()
+                .filter_map(|id| diagnostics.get(*id))
+                .collect(),
+            None => diagnostics.iter().collect(),
+        };
+        for diagnostic in exported {
+            let last_exported = state.last_exported.get(&diagnostic.id).copied();
+            // measurements are stored newest first
+            let mut new_measurements: Vec<_> = diagnostic
+                .measurements()
+                .take_while(|measurement| Some(measurement.time) > last_exported)
+                .collect();
+            new_measurements.reverse();
+            if let Some(newest) = new_measurements.last() {
+                state.last_exported.insert(diagnostic.id, newest.time);
+            }
+
+            let summary = state.summaries.entry(diagnostic.id).or_default();
+            for measurement in &new_measurements {
+                summary.add(measurement.value);
+            }
+
+            for measurement in new_measurements {
+                let time = measurement
+                    .time
+                    .saturating_duration_since(state.start)
+                    .as_secs_f64();
+                let result = match state.format {
+                    DiagnosticsExportFormat::Csv => writeln!(
+                        state.writer,
+                        "{},{:.6},{},{}",
+                        state.frame,
+                        time,
+                        csv_escape(&diagnostic.name),
+                        measurement.value
+                    ),
+                    DiagnosticsExportFormat::JsonLines => writeln!(
+                        state.writer,
+                        "{{\"frame\":{},\"time\":{:.6},\"diagnostic\":{},\"value\":{}}}",
+                        state.frame,
+                        time,
+                        json_escape(&diagnostic.name),
+                        json_number(measurement.value)
+                    ),
+                };
+                state.handle_result(result);
+            }
+        }
+
+        if app_exit_events.iter().next().is_some() {
+            let result = state.writer.flush();
+            state.handle_result(result);
+            if let Some(settings) = state.summary_settings.take() {
+                state.finish(&settings, &diagnostics);
+            }
+        }
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_number(value: f64) -> String {
+    // JSON has no representation for NaN and infinities
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(mean: f64) -> DiagnosticSummary {
+        DiagnosticSummary {
+            count: 10,
+            mean,
+            min: mean,
+            max: mean,
+        }
+    }
+
+    #[test]
+    fn regression_check() {
+        let id = DiagnosticId::default();
+        let check = RegressionCheck::new(id).with_tolerance(0.1);
+        assert!(check.check("a", &summary(1.05), &summary(1.0)).is_none());
+        assert!(check.check("a", &summary(1.2), &summary(1.0)).is_some());
+        assert!(check.check("a", &summary(0.5), &summary(1.0)).is_none());
+
+        let check = RegressionCheck::new(id).higher_is_better();
+        assert!(check.check("a", &summary(1.2), &summary(1.0)).is_none());
+        assert!(check.check("a", &summary(0.5), &summary(1.0)).is_some());
+    }
+
+    #[test]
+    fn summary_add() {
+        let mut summary = DiagnosticSummary::default();
+        for value in [1.0, 2.0, 3.0, 6.0] {
+            summary.add(value);
+        }
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.mean, 3.0);
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.max, 6.0);
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(csv_escape("fps"), "fps");
+        assert_eq!(csv_escape("a,\"b\""), "\"a,\"\"b\"\"\"");
+        assert_eq!(json_escape("a\"b\\"), "\"a\\\"b\\\\\"");
+        assert_eq!(json_number(f64::NAN), "null");
+    }
+}
@@ -1,11 +1,21 @@
 mod diagnostic;
 mod entity_count_diagnostics_plugin;
+mod export_diagnostics_plugin;
 mod frame_time_diagnostics_plugin;
 mod log_diagnostics_plugin;
+mod memory_diagnostics_plugin;
 pub use diagnostic::*;
 pub use entity_count_diagnostics_plugin::EntityCountDiagnosticsPlugin;
+pub use export_diagnostics_plugin::{
+    DiagnosticSummary, DiagnosticsExportFormat, DiagnosticsExportPlugin, DiagnosticsSummary,
+    DiagnosticsSummarySettings, RegressionCheck,
+};
 pub use frame_time_diagnostics_plugin::FrameTimeDiagnosticsPlugin;
 pub use log_diagnostics_plugin::LogDiagnosticsPlugin;
+pub use memory_diagnostics_plugin::{
+    memory_diagnostic_name, ComponentMemoryDiagnosticsPlugin, EventMemoryDiagnosticsPlugin,
+    MemoryGrowthThreshold, DEFAULT_MEMORY_GROWTH_STEP,
+};
 
 use bevy_app::prelude::*;
 
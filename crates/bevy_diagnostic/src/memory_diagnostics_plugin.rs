@@ -0,0 +1,310 @@
+use crate::{Diagnostic, DiagnosticId, Diagnostics, MAX_DIAGNOSTIC_NAME_WIDTH};
+use bevy_app::{App, Plugin};
+use bevy_ecs::{
+    component::ComponentId,
+    event::Events,
+    system::{IntoExclusiveSystem, Res, ResMut, Resource},
+    world::{Mut, World},
+};
+use bevy_log::warn;
+use bevy_utils::HashMap;
+
+/// The default number of bytes between two growth warnings of a memory diagnostic: 64 MiB
+pub const DEFAULT_MEMORY_GROWTH_STEP: usize = 64 * 1024 * 1024;
+
+/// Tracks the high-water mark of a memory diagnostic and logs a warning every time it grows
+/// past a multiple of `step` bytes.
+#[derive(Debug, Clone)]
+pub struct MemoryGrowthThreshold {
+    step: usize,
+    next: usize,
+}
+
+impl MemoryGrowthThreshold {
+    pub fn new(step: usize) -> Self {
+        MemoryGrowthThreshold {
+            step: step.max(1),
+            next: step.max(1),
+        }
+    }
+
+    /// Records a new measurement of `bytes`, logging a warning if it crossed the next threshold.
+    ///
+    /// Returns `true` if a threshold was crossed.
+    pub fn update(&mut self, name: impl std::fmt::Display, bytes: usize) -> bool {
+        if bytes < self.next {
+            return false;
+        }
+        let crossed = bytes / self.step * self.step;
+        warn!(
+            "{} grew past {:.1} MiB (now {:.1} MiB)",
+            name,
+            crossed as f64 / (1024.0 * 1024.0),
+            bytes as f64 / (1024.0 * 1024.0)
+        );
+        self.next = crossed + self.step;
+        true
+    }
+}
+
+/// Builds a diagnostic name out of `prefix` and `type_name`, truncating the type name so that
+/// the result fits in [MAX_DIAGNOSTIC_NAME_WIDTH].
+pub fn memory_diagnostic_name(prefix: &str, type_name: &str) -> String {
+    let max_length = MAX_DIAGNOSTIC_NAME_WIDTH.saturating_sub(prefix.len() + 1);
+    format!(
+        "{} {}",
+        prefix,
+        if type_name.len() > max_length {
+            let mut start = type_name.len() - max_length + 1;
+            while !type_name.is_char_boundary(start) {
+                start += 1;
+            }
+            &type_name[start..]
+        } else {
+            type_name
+        }
+    )
+}
+
+/// Adds "component bytes" diagnostics to an App: the total number of bytes allocated for
+/// component storage, and the number of bytes allocated per component type.
+///
+/// This accounts for table columns and sparse sets, including unused capacity, but not for
+/// heap allocations owned by the components themselves.
+pub struct ComponentMemoryDiagnosticsPlugin {
+    /// If set, a warning is logged every time a diagnostic grows past a multiple of this many bytes
+    pub growth_log_step: Option<usize>,
+}
+
+impl Default for ComponentMemoryDiagnosticsPlugin {
+    fn default() -> Self {
+        ComponentMemoryDiagnosticsPlugin {
+            growth_log_step: Some(DEFAULT_MEMORY_GROWTH_STEP),
+        }
+    }
+}
+
+/// State used by the [ComponentMemoryDiagnosticsPlugin]
+struct ComponentMemoryDiagnosticsState {
+    growth_log_step: Option<usize>,
+    total_threshold: Option<MemoryGrowthThreshold>,
+    components: HashMap<ComponentId, (DiagnosticId, Option<MemoryGrowthThreshold>)>,
+}
+
+impl Plugin for ComponentMemoryDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ComponentMemoryDiagnosticsState {
+            growth_log_step: self.growth_log_step,
+            total_threshold: self.growth_log_step.map(MemoryGrowthThreshold::new),
+            components: Default::default(),
+        })
+        .add_startup_system(Self::setup_system)
+        .add_system(Self::diagnostic_system.exclusive_system());
+    }
+}
+
+impl ComponentMemoryDiagnosticsPlugin {
+    pub const COMPONENT_BYTES: DiagnosticId =
+        DiagnosticId::from_u128(263457231845918370318251442046236094571);
+
+    /// Returns the id of the diagnostic tracking the storage of the given component.
+    ///
+    /// Component ids are only stable for a given [World], and so are these diagnostic ids.
+    pub fn component_diagnostic_id(component_id: ComponentId) -> DiagnosticId {
+        DiagnosticId::from_u128(
+            Self::COMPONENT_BYTES.0.as_u128() ^ (component_id.index() as u128 + 1),
+        )
+    }
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics
+            .add(Diagnostic::new(Self::COMPONENT_BYTES, "component_bytes", 20).with_suffix("B"));
+    }
+
+    pub fn diagnostic_system(world: &mut World) {
+        let mut bytes_per_component: HashMap<ComponentId, usize> = HashMap::default();
+        let mut total = 0;
+        let storages = world.storages();
+        for table in storages.tables.iter() {
+            total += table.allocated_bytes();
+            for column in table.iter() {
+                *bytes_per_component
+                    .entry(column.component_id())
+                    .or_default() += column.allocated_bytes();
+            }
+        }
+        for (component_id, sparse_set) in storages.sparse_sets.iter() {
+            let bytes = sparse_set.allocated_bytes();
+            total += bytes;
+            *bytes_per_component.entry(component_id).or_default() += bytes;
+        }
+
+        world.resource_scope(|world, mut state: Mut<ComponentMemoryDiagnosticsState>| {
+            let state = &mut *state;
+            let components = world.components();
+            let mut new_diagnostics = Vec::new();
+            let mut measurements = Vec::with_capacity(bytes_per_component.len() + 1);
+            measurements.push((Self::COMPONENT_BYTES, total));
+            if let Some(threshold) = &mut state.total_threshold {
+                threshold.update("component_bytes", total);
+            }
+            for (component_id, bytes) in bytes_per_component {
+                let name = components
+                    .get_info(component_id)
+                    .map(|info| info.name())
+                    .unwrap_or("unknown");
+                let growth_log_step = state.growth_log_step;
+                let (diagnostic_id, threshold) =
+                    state.components.entry(component_id).or_insert_with(|| {
+                        let diagnostic_id = Self::component_diagnostic_id(component_id);
+                        new_diagnostics.push(
+                            Diagnostic::new(
+                                diagnostic_id,
+                                memory_diagnostic_name("component_bytes", name),
+                                20,
+                            )
+                            .with_suffix("B"),
+                        );
+                        (
+                            diagnostic_id,
+                            growth_log_step.map(MemoryGrowthThreshold::new),
+                        )
+                    });
+                if let Some(threshold) = threshold {
+                    threshold.update(format_args!("Storage of component {}", name), bytes);
+                }
+                measurements.push((*diagnostic_id, bytes));
+            }
+
+            if let Some(mut diagnostics) = world.get_resource_mut::<Diagnostics>() {
+                for diagnostic in new_diagnostics {
+                    diagnostics.add(diagnostic);
+                }
+                for (id, bytes) in measurements {
+                    diagnostics.add_measurement(id, bytes as f64);
+                }
+            }
+        });
+    }
+}
+
+/// Adds an "event bytes" diagnostic to an App, tracking the number of bytes allocated by the
+/// buffers of [`Events<T>`].
+///
+/// Heap allocations owned by the events themselves are not taken into account.
+pub struct EventMemoryDiagnosticsPlugin<T: Resource> {
+    /// If set, a warning is logged every time the buffers grow past a multiple of this many bytes
+    pub growth_log_step: Option<usize>,
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Resource> Default for EventMemoryDiagnosticsPlugin<T> {
+    fn default() -> Self {
+        Self {
+            growth_log_step: Some(DEFAULT_MEMORY_GROWTH_STEP),
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+/// State used by the [EventMemoryDiagnosticsPlugin]
+struct EventMemoryDiagnosticsState<T> {
+    threshold: Option<MemoryGrowthThreshold>,
+    marker: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: Resource> Plugin for EventMemoryDiagnosticsPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EventMemoryDiagnosticsState::<T> {
+            threshold: self.growth_log_step.map(MemoryGrowthThreshold::new),
+            marker: std::marker::PhantomData,
+        })
+        .add_startup_system(Self::setup_system)
+        .add_system(Self::diagnostic_system);
+    }
+}
+
+impl<T: Resource> EventMemoryDiagnosticsPlugin<T> {
+    pub const EVENT_BYTES: DiagnosticId =
+        DiagnosticId::from_u128(124747573983280683516331861577369271183);
+
+    /// Returns the id of the diagnostic tracking the buffers of [`Events<T>`].
+    ///
+    /// It is derived from the type name of `T`, so it is the same across runs.
+    pub fn diagnostic_id() -> DiagnosticId {
+        // FNV-1a
+        let mut hash = 0xcbf2_9ce4_8422_2325u64;
+        for byte in std::any::type_name::<T>().bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        DiagnosticId::from_u128(Self::EVENT_BYTES.0.as_u128() ^ hash as u128)
+    }
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(
+            Diagnostic::new(
+                Self::diagnostic_id(),
+                memory_diagnostic_name("event_bytes", std::any::type_name::<T>()),
+                20,
+            )
+            .with_suffix("B"),
+        );
+    }
+
+    fn diagnostic_system(
+        mut diagnostics: ResMut<Diagnostics>,
+        mut state: ResMut<EventMemoryDiagnosticsState<T>>,
+        events: Res<Events<T>>,
+    ) {
+        let bytes = events.allocated_bytes();
+        if let Some(threshold) = &mut state.threshold {
+            threshold.update(
+                format_args!("Events<{}> buffers", std::any::type_name::<T>()),
+                bytes,
+            );
+        }
+        diagnostics.add_measurement(Self::diagnostic_id(), bytes as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn growth_threshold() {
+        let mut threshold = MemoryGrowthThreshold::new(100);
+        assert!(!threshold.update("a", 50));
+        assert!(threshold.update("a", 150));
+        assert!(!threshold.update("a", 199));
+        assert!(!threshold.update("a", 120));
+        assert!(threshold.update("a", 450));
+        assert!(!threshold.update("a", 499));
+        assert!(threshold.update("a", 500));
+    }
+
+    #[test]
+    fn diagnostic_name() {
+        assert_eq!(
+            memory_diagnostic_name("event_bytes", "u32"),
+            "event_bytes u32"
+        );
+        let name =
+            memory_diagnostic_name("component_bytes", "bevy_transform::components::Transform");
+        assert!(name.len() <= MAX_DIAGNOSTIC_NAME_WIDTH);
+        assert!(name.ends_with("::Transform"));
+    }
+
+    #[test]
+    fn event_diagnostic_id() {
+        assert_eq!(
+            EventMemoryDiagnosticsPlugin::<u32>::diagnostic_id(),
+            EventMemoryDiagnosticsPlugin::<u32>::diagnostic_id()
+        );
+        assert_ne!(
+            EventMemoryDiagnosticsPlugin::<u32>::diagnostic_id(),
+            EventMemoryDiagnosticsPlugin::<u64>::diagnostic_id()
+        );
+    }
+}
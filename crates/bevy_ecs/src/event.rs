@@ -349,6 +349,14 @@ impl<T: Resource> Events<T> {
         self.events_a.is_empty() && self.events_b.is_empty()
     }
 
+    /// Returns the number of bytes allocated by the two event buffers, including unused capacity.
+    ///
+    /// Heap allocations owned by the events themselves are not taken into account.
+    pub fn allocated_bytes(&self) -> usize {
+        (self.events_a.capacity() + self.events_b.capacity())
+            * std::mem::size_of::<EventInstance<T>>()
+    }
+
     /// Creates a draining iterator that removes all events.
     pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
         self.reset_start_event_count();
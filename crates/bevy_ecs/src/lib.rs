@@ -18,7 +18,7 @@ pub mod world;
 pub mod prelude {
     #[doc(hidden)]
     #[cfg(feature = "bevy_reflect")]
-    pub use crate::reflect::ReflectComponent;
+    pub use crate::reflect::{ReflectComponent, ReflectResource};
     #[doc(hidden)]
     pub use crate::{
         bundle::Bundle,
@@ -50,7 +50,8 @@ mod tests {
         component::{Component, ComponentId},
         entity::Entity,
         query::{
-            Added, ChangeTrackers, Changed, FilterFetch, FilteredAccess, With, Without, WorldQuery,
+            Added, BatchingStrategy, ChangeTrackers, Changed, FilterFetch, FilteredAccess, With,
+            Without, WorldQuery,
         },
         world::{Mut, World},
     };
@@ -415,6 +416,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn par_iter_dense() {
+        let mut world = World::new();
+        let task_pool = TaskPool::default();
+        let e1 = world.spawn().insert(A(1)).id();
+        let e2 = world.spawn().insert(A(2)).id();
+        let e3 = world.spawn().insert(A(3)).id();
+        let e4 = world.spawn().insert_bundle((A(4), B(1))).id();
+        let e5 = world.spawn().insert_bundle((A(5), B(1))).id();
+        let results = Arc::new(Mutex::new(Vec::new()));
+        world
+            .query::<(Entity, &A)>()
+            .par_iter(&world, &task_pool)
+            .for_each(|(e, &A(i))| results.lock().push((e, i)));
+        results.lock().sort();
+        assert_eq!(
+            &*results.lock(),
+            &[(e1, 1), (e2, 2), (e3, 3), (e4, 4), (e5, 5)]
+        );
+    }
+
+    #[test]
+    fn par_iter_mut_sparse() {
+        let mut world = World::new();
+        let task_pool = TaskPool::default();
+        let e1 = world.spawn().insert(SparseStored(1)).id();
+        let e2 = world.spawn().insert_bundle((SparseStored(2), A(1))).id();
+        world
+            .query::<&mut SparseStored>()
+            .par_iter_mut(&mut world, &task_pool)
+            .batching_strategy(BatchingStrategy::fixed(1))
+            .for_each(|mut s| s.0 *= 10);
+        assert_eq!(world.get::<SparseStored>(e1).unwrap().0, 10);
+        assert_eq!(world.get::<SparseStored>(e2).unwrap().0, 20);
+    }
+
+    #[test]
+    fn par_iter_map_filter_collect_reduce() {
+        let mut world = World::new();
+        let task_pool = TaskPool::default();
+        for i in 0..100 {
+            if i % 2 == 0 {
+                world.spawn().insert(A(i));
+            } else {
+                world.spawn().insert_bundle((A(i), SparseStored(i as u32)));
+            }
+        }
+        let mut query = world.query::<&A>();
+        let values: Vec<usize> = query
+            .par_iter(&world, &task_pool)
+            .batching_strategy(BatchingStrategy::fixed(7))
+            .map(|a| a.0)
+            .filter(|i| i % 3 == 0)
+            .collect();
+        let expected = query
+            .iter(&world)
+            .map(|a| a.0)
+            .filter(|i| i % 3 == 0)
+            .collect::<Vec<_>>();
+        assert_eq!(values, expected);
+        let sum = query
+            .par_iter(&world, &task_pool)
+            .map(|a| a.0)
+            .reduce(|a, b| a + b);
+        assert_eq!(sum, Some(4950));
+        let none = query
+            .par_iter(&world, &task_pool)
+            .filter(|a| a.0 > 100)
+            .map(|a| a.0)
+            .reduce(|a, b| a + b);
+        assert_eq!(none, None);
+
+        let mut query = world.query_filtered::<Entity, With<SparseStored>>();
+        let entities: Vec<Entity> = query.par_iter(&world, &task_pool).collect();
+        assert_eq!(entities, query.iter(&world).collect::<Vec<_>>());
+        assert_eq!(entities.len(), 50);
+    }
+
     #[test]
     fn query_missing_component() {
         let mut world = World::new();
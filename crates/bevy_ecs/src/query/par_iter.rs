@@ -0,0 +1,493 @@
+use crate::{
+    query::{Fetch, FilterFetch, QueryState, WorldQuery},
+    world::World,
+};
+use bevy_tasks::TaskPool;
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Dictates how a parallel query splits up the matched tables/archetypes
+/// into batches during iteration.
+///
+/// The batches aren't assigned to the threads up front: each thread takes
+/// the next batch when it is done with the previous one, so threads that
+/// finish early take over the remaining work. The size of each batch is
+/// derived from the number of entities left to iterate and the number of
+/// threads, so batches shrink towards the end of the iteration to keep the
+/// threads busy until the end.
+///
+/// This assumes each entity has roughly the same amount of work to be done,
+/// which may not hold true in every workload.
+#[derive(Clone, Debug)]
+pub struct BatchingStrategy {
+    /// The smallest allowed batch size.
+    ///
+    /// Defaults to 1.
+    pub min_batch_size: usize,
+    /// The largest allowed batch size.
+    ///
+    /// Defaults to `usize::MAX`.
+    pub max_batch_size: usize,
+    /// The number of batches per thread in the task pool.
+    ///
+    /// Increasing this value will decrease the batch size, which may
+    /// improve workload balance across threads.
+    ///
+    /// Defaults to 1.
+    pub batches_per_thread: usize,
+}
+
+impl Default for BatchingStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchingStrategy {
+    /// Creates a new unconstrained default batching strategy.
+    pub const fn new() -> Self {
+        Self {
+            min_batch_size: 1,
+            max_batch_size: usize::MAX,
+            batches_per_thread: 1,
+        }
+    }
+
+    /// Declares a batching strategy with a fixed batch size.
+    pub const fn fixed(batch_size: usize) -> Self {
+        Self {
+            min_batch_size: batch_size,
+            max_batch_size: batch_size,
+            batches_per_thread: 1,
+        }
+    }
+
+    /// Configures the minimum allowed batch size of this instance.
+    pub const fn min_batch_size(mut self, batch_size: usize) -> Self {
+        self.min_batch_size = batch_size;
+        self
+    }
+
+    /// Configures the maximum allowed batch size of this instance.
+    pub const fn max_batch_size(mut self, batch_size: usize) -> Self {
+        self.max_batch_size = batch_size;
+        self
+    }
+
+    /// Configures the number of batches to assign to each thread for this instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batches_per_thread` is zero.
+    pub fn batches_per_thread(mut self, batches_per_thread: usize) -> Self {
+        assert!(
+            batches_per_thread > 0,
+            "The number of batches per thread must be non-zero."
+        );
+        self.batches_per_thread = batches_per_thread;
+        self
+    }
+
+    /// Calculates the size of the next batch, when `remaining` entities are left to iterate on
+    /// `thread_count` threads.
+    ///
+    /// The result is always at least 1.
+    pub fn calc_batch_size(&self, remaining: usize, thread_count: usize) -> usize {
+        let batches = thread_count.max(1) * self.batches_per_thread;
+        (remaining / batches)
+            .min(self.max_batch_size)
+            .max(self.min_batch_size)
+            .max(1)
+    }
+}
+
+/// Hands out the batches of a parallel query to the tasks running it.
+///
+/// The entities of the matched tables/archetypes are numbered one after the other, and a batch is
+/// a range of those numbers, which may span several tables/archetypes.
+pub(crate) struct BatchCursor {
+    /// The number one past the last entity of each matched table/archetype
+    ends: Vec<usize>,
+    next: AtomicUsize,
+    strategy: BatchingStrategy,
+    thread_count: usize,
+}
+
+impl BatchCursor {
+    pub(crate) fn new(
+        lens: impl Iterator<Item = usize>,
+        strategy: BatchingStrategy,
+        thread_count: usize,
+    ) -> Self {
+        let ends = lens
+            .scan(0, |end, len| {
+                *end += len;
+                Some(*end)
+            })
+            .collect();
+        Self {
+            ends,
+            next: AtomicUsize::new(0),
+            strategy,
+            thread_count: thread_count.max(1),
+        }
+    }
+
+    /// The number of tasks that should take batches from this cursor
+    pub(crate) fn task_count(&self) -> usize {
+        if self.len() == 0 {
+            0
+        } else {
+            self.thread_count
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    /// Takes the next batch, or returns `None` if all the batches were taken
+    pub(crate) fn claim(&self) -> Option<Range<usize>> {
+        let len = self.len();
+        let mut start = self.next.load(Ordering::Relaxed);
+        loop {
+            if start >= len {
+                return None;
+            }
+            let batch_size = self
+                .strategy
+                .calc_batch_size(len - start, self.thread_count);
+            let end = len.min(start + batch_size);
+            match self
+                .next
+                .compare_exchange_weak(start, end, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => return Some(start..end),
+                Err(next) => start = next,
+            }
+        }
+    }
+
+    /// Splits `batch` into the index of each table/archetype it covers, and the range of its
+    /// entities in the table/archetype.
+    pub(crate) fn split(
+        &self,
+        batch: Range<usize>,
+    ) -> impl Iterator<Item = (usize, Range<usize>)> + '_ {
+        let first = self.ends.partition_point(|end| *end <= batch.start);
+        self.ends[first..]
+            .iter()
+            .enumerate()
+            .map(move |(index, end)| {
+                let index = first + index;
+                let start = if index == 0 { 0 } else { self.ends[index - 1] };
+                (index, start, *end)
+            })
+            .take_while(move |(_, start, _)| *start < batch.end)
+            .map(move |(index, start, end)| {
+                (
+                    index,
+                    batch.start.max(start) - start..batch.end.min(end) - start,
+                )
+            })
+    }
+}
+
+/// A parallel iterator over query results of a [`Query`](crate::system::Query).
+///
+/// This struct is created by the [`Query::par_iter`](crate::system::Query::par_iter) and
+/// [`Query::par_iter_mut`](crate::system::Query::par_iter_mut) methods.
+pub struct QueryParIter<'w, 's, Q: WorldQuery, QF: Fetch<'w, 's, State = Q::State>, F: WorldQuery>
+where
+    F::Fetch: FilterFetch,
+{
+    pub(crate) world: &'w World,
+    pub(crate) state: &'s QueryState<Q, F>,
+    pub(crate) task_pool: TaskPool,
+    pub(crate) batching_strategy: BatchingStrategy,
+    pub(crate) last_change_tick: u32,
+    pub(crate) change_tick: u32,
+    pub(crate) marker: std::marker::PhantomData<fn() -> QF>,
+}
+
+impl<'w, 's, Q: WorldQuery, QF, F: WorldQuery> QueryParIter<'w, 's, Q, QF, F>
+where
+    F::Fetch: FilterFetch,
+    QF: Fetch<'w, 's, State = Q::State>,
+{
+    /// # Safety
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `state.world_id`. Calling this on a `world`
+    /// with a mismatched WorldId is unsound.
+    pub(crate) unsafe fn new(
+        world: &'w World,
+        state: &'s QueryState<Q, F>,
+        task_pool: &TaskPool,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Self {
+        QueryParIter {
+            world,
+            state,
+            task_pool: task_pool.clone(),
+            batching_strategy: BatchingStrategy::default(),
+            last_change_tick,
+            change_tick,
+            marker: Default::default(),
+        }
+    }
+
+    /// Changes the batching strategy used when iterating.
+    ///
+    /// For more information on how this affects the resultant iteration, see
+    /// [`BatchingStrategy`].
+    pub fn batching_strategy(mut self, strategy: BatchingStrategy) -> Self {
+        self.batching_strategy = strategy;
+        self
+    }
+
+    /// Runs `func` on each query result in parallel.
+    #[inline]
+    pub fn for_each<FN: Fn(QF::Item) + Send + Sync + Clone>(self, func: FN) {
+        self.fold(|| (), move |_, item| func(item));
+    }
+
+    /// Returns a parallel iterator over the results of `func` on each query result.
+    #[inline]
+    pub fn map<T, M>(
+        self,
+        func: M,
+    ) -> QueryParMap<'w, 's, Q, QF, F, impl Fn(QF::Item) -> Option<T> + Send + Sync + Clone>
+    where
+        M: Fn(QF::Item) -> T + Send + Sync + Clone,
+    {
+        QueryParMap {
+            iter: self,
+            func: move |item| Some(func(item)),
+        }
+    }
+
+    /// Returns a parallel iterator over the query results matching `predicate`.
+    #[inline]
+    pub fn filter<P>(
+        self,
+        predicate: P,
+    ) -> QueryParMap<'w, 's, Q, QF, F, impl Fn(QF::Item) -> Option<QF::Item> + Send + Sync + Clone>
+    where
+        P: Fn(&QF::Item) -> bool + Send + Sync + Clone,
+    {
+        QueryParMap {
+            iter: self,
+            func: move |item| if predicate(&item) { Some(item) } else { None },
+        }
+    }
+
+    /// Collects the query results in parallel, in the order of sequential iteration.
+    ///
+    /// This is only possible for query results that don't borrow from the world, such as
+    /// [`Entity`](crate::entity::Entity). Use [`Self::map`] to turn them into owned values.
+    #[inline]
+    pub fn collect<C: FromIterator<QF::Item>>(self) -> C
+    where
+        QF::Item: Send + 'static,
+    {
+        self.map(|item| item).collect()
+    }
+
+    /// Reduces the query results to a single one in parallel, by repeatedly applying `func`.
+    ///
+    /// Each batch is reduced independently, then the results of the batches are reduced in the
+    /// order of sequential iteration. `func` should therefore be associative.
+    #[inline]
+    pub fn reduce<R>(self, func: R) -> Option<QF::Item>
+    where
+        QF::Item: Send + 'static,
+        R: Fn(QF::Item, QF::Item) -> QF::Item + Send + Sync + Clone,
+    {
+        self.map(|item| item).reduce(func)
+    }
+
+    /// Folds each batch of query results into a value starting from `init()`, in parallel, and
+    /// returns the values in the order of the batches.
+    fn fold<A, INIT, FOLD>(self, init: INIT, fold: FOLD) -> Vec<A>
+    where
+        A: Send + 'static,
+        INIT: Fn() -> A + Send + Sync + Clone,
+        FOLD: Fn(A, QF::Item) -> A + Send + Sync + Clone,
+    {
+        // SAFE: the safety requirements were upheld when this iterator was created
+        unsafe {
+            self.state.par_fold_unchecked_manual::<QF, A, INIT, FOLD>(
+                self.world,
+                &self.task_pool,
+                &self.batching_strategy,
+                init,
+                fold,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+}
+
+/// A parallel iterator over the results of a function on query results.
+///
+/// This struct is created by the [`QueryParIter::map`] and [`QueryParIter::filter`] methods.
+pub struct QueryParMap<'w, 's, Q: WorldQuery, QF: Fetch<'w, 's, State = Q::State>, F: WorldQuery, M>
+where
+    F::Fetch: FilterFetch,
+{
+    iter: QueryParIter<'w, 's, Q, QF, F>,
+    func: M,
+}
+
+impl<'w, 's, Q: WorldQuery, QF, F: WorldQuery, M, T> QueryParMap<'w, 's, Q, QF, F, M>
+where
+    F::Fetch: FilterFetch,
+    QF: Fetch<'w, 's, State = Q::State>,
+    M: Fn(QF::Item) -> Option<T> + Send + Sync + Clone,
+{
+    /// Changes the batching strategy used when iterating.
+    ///
+    /// For more information on how this affects the resultant iteration, see
+    /// [`BatchingStrategy`].
+    pub fn batching_strategy(mut self, strategy: BatchingStrategy) -> Self {
+        self.iter.batching_strategy = strategy;
+        self
+    }
+
+    /// Runs `func` on each item in parallel.
+    #[inline]
+    pub fn for_each<FN: Fn(T) + Send + Sync + Clone>(self, func: FN) {
+        let map = self.func;
+        self.iter.fold(
+            || (),
+            move |_, item| {
+                if let Some(value) = map(item) {
+                    func(value);
+                }
+            },
+        );
+    }
+
+    /// Returns a parallel iterator over the results of `func` on each item.
+    #[inline]
+    pub fn map<U, N>(
+        self,
+        func: N,
+    ) -> QueryParMap<'w, 's, Q, QF, F, impl Fn(QF::Item) -> Option<U> + Send + Sync + Clone>
+    where
+        N: Fn(T) -> U + Send + Sync + Clone,
+    {
+        let map = self.func;
+        QueryParMap {
+            iter: self.iter,
+            func: move |item| map(item).map(&func),
+        }
+    }
+
+    /// Returns a parallel iterator over the items matching `predicate`.
+    #[inline]
+    pub fn filter<P>(
+        self,
+        predicate: P,
+    ) -> QueryParMap<'w, 's, Q, QF, F, impl Fn(QF::Item) -> Option<T> + Send + Sync + Clone>
+    where
+        P: Fn(&T) -> bool + Send + Sync + Clone,
+    {
+        let map = self.func;
+        QueryParMap {
+            iter: self.iter,
+            func: move |item| map(item).filter(&predicate),
+        }
+    }
+
+    /// Collects the items in parallel, in the order of sequential iteration.
+    #[inline]
+    pub fn collect<C: FromIterator<T>>(self) -> C
+    where
+        T: Send + 'static,
+    {
+        let map = self.func;
+        self.iter
+            .fold(Vec::new, move |mut values, item| {
+                values.extend(map(item));
+                values
+            })
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Reduces the items to a single one in parallel, by repeatedly applying `func`.
+    ///
+    /// Each batch is reduced independently, then the results of the batches are reduced in the
+    /// order of sequential iteration. `func` should therefore be associative.
+    #[inline]
+    pub fn reduce<R>(self, func: R) -> Option<T>
+    where
+        T: Send + 'static,
+        R: Fn(T, T) -> T + Send + Sync + Clone,
+    {
+        let map = self.func;
+        let reduce = func.clone();
+        self.iter
+            .fold(
+                || None,
+                move |reduced, item| match (reduced, map(item)) {
+                    (Some(reduced), Some(value)) => Some(reduce(reduced, value)),
+                    (reduced, value) => reduced.or(value),
+                },
+            )
+            .into_iter()
+            .flatten()
+            .reduce(func)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchCursor, BatchingStrategy};
+
+    #[test]
+    fn calc_batch_size() {
+        let strategy = BatchingStrategy::new();
+        assert_eq!(strategy.calc_batch_size(1000, 4), 250);
+        assert_eq!(strategy.calc_batch_size(3, 4), 1);
+        assert_eq!(strategy.calc_batch_size(0, 4), 1);
+
+        let strategy = BatchingStrategy::new().batches_per_thread(2);
+        assert_eq!(strategy.calc_batch_size(1000, 4), 125);
+
+        let strategy = BatchingStrategy::new().min_batch_size(300);
+        assert_eq!(strategy.calc_batch_size(1000, 4), 300);
+
+        let strategy = BatchingStrategy::new().max_batch_size(100);
+        assert_eq!(strategy.calc_batch_size(1000, 4), 100);
+
+        let strategy = BatchingStrategy::fixed(64);
+        assert_eq!(strategy.calc_batch_size(1000, 4), 64);
+        assert_eq!(strategy.calc_batch_size(10, 4), 64);
+    }
+
+    #[test]
+    fn batch_cursor() {
+        let cursor = BatchCursor::new(vec![3, 0, 5].into_iter(), BatchingStrategy::new(), 2);
+        assert_eq!(cursor.task_count(), 2);
+        // batches shrink as the remaining entities are taken
+        let batches = std::iter::from_fn(|| cursor.claim()).collect::<Vec<_>>();
+        assert_eq!(batches, vec![0..4, 4..6, 6..7, 7..8]);
+        assert_eq!(
+            cursor.split(0..4).collect::<Vec<_>>(),
+            vec![(0, 0..3), (1, 0..0), (2, 0..1)]
+        );
+        assert_eq!(cursor.split(4..6).collect::<Vec<_>>(), vec![(2, 1..3)]);
+
+        let cursor = BatchCursor::new(std::iter::empty(), BatchingStrategy::new(), 2);
+        assert_eq!(cursor.task_count(), 0);
+        assert_eq!(cursor.claim(), None);
+    }
+}
@@ -3,8 +3,8 @@ use crate::{
     component::ComponentId,
     entity::Entity,
     query::{
-        Access, Fetch, FetchState, FilterFetch, FilteredAccess, NopFetch, QueryCombinationIter,
-        QueryIter, WorldQuery,
+        Access, BatchCursor, BatchingStrategy, Fetch, FetchState, FilterFetch, FilteredAccess,
+        NopFetch, QueryCombinationIter, QueryIter, QueryParIter, WorldQuery,
     },
     storage::TableId,
     world::{World, WorldId},
@@ -493,6 +493,52 @@ where
         );
     }
 
+    /// Returns a parallel iterator over the query results for the given [`World`].
+    ///
+    /// Unlike [`Self::par_for_each`], the batch size is chosen automatically. See
+    /// [`BatchingStrategy`](crate::query::BatchingStrategy) for how to tune it.
+    ///
+    /// This can only be called for read-only queries, see [`Self::par_iter_mut`] for
+    /// write-queries.
+    #[inline]
+    pub fn par_iter<'w, 's>(
+        &'s mut self,
+        world: &'w World,
+        task_pool: &TaskPool,
+    ) -> QueryParIter<'w, 's, Q, Q::ReadOnlyFetch, F> {
+        // SAFE: query is read only
+        unsafe {
+            self.update_archetypes(world);
+            QueryParIter::new(
+                world,
+                self,
+                task_pool,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Returns a parallel iterator over the query results for the given [`World`].
+    #[inline]
+    pub fn par_iter_mut<'w, 's>(
+        &'s mut self,
+        world: &'w mut World,
+        task_pool: &TaskPool,
+    ) -> QueryParIter<'w, 's, Q, Q::Fetch, F> {
+        // SAFE: query has unique world access
+        unsafe {
+            self.update_archetypes(world);
+            QueryParIter::new(
+                world,
+                self,
+                task_pool,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
     /// Runs `func` on each query result in parallel using the given `task_pool`.
     ///
     /// This can only be called for read-only queries, see [`Self::par_for_each_mut`] for
@@ -735,6 +781,111 @@ where
             }
         });
     }
+
+    /// Folds the query results in parallel for the given [`World`], where the last change and
+    /// the current change tick are given. The tasks of `task_pool` take batches of results,
+    /// sized according to `batching_strategy`, until all were taken, and fold each batch into a
+    /// value starting from `init()`. The values are returned in the order of the batches.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched WorldId is unsound.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) unsafe fn par_fold_unchecked_manual<
+        'w,
+        's,
+        QF: Fetch<'w, 's, State = Q::State>,
+        A: Send + 'static,
+        INIT: Fn() -> A + Send + Sync + Clone,
+        FOLD: Fn(A, QF::Item) -> A + Send + Sync + Clone,
+    >(
+        &'s self,
+        world: &'w World,
+        task_pool: &TaskPool,
+        batching_strategy: &BatchingStrategy,
+        init: INIT,
+        fold: FOLD,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Vec<A> {
+        // NOTE: If you are changing query iteration code, remember to update the following places, where relevant:
+        // QueryIter, QueryIterationCursor, QueryState::for_each_unchecked_manual, QueryState::par_for_each_unchecked_manual
+        let dense = QF::IS_DENSE && F::Fetch::IS_DENSE;
+        let cursor = if dense {
+            let tables = &world.storages().tables;
+            BatchCursor::new(
+                self.matched_table_ids.iter().map(|id| tables[*id].len()),
+                batching_strategy.clone(),
+                task_pool.thread_num(),
+            )
+        } else {
+            let archetypes = &world.archetypes;
+            BatchCursor::new(
+                self.matched_archetype_ids
+                    .iter()
+                    .map(|id| archetypes[*id].len()),
+                batching_strategy.clone(),
+                task_pool.thread_num(),
+            )
+        };
+        let cursor = &cursor;
+        let mut batches = task_pool
+            .scope(|scope| {
+                for _ in 0..cursor.task_count() {
+                    let init = init.clone();
+                    let fold = fold.clone();
+                    scope.spawn(async move {
+                        let mut fetch =
+                            QF::init(world, &self.fetch_state, last_change_tick, change_tick);
+                        let mut filter = <F::Fetch as Fetch>::init(
+                            world,
+                            &self.filter_state,
+                            last_change_tick,
+                            change_tick,
+                        );
+                        let tables = &world.storages().tables;
+                        let archetypes = &world.archetypes;
+                        let mut results = Vec::new();
+                        while let Some(batch) = cursor.claim() {
+                            let mut value = init();
+                            for (index, range) in cursor.split(batch.clone()) {
+                                if dense {
+                                    let table = &tables[self.matched_table_ids[index]];
+                                    fetch.set_table(&self.fetch_state, table);
+                                    filter.set_table(&self.filter_state, table);
+                                    for table_index in range {
+                                        if !filter.table_filter_fetch(table_index) {
+                                            continue;
+                                        }
+                                        value = fold(value, fetch.table_fetch(table_index));
+                                    }
+                                } else {
+                                    let archetype = &archetypes[self.matched_archetype_ids[index]];
+                                    fetch.set_archetype(&self.fetch_state, archetype, tables);
+                                    filter.set_archetype(&self.filter_state, archetype, tables);
+                                    for archetype_index in range {
+                                        if !filter.archetype_filter_fetch(archetype_index) {
+                                            continue;
+                                        }
+                                        value = fold(value, fetch.archetype_fetch(archetype_index));
+                                    }
+                                }
+                            }
+                            results.push((batch.start, value));
+                        }
+                        results
+                    });
+                }
+            })
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        batches.sort_unstable_by_key(|(start, _)| *start);
+        batches.into_iter().map(|(_, value)| value).collect()
+    }
 }
 
 /// An error that occurs when retrieving a specific [`Entity`]'s query result.
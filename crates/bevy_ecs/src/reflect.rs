@@ -4,6 +4,7 @@ pub use crate::change_detection::ReflectMut;
 use crate::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    system::Resource,
     world::{FromWorld, World},
 };
 use bevy_reflect::{
@@ -18,6 +19,7 @@ pub struct ReflectComponent {
     reflect_component: fn(&World, Entity) -> Option<&dyn Reflect>,
     reflect_component_mut: unsafe fn(&World, Entity) -> Option<ReflectMut>,
     copy_component: fn(&World, &mut World, Entity, Entity),
+    from_world: fn(&mut World) -> Box<dyn Reflect>,
 }
 
 impl ReflectComponent {
@@ -78,6 +80,12 @@ impl ReflectComponent {
             destination_entity,
         );
     }
+
+    /// Creates a component with its [`FromWorld`] implementation, to be modified through
+    /// reflection before being added with [`ReflectComponent::add_component`].
+    pub fn from_world(&self, world: &mut World) -> Box<dyn Reflect> {
+        (self.from_world)(world)
+    }
 }
 
 impl<C: Component + Reflect + FromWorld> FromType<C> for ReflectComponent {
@@ -118,6 +126,68 @@ impl<C: Component + Reflect + FromWorld> FromType<C> for ReflectComponent {
                         ticks: c.ticks,
                     })
             },
+            from_world: |world| Box::new(C::from_world(world)),
+        }
+    }
+}
+
+/// Type data to access a [`Reflect`] resource of a [`World`] from its type
+#[derive(Clone)]
+pub struct ReflectResource {
+    insert_resource: fn(&mut World, &dyn Reflect),
+    apply_resource: fn(&mut World, &dyn Reflect),
+    remove_resource: fn(&mut World),
+    reflect_resource: fn(&World) -> Option<&dyn Reflect>,
+    from_world: fn(&mut World) -> Box<dyn Reflect>,
+}
+
+impl ReflectResource {
+    /// Inserts the resource, created with its [`FromWorld`] implementation and then set from
+    /// `resource`. Replaces the resource if it already exists.
+    pub fn insert_resource(&self, world: &mut World, resource: &dyn Reflect) {
+        (self.insert_resource)(world, resource);
+    }
+
+    /// Applies `resource` to the resource of the world.
+    ///
+    /// # Panics
+    /// Panics if the resource doesn't exist.
+    pub fn apply_resource(&self, world: &mut World, resource: &dyn Reflect) {
+        (self.apply_resource)(world, resource);
+    }
+
+    pub fn remove_resource(&self, world: &mut World) {
+        (self.remove_resource)(world);
+    }
+
+    pub fn reflect_resource<'a>(&self, world: &'a World) -> Option<&'a dyn Reflect> {
+        (self.reflect_resource)(world)
+    }
+
+    /// Creates a resource with its [`FromWorld`] implementation, to be modified through
+    /// reflection before being inserted with [`ReflectResource::insert_resource`].
+    pub fn from_world(&self, world: &mut World) -> Box<dyn Reflect> {
+        (self.from_world)(world)
+    }
+}
+
+impl<R: Resource + Reflect + FromWorld> FromType<R> for ReflectResource {
+    fn from_type() -> Self {
+        ReflectResource {
+            insert_resource: |world, reflected_resource| {
+                let mut resource = R::from_world(world);
+                resource.apply(reflected_resource);
+                world.insert_resource(resource);
+            },
+            apply_resource: |world, reflected_resource| {
+                let mut resource = world.get_resource_mut::<R>().unwrap();
+                resource.apply(reflected_resource);
+            },
+            remove_resource: |world| {
+                world.remove_resource::<R>();
+            },
+            reflect_resource: |world| world.get_resource::<R>().map(|r| r as &dyn Reflect),
+            from_world: |world| Box::new(R::from_world(world)),
         }
     }
 }
@@ -128,9 +198,11 @@ impl_from_reflect_value!(Entity);
 #[derive(Clone)]
 pub struct ReflectMapEntities {
     map_entities: fn(&mut World, &EntityMap) -> Result<(), MapEntitiesError>,
+    map_specific_entities: fn(&mut World, &EntityMap, &[Entity]) -> Result<(), MapEntitiesError>,
 }
 
 impl ReflectMapEntities {
+    /// Maps the component of all the entities that are values of `entity_map`
     pub fn map_entities(
         &self,
         world: &mut World,
@@ -138,6 +210,17 @@ impl ReflectMapEntities {
     ) -> Result<(), MapEntitiesError> {
         (self.map_entities)(world, entity_map)
     }
+
+    /// Maps the component of the given `entities` only, leaving alone the components of the other
+    /// entities of `entity_map`, which may already refer to entities of the world.
+    pub fn map_specific_entities(
+        &self,
+        world: &mut World,
+        entity_map: &EntityMap,
+        entities: &[Entity],
+    ) -> Result<(), MapEntitiesError> {
+        (self.map_specific_entities)(world, entity_map, entities)
+    }
 }
 
 impl<C: Component + MapEntities> FromType<C> for ReflectMapEntities {
@@ -151,6 +234,14 @@ impl<C: Component + MapEntities> FromType<C> for ReflectMapEntities {
                 }
                 Ok(())
             },
+            map_specific_entities: |world, entity_map, entities| {
+                for entity in entities {
+                    if let Some(mut component) = world.get_mut::<C>(*entity) {
+                        component.map_entities(entity_map)?;
+                    }
+                }
+                Ok(())
+            },
         }
     }
 }
@@ -58,6 +58,17 @@ impl BlobVec {
         self.capacity
     }
 
+    /// Returns the number of bytes allocated by this [`BlobVec`], including unused capacity.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        if self.item_layout.size() == 0 {
+            0
+        } else {
+            // the swap scratch space holds a single item
+            (self.capacity + 1) * self.item_layout.size()
+        }
+    }
+
     pub fn reserve_exact(&mut self, additional: usize) {
         let available_space = self.capacity - self.len;
         if available_space < additional {
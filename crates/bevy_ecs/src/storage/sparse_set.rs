@@ -86,6 +86,12 @@ impl<I: SparseSetIndex, V> SparseArray<I, V> {
     pub fn clear(&mut self) {
         self.values.clear();
     }
+
+    /// Returns the number of bytes allocated by this [`SparseArray`], including unused capacity.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.values.capacity() * std::mem::size_of::<Option<V>>()
+    }
 }
 
 /// A sparse data structure of [Components](crate::component::Component)
@@ -126,6 +132,15 @@ impl ComponentSparseSet {
         self.dense.len() == 0
     }
 
+    /// Returns the number of bytes allocated by this sparse set for component data, change
+    /// ticks and bookkeeping, including unused capacity.
+    pub fn allocated_bytes(&self) -> usize {
+        self.dense.allocated_bytes()
+            + self.ticks.capacity() * std::mem::size_of::<UnsafeCell<ComponentTicks>>()
+            + self.entities.capacity() * std::mem::size_of::<Entity>()
+            + self.sparse.allocated_bytes()
+    }
+
     /// Inserts the `entity` key and component `value` pair into this sparse
     /// set. This collection takes ownership of the contents of `value`, and
     /// will drop the value when needed. Also, it may overwrite the contents of
@@ -425,6 +440,11 @@ impl SparseSets {
         self.sets.get_mut(component_id)
     }
 
+    /// Iterates over every [ComponentSparseSet] and the [ComponentId] it stores.
+    pub fn iter(&self) -> impl Iterator<Item = (ComponentId, &ComponentSparseSet)> {
+        self.sets.indices().zip(self.sets.values())
+    }
+
     pub fn clear(&mut self) {
         for set in self.sets.values_mut() {
             set.clear();
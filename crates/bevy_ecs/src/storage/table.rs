@@ -93,6 +93,19 @@ impl Column {
         self.data.is_empty()
     }
 
+    #[inline]
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+
+    /// Returns the number of bytes allocated for the component data and change ticks of this
+    /// column, including unused capacity.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.data.allocated_bytes()
+            + self.ticks.capacity() * std::mem::size_of::<UnsafeCell<ComponentTicks>>()
+    }
+
     /// # Safety
     /// index must be in-bounds
     #[inline]
@@ -382,6 +395,14 @@ impl Table {
         self.entities.capacity()
     }
 
+    /// Returns the number of bytes allocated by this table, including unused capacity.
+    ///
+    /// This is the sum of [`Column::allocated_bytes`] over every column, plus the entity list.
+    pub fn allocated_bytes(&self) -> usize {
+        self.entities.capacity() * std::mem::size_of::<Entity>()
+            + self.iter().map(Column::allocated_bytes).sum::<usize>()
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         self.entities.len()
@@ -528,7 +549,11 @@ mod tests {
     use crate as bevy_ecs;
     use crate::component::Component;
     use crate::storage::Storages;
-    use crate::{component::Components, entity::Entity, storage::Table};
+    use crate::{
+        component::{ComponentTicks, Components},
+        entity::Entity,
+        storage::Table,
+    };
     #[derive(Component)]
     struct W<T>(T);
 
@@ -556,5 +581,16 @@ mod tests {
 
         assert_eq!(table.capacity(), 256);
         assert_eq!(table.len(), 200);
+
+        let column = table.get_column(component_id).unwrap();
+        assert_eq!(column.component_id(), component_id);
+        // 256 items of component data, plus one item of swap scratch space
+        let data_bytes = 257 * std::mem::size_of::<usize>();
+        let ticks_bytes = 256 * std::mem::size_of::<ComponentTicks>();
+        assert_eq!(column.allocated_bytes(), data_bytes + ticks_bytes);
+        assert_eq!(
+            table.allocated_bytes(),
+            256 * std::mem::size_of::<Entity>() + data_bytes + ticks_bytes
+        );
     }
 }
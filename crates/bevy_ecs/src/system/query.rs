@@ -2,8 +2,8 @@ use crate::{
     component::Component,
     entity::Entity,
     query::{
-        Fetch, FilterFetch, QueryCombinationIter, QueryEntityError, QueryIter, QueryState,
-        WorldQuery,
+        Fetch, FilterFetch, QueryCombinationIter, QueryEntityError, QueryIter, QueryParIter,
+        QueryState, WorldQuery,
     },
     world::{Mut, World},
 };
@@ -503,6 +503,65 @@ where
         };
     }
 
+    /// Returns a parallel iterator over the query results, using the given task pool.
+    ///
+    /// Unlike [`Self::par_for_each`], no batch size has to be picked: it is derived from the
+    /// number of entities left to iterate and the number of threads in `task_pool`.
+    /// Use [`QueryParIter::batching_strategy`] to tune it.
+    ///
+    /// This can only return immutable data, see [`Self::par_iter_mut`] for mutable access.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_tasks::ComputeTaskPool;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Velocity { x: f32, y: f32, z: f32 }
+    /// fn print_speed_system(pool: Res<ComputeTaskPool>, query: Query<&Velocity>) {
+    ///     query.par_iter(&pool).for_each(|velocity| {
+    ///         println!("{}", (velocity.x.powi(2) + velocity.y.powi(2) + velocity.z.powi(2)).sqrt());
+    ///     });
+    /// }
+    /// # print_speed_system.system();
+    /// ```
+    #[inline]
+    pub fn par_iter(
+        &'s self,
+        task_pool: &TaskPool,
+    ) -> QueryParIter<'w, 's, Q, Q::ReadOnlyFetch, F> {
+        // SAFE: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            QueryParIter::new(
+                self.world,
+                self.state,
+                task_pool,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Returns a parallel iterator over the query results, using the given task pool.
+    ///
+    /// See [`Self::par_iter`] for how the batch size is chosen.
+    #[inline]
+    pub fn par_iter_mut(&mut self, task_pool: &TaskPool) -> QueryParIter<'_, '_, Q, Q::Fetch, F> {
+        // SAFE: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            QueryParIter::new(
+                self.world,
+                self.state,
+                task_pool,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
     /// Runs `f` on each query result in parallel using the given task pool.
     ///
     /// This can only be called for immutable data, see [`Self::par_for_each_mut`] for
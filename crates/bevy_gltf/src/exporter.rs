@@ -0,0 +1,956 @@
+use bevy_asset::{Assets, Handle, HandleId};
+use bevy_core::Name;
+use bevy_ecs::{entity::Entity, world::World};
+use bevy_log::warn;
+use bevy_math::Mat3;
+use bevy_pbr::{AlphaMode, DirectionalLight, PointLight, StandardMaterial};
+use bevy_render::{
+    mesh::{Indices, Mesh, MorphWeights, SkinnedMesh, VertexAttributeValues},
+    render_resource::PrimitiveTopology,
+    texture::Image,
+};
+use bevy_scene::Scene;
+use bevy_transform::{
+    components::{Children, Parent},
+    prelude::Transform,
+};
+use bevy_utils::HashMap;
+use gltf::json::{
+    self,
+    accessor::{ComponentType, GenericComponentType, Type},
+    buffer::Target,
+    extensions::scene::khr_lights_punctual,
+    mesh::Semantic,
+    validation::Checked::Valid,
+    Index,
+};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[cfg(feature = "png")]
+use bevy_render::render_resource::{AddressMode, FilterMode, SamplerDescriptor};
+
+/// The file format of an exported glTF file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GltfExportFormat {
+    /// A `.gltf` JSON file
+    Gltf,
+    /// A `.glb` binary file
+    Glb,
+}
+
+/// Settings of a [`GltfExporter`].
+#[derive(Debug, Clone)]
+pub struct GltfExportSettings {
+    pub format: GltfExportFormat,
+    /// Whether the buffer and the images are stored in the exported file, or in separate files
+    /// next to it. Embedded buffers of `.gltf` files are stored as base64 data URIs.
+    pub embed_resources: bool,
+    /// The name of the exported glTF scene, also used to name the external files
+    pub name: String,
+}
+
+impl Default for GltfExportSettings {
+    fn default() -> Self {
+        GltfExportSettings {
+            format: GltfExportFormat::Gltf,
+            embed_resources: true,
+            name: "scene".to_string(),
+        }
+    }
+}
+
+/// An error that occurs when exporting a glTF file.
+#[derive(Error, Debug)]
+pub enum GltfExportError {
+    #[error("mesh {0:?} has no vertex positions")]
+    MissingPositions(HandleId),
+    #[error("failed to serialize the glTF JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to write the binary glTF: {0}")]
+    Gltf(#[from] gltf::Error),
+    #[error("failed to encode image {0:?}: {1}")]
+    ImageEncoding(HandleId, anyhow::Error),
+}
+
+/// An exported glTF file, and the files it refers to when its resources aren't embedded.
+#[derive(Debug, Clone)]
+pub struct GltfExport {
+    /// The content of the `.gltf` or `.glb` file
+    pub document: Vec<u8>,
+    /// The buffer and images, by their path relative to the document
+    pub external_files: Vec<(String, Vec<u8>)>,
+}
+
+impl GltfExport {
+    /// Writes the document to `path`, and the external files next to it.
+    pub fn write_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, &self.document)?;
+        let directory = path.parent().map_or_else(PathBuf::new, Path::to_path_buf);
+        for (file_path, bytes) in self.external_files.iter() {
+            std::fs::write(directory.join(file_path), bytes)?;
+        }
+        Ok(())
+    }
+}
+
+/// Exports entities with their [`Transform`], [`Children`], [`Name`], [`Handle<Mesh>`],
+/// [`Handle<StandardMaterial>`], [`PointLight`] and [`DirectionalLight`] as a glTF file, to be
+/// loaded by the [`GltfLoader`](crate::GltfLoader) or by other tools.
+///
+/// Each entity becomes a node, and the mesh of an entity a mesh with a single primitive. Lights
+/// use the `KHR_lights_punctual` extension, the UV transform of materials the
+/// `KHR_texture_transform` extension and emissive colors brighter than 1 the
+/// `KHR_materials_emissive_strength` extension. Morph targets, skins and animations aren't
+/// exported, and neither are the joints of skinned meshes. Textures are encoded as PNG images,
+/// which requires the `png` feature.
+pub struct GltfExporter<'a> {
+    meshes: &'a Assets<Mesh>,
+    materials: &'a Assets<StandardMaterial>,
+    images: &'a Assets<Image>,
+    settings: GltfExportSettings,
+}
+
+impl<'a> GltfExporter<'a> {
+    pub fn new(
+        meshes: &'a Assets<Mesh>,
+        materials: &'a Assets<StandardMaterial>,
+        images: &'a Assets<Image>,
+    ) -> Self {
+        GltfExporter {
+            meshes,
+            materials,
+            images,
+            settings: Default::default(),
+        }
+    }
+
+    pub fn with_settings(mut self, settings: GltfExportSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Exports the entities of `scene` that don't have a [`Parent`], with their descendants.
+    pub fn export_scene(&self, scene: &Scene) -> Result<GltfExport, GltfExportError> {
+        let mut roots: Vec<Entity> = scene
+            .world
+            .archetypes()
+            .iter()
+            .flat_map(|archetype| archetype.entities().iter().copied())
+            .filter(|entity| scene.world.get::<Parent>(*entity).is_none())
+            .collect();
+        roots.sort_by_key(|entity| entity.id());
+        self.export_entities(&scene.world, &roots)
+    }
+
+    /// Exports `roots` and their descendants in `world`.
+    pub fn export_entities(
+        &self,
+        world: &World,
+        roots: &[Entity],
+    ) -> Result<GltfExport, GltfExportError> {
+        let mut builder = GltfBuilder {
+            exporter: self,
+            root: json::Root {
+                asset: json::Asset {
+                    generator: Some("bevy".to_string()),
+                    version: "2.0".to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            buffer: Vec::new(),
+            external_files: Vec::new(),
+            lights: Vec::new(),
+            emissive_strengths: Vec::new(),
+            meshes: HashMap::default(),
+            materials: HashMap::default(),
+            textures: HashMap::default(),
+        };
+        let nodes = roots
+            .iter()
+            .map(|root| builder.export_node(world, *root))
+            .collect::<Result<_, _>>()?;
+        builder.root.scenes.push(json::Scene {
+            extensions: None,
+            extras: Default::default(),
+            name: Some(self.settings.name.clone()),
+            nodes,
+        });
+        builder.root.scene = Some(Index::new(0));
+        builder.finish()
+    }
+}
+
+/// Builds the glTF document and the buffer of an export.
+struct GltfBuilder<'a> {
+    exporter: &'a GltfExporter<'a>,
+    root: json::Root,
+    buffer: Vec<u8>,
+    external_files: Vec<(String, Vec<u8>)>,
+    lights: Vec<khr_lights_punctual::Light>,
+    /// The `KHR_materials_emissive_strength` of the materials, by material index, added to the
+    /// serialized document as `gltf_json` doesn't support the extension
+    emissive_strengths: Vec<(usize, f32)>,
+    meshes: HashMap<(HandleId, Option<HandleId>), Index<json::Mesh>>,
+    materials: HashMap<HandleId, Index<json::Material>>,
+    textures: HashMap<HandleId, Option<Index<json::Texture>>>,
+}
+
+impl<'a> GltfBuilder<'a> {
+    fn export_node(
+        &mut self,
+        world: &World,
+        entity: Entity,
+    ) -> Result<Index<json::Node>, GltfExportError> {
+        let children = match world.get::<Children>(entity) {
+            Some(children) => Some(
+                children
+                    .iter()
+                    .map(|child| self.export_node(world, *child))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            None => None,
+        };
+        let transform = world.get::<Transform>(entity).cloned().unwrap_or_default();
+        let mesh = match self.entity_mesh(world, entity) {
+            Some((mesh_handle, mesh)) => {
+                let material = world.get::<Handle<StandardMaterial>>(entity);
+                Some(self.export_mesh(mesh_handle, mesh, material)?)
+            }
+            None => None,
+        };
+        let light = world
+            .get::<PointLight>(entity)
+            .map(point_light)
+            .or_else(|| world.get::<DirectionalLight>(entity).map(directional_light))
+            .map(|light| {
+                self.lights.push(light);
+                json::extensions::scene::Node {
+                    khr_lights_punctual: Some(khr_lights_punctual::KhrLightsPunctual {
+                        light: Index::new(self.lights.len() as u32 - 1),
+                    }),
+                }
+            });
+
+        self.root.nodes.push(json::Node {
+            camera: None,
+            children: children.filter(|children| !children.is_empty()),
+            extensions: light,
+            extras: Default::default(),
+            matrix: None,
+            mesh,
+            name: world
+                .get::<Name>(entity)
+                .map(|name| name.as_str().to_string()),
+            rotation: Some(json::scene::UnitQuaternion(transform.rotation.into())),
+            scale: Some(transform.scale.into()),
+            translation: Some(transform.translation.into()),
+            skin: None,
+            weights: None,
+        });
+        Ok(Index::new(self.root.nodes.len() as u32 - 1))
+    }
+
+    /// The mesh of `entity`, or the mesh it's deformed from if it's skinned or morphed.
+    fn entity_mesh(&self, world: &World, entity: Entity) -> Option<(HandleId, &'a Mesh)> {
+        let meshes = self.exporter.meshes;
+        let handle = world.get::<Handle<Mesh>>(entity)?;
+        if let Some(mesh) = meshes.get(handle) {
+            return Some((handle.id, mesh));
+        }
+        let base_handle = world
+            .get::<SkinnedMesh>(entity)
+            .map(|skinned_mesh| &skinned_mesh.mesh)
+            .or_else(|| {
+                world
+                    .get::<MorphWeights>(entity)
+                    .map(|morph_weights| &morph_weights.mesh)
+            })?;
+        meshes.get(base_handle).map(|mesh| (base_handle.id, mesh))
+    }
+
+    fn export_mesh(
+        &mut self,
+        mesh_handle: HandleId,
+        mesh: &Mesh,
+        material: Option<&Handle<StandardMaterial>>,
+    ) -> Result<Index<json::Mesh>, GltfExportError> {
+        let key = (mesh_handle, material.map(|material| material.id));
+        if let Some(index) = self.meshes.get(&key) {
+            return Ok(*index);
+        }
+
+        let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions,
+            _ => return Err(GltfExportError::MissingPositions(mesh_handle)),
+        };
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for position in positions.iter() {
+            for i in 0..3 {
+                min[i] = min[i].min(position[i]);
+                max[i] = max[i].max(position[i]);
+            }
+        }
+
+        let mut attributes = std::collections::HashMap::new();
+        for (name, semantic) in [
+            (Mesh::ATTRIBUTE_POSITION, Semantic::Positions),
+            (Mesh::ATTRIBUTE_NORMAL, Semantic::Normals),
+            (Mesh::ATTRIBUTE_TANGENT, Semantic::Tangents),
+            (Mesh::ATTRIBUTE_UV_0, Semantic::TexCoords(0)),
+            (Mesh::ATTRIBUTE_COLOR, Semantic::Colors(0)),
+        ] {
+            let values = match mesh.attribute(name) {
+                Some(values) => values,
+                None => continue,
+            };
+            let (component_type, type_) = match values {
+                VertexAttributeValues::Float32x2(_) => (ComponentType::F32, Type::Vec2),
+                VertexAttributeValues::Float32x3(_) => (ComponentType::F32, Type::Vec3),
+                VertexAttributeValues::Float32x4(_) => (ComponentType::F32, Type::Vec4),
+                _ => {
+                    warn!(
+                        "Attribute {} of mesh {:?} ignored: its format isn't supported by glTF",
+                        name, mesh_handle
+                    );
+                    continue;
+                }
+            };
+            let view = self.push_view(values.get_bytes(), Some(Target::ArrayBuffer));
+            let (min, max) = if semantic == Semantic::Positions {
+                (Some(min[..].into()), Some(max[..].into()))
+            } else {
+                (None, None)
+            };
+            let accessor = self.push_accessor(json::Accessor {
+                buffer_view: Some(view),
+                byte_offset: 0,
+                count: values.len() as u32,
+                component_type: Valid(GenericComponentType(component_type)),
+                extensions: None,
+                extras: Default::default(),
+                type_: Valid(type_),
+                min,
+                max,
+                name: None,
+                normalized: false,
+                sparse: None,
+            });
+            attributes.insert(Valid(semantic), accessor);
+        }
+
+        let indices = match (mesh.indices(), mesh.get_index_buffer_bytes()) {
+            (Some(indices), Some(bytes)) => {
+                let component_type = match indices {
+                    Indices::U16(_) => ComponentType::U16,
+                    Indices::U32(_) => ComponentType::U32,
+                };
+                let view = self.push_view(bytes, Some(Target::ElementArrayBuffer));
+                Some(self.push_accessor(json::Accessor {
+                    buffer_view: Some(view),
+                    byte_offset: 0,
+                    count: indices.len() as u32,
+                    component_type: Valid(GenericComponentType(component_type)),
+                    extensions: None,
+                    extras: Default::default(),
+                    type_: Valid(Type::Scalar),
+                    min: None,
+                    max: None,
+                    name: None,
+                    normalized: false,
+                    sparse: None,
+                }))
+            }
+            _ => None,
+        };
+
+        let material = match material {
+            Some(material) => self.export_material(material)?,
+            None => None,
+        };
+        self.root.meshes.push(json::Mesh {
+            extensions: None,
+            extras: Default::default(),
+            name: None,
+            primitives: vec![json::mesh::Primitive {
+                attributes,
+                extensions: None,
+                extras: Default::default(),
+                indices,
+                material,
+                mode: Valid(primitive_mode(mesh.primitive_topology())),
+                targets: None,
+            }],
+            weights: None,
+        });
+        let index = Index::new(self.root.meshes.len() as u32 - 1);
+        self.meshes.insert(key, index);
+        Ok(index)
+    }
+
+    fn export_material(
+        &mut self,
+        handle: &Handle<StandardMaterial>,
+    ) -> Result<Option<Index<json::Material>>, GltfExportError> {
+        if let Some(index) = self.materials.get(&handle.id) {
+            return Ok(Some(*index));
+        }
+        let material = match self.exporter.materials.get(handle) {
+            Some(material) => material,
+            None => return Ok(None),
+        };
+
+        // the transform applies to all the textures, but only texture infos can hold it
+        let texture_transform = if material.uv_transform != Mat3::IDENTITY {
+            self.use_extension("KHR_texture_transform");
+            Some(texture_transform(material.uv_transform))
+        } else {
+            None
+        };
+        let texture_info = |texture: Option<Index<json::Texture>>| {
+            texture.map(|index| json::texture::Info {
+                index,
+                tex_coord: 0,
+                extensions: texture_transform.clone().map(|texture_transform| {
+                    json::extensions::texture::Info {
+                        texture_transform: Some(texture_transform),
+                    }
+                }),
+                extras: Default::default(),
+            })
+        };
+        let base_color_texture = self.export_texture(&material.base_color_texture)?;
+        let metallic_roughness_texture =
+            self.export_texture(&material.metallic_roughness_texture)?;
+        let emissive_texture = self.export_texture(&material.emissive_texture)?;
+        let normal_texture = self.export_texture(&material.normal_map_texture)?;
+        let occlusion_texture = self.export_texture(&material.occlusion_texture)?;
+
+        // the emissive factor is at most 1, brighter colors are scaled by an emissive strength
+        let [r, g, b, _] = material.emissive.as_rgba_f32();
+        let emissive_strength = r.max(g).max(b);
+        let emissive_factor = if emissive_strength > 1.0 {
+            self.use_extension("KHR_materials_emissive_strength");
+            self.emissive_strengths
+                .push((self.root.materials.len(), emissive_strength));
+            [
+                r / emissive_strength,
+                g / emissive_strength,
+                b / emissive_strength,
+            ]
+        } else {
+            [r, g, b]
+        };
+        let (alpha_mode, alpha_cutoff) = match material.alpha_mode {
+            AlphaMode::Opaque => (json::material::AlphaMode::Opaque, None),
+            AlphaMode::Mask(cutoff) => (
+                json::material::AlphaMode::Mask,
+                Some(json::material::AlphaCutoff(cutoff)),
+            ),
+            AlphaMode::Blend => (json::material::AlphaMode::Blend, None),
+        };
+        let extensions = if material.unlit {
+            self.use_extension("KHR_materials_unlit");
+            Some(json::extensions::material::Material {
+                unlit: Some(json::extensions::material::Unlit {}),
+            })
+        } else {
+            None
+        };
+
+        self.root.materials.push(json::Material {
+            alpha_cutoff,
+            alpha_mode: Valid(alpha_mode),
+            double_sided: material.double_sided,
+            name: None,
+            pbr_metallic_roughness: json::material::PbrMetallicRoughness {
+                base_color_factor: json::material::PbrBaseColorFactor(
+                    material.base_color.as_rgba_f32(),
+                ),
+                base_color_texture: texture_info(base_color_texture),
+                metallic_factor: json::material::StrengthFactor(material.metallic),
+                roughness_factor: json::material::StrengthFactor(material.perceptual_roughness),
+                metallic_roughness_texture: texture_info(metallic_roughness_texture),
+                extensions: None,
+                extras: Default::default(),
+            },
+            normal_texture: normal_texture.map(|index| json::material::NormalTexture {
+                index,
+                scale: 1.0,
+                tex_coord: 0,
+                extensions: None,
+                extras: Default::default(),
+            }),
+            occlusion_texture: occlusion_texture.map(|index| json::material::OcclusionTexture {
+                index,
+                strength: json::material::StrengthFactor(1.0),
+                tex_coord: 0,
+                extensions: None,
+                extras: Default::default(),
+            }),
+            emissive_texture: texture_info(emissive_texture),
+            emissive_factor: json::material::EmissiveFactor(emissive_factor),
+            extensions,
+            extras: Default::default(),
+        });
+        let index = Index::new(self.root.materials.len() as u32 - 1);
+        self.materials.insert(handle.id, index);
+        Ok(Some(index))
+    }
+
+    /// Exports the image of `handle` as a PNG texture. Images that aren't loaded, or that can't
+    /// be encoded without the `png` feature, are ignored.
+    fn export_texture(
+        &mut self,
+        handle: &Option<Handle<Image>>,
+    ) -> Result<Option<Index<json::Texture>>, GltfExportError> {
+        let handle = match handle {
+            Some(handle) => handle,
+            None => return Ok(None),
+        };
+        if let Some(index) = self.textures.get(&handle.id) {
+            return Ok(*index);
+        }
+        let index = match self.exporter.images.get(handle) {
+            Some(image) => self.push_texture(handle.id, image)?,
+            None => None,
+        };
+        self.textures.insert(handle.id, index);
+        Ok(index)
+    }
+
+    #[cfg(feature = "png")]
+    fn push_texture(
+        &mut self,
+        handle: HandleId,
+        image: &Image,
+    ) -> Result<Option<Index<json::Texture>>, GltfExportError> {
+        use bevy_asset::AssetSaver;
+        use bevy_render::texture::ImageTextureSaver;
+
+        let bytes = ImageTextureSaver
+            .save(image, Path::new("image.png"))
+            .map_err(|err| GltfExportError::ImageEncoding(handle, err))?;
+        let json_image = if self.exporter.settings.embed_resources {
+            json::Image {
+                buffer_view: Some(self.push_view(&bytes, None)),
+                mime_type: Some(json::image::MimeType("image/png".to_string())),
+                name: None,
+                uri: None,
+                extensions: None,
+                extras: Default::default(),
+            }
+        } else {
+            let uri = format!(
+                "{}_image{}.png",
+                self.exporter.settings.name,
+                self.root.images.len()
+            );
+            self.external_files.push((uri.clone(), bytes));
+            json::Image {
+                buffer_view: None,
+                mime_type: None,
+                name: None,
+                uri: Some(uri),
+                extensions: None,
+                extras: Default::default(),
+            }
+        };
+        self.root.images.push(json_image);
+
+        self.root.samplers.push(sampler(&image.sampler_descriptor));
+
+        self.root.textures.push(json::Texture {
+            name: None,
+            sampler: Some(Index::new(self.root.samplers.len() as u32 - 1)),
+            source: Index::new(self.root.images.len() as u32 - 1),
+            extensions: None,
+            extras: Default::default(),
+        });
+        Ok(Some(Index::new(self.root.textures.len() as u32 - 1)))
+    }
+
+    #[cfg(not(feature = "png"))]
+    fn push_texture(
+        &mut self,
+        handle: HandleId,
+        _image: &Image,
+    ) -> Result<Option<Index<json::Texture>>, GltfExportError> {
+        warn!(
+            "Image {:?} isn't exported: the `png` feature is needed to encode images",
+            handle
+        );
+        Ok(None)
+    }
+
+    /// Appends `bytes` to the buffer, aligned to 4 bytes, and adds a view of them.
+    fn push_view(&mut self, bytes: &[u8], target: Option<Target>) -> Index<json::buffer::View> {
+        let padding = (4 - self.buffer.len() % 4) % 4;
+        self.buffer.resize(self.buffer.len() + padding, 0);
+        self.root.buffer_views.push(json::buffer::View {
+            buffer: Index::new(0),
+            byte_length: bytes.len() as u32,
+            byte_offset: Some(self.buffer.len() as u32),
+            byte_stride: None,
+            name: None,
+            target: target.map(Valid),
+            extensions: None,
+            extras: Default::default(),
+        });
+        self.buffer.extend_from_slice(bytes);
+        Index::new(self.root.buffer_views.len() as u32 - 1)
+    }
+
+    fn push_accessor(&mut self, accessor: json::Accessor) -> Index<json::Accessor> {
+        self.root.accessors.push(accessor);
+        Index::new(self.root.accessors.len() as u32 - 1)
+    }
+
+    fn use_extension(&mut self, name: &str) {
+        if !self.root.extensions_used.iter().any(|used| used == name) {
+            self.root.extensions_used.push(name.to_string());
+        }
+    }
+
+    /// Adds the buffer and the lights to the document, and serializes it.
+    fn finish(mut self) -> Result<GltfExport, GltfExportError> {
+        let settings = &self.exporter.settings;
+        if !self.lights.is_empty() {
+            self.use_extension("KHR_lights_punctual");
+            self.root.extensions = Some(json::extensions::Root {
+                khr_lights_punctual: Some(json::extensions::root::KhrLightsPunctual {
+                    lights: std::mem::take(&mut self.lights),
+                }),
+            });
+        }
+
+        let is_glb = settings.format == GltfExportFormat::Glb;
+        let mut glb_buffer = None;
+        if !self.buffer.is_empty() {
+            let byte_length = self.buffer.len() as u32;
+            // the binary chunk of a `.glb` file is its first buffer, which has no uri
+            let uri = if !settings.embed_resources {
+                let uri = format!("{}.bin", settings.name);
+                self.external_files.push((uri.clone(), self.buffer));
+                Some(uri)
+            } else if is_glb {
+                glb_buffer = Some(self.buffer);
+                None
+            } else {
+                Some(format!(
+                    "data:application/octet-stream;base64,{}",
+                    base64::encode(&self.buffer)
+                ))
+            };
+            self.root.buffers.push(json::Buffer {
+                byte_length,
+                name: None,
+                uri,
+                extensions: None,
+                extras: Default::default(),
+            });
+        }
+
+        let json = if self.emissive_strengths.is_empty() {
+            json::serialize::to_vec(&self.root)?
+        } else {
+            let mut json = serde_json::to_value(&self.root)?;
+            for (material, emissive_strength) in self.emissive_strengths.iter() {
+                let material = &mut json["materials"][material];
+                if material.get("extensions").is_none() {
+                    material["extensions"] = serde_json::json!({});
+                }
+                material["extensions"]["KHR_materials_emissive_strength"] =
+                    serde_json::json!({ "emissiveStrength": emissive_strength });
+            }
+            serde_json::to_vec(&json)?
+        };
+        let document = if is_glb {
+            gltf::binary::Glb {
+                header: gltf::binary::Header {
+                    magic: *b"glTF",
+                    version: 2,
+                    // the length is computed when writing the file
+                    length: 0,
+                },
+                json: json.into(),
+                bin: glb_buffer.map(Into::into),
+            }
+            .to_vec()?
+        } else {
+            json
+        };
+        Ok(GltfExport {
+            document,
+            external_files: self.external_files,
+        })
+    }
+}
+
+fn point_light(light: &PointLight) -> khr_lights_punctual::Light {
+    let [r, g, b, _] = light.color.as_rgba_f32();
+    khr_lights_punctual::Light {
+        color: [r, g, b],
+        extensions: None,
+        extras: Default::default(),
+        // the intensity of point lights is in lumens, and converted to candela
+        intensity: light.intensity / (4.0 * std::f32::consts::PI),
+        name: None,
+        range: Some(light.range),
+        spot: None,
+        type_: Valid(khr_lights_punctual::Type::Point),
+    }
+}
+
+fn directional_light(light: &DirectionalLight) -> khr_lights_punctual::Light {
+    let [r, g, b, _] = light.color.as_rgba_f32();
+    khr_lights_punctual::Light {
+        color: [r, g, b],
+        extensions: None,
+        extras: Default::default(),
+        intensity: light.illuminance,
+        name: None,
+        range: None,
+        spot: None,
+        type_: Valid(khr_lights_punctual::Type::Directional),
+    }
+}
+
+/// Returns the `KHR_texture_transform` of the `uv_transform` of a material, the inverse of the
+/// conversion of the [`GltfLoader`](crate::GltfLoader). The transform can't be sheared.
+fn texture_transform(uv_transform: Mat3) -> json::extensions::texture::TextureTransform {
+    use json::extensions::texture::{
+        TextureTransform, TextureTransformOffset, TextureTransformRotation, TextureTransformScale,
+    };
+
+    let x_axis = uv_transform.x_axis.truncate();
+    let y_axis = uv_transform.y_axis.truncate();
+    // the rotation is counterclockwise in texture space, whose y axis points down
+    TextureTransform {
+        offset: TextureTransformOffset(uv_transform.z_axis.truncate().into()),
+        rotation: TextureTransformRotation(-x_axis.y.atan2(x_axis.x)),
+        scale: TextureTransformScale([x_axis.length(), y_axis.length()]),
+        tex_coord: None,
+        extras: Default::default(),
+    }
+}
+
+/// Maps the primitive_topology from wgpu to glTF.
+fn primitive_mode(topology: PrimitiveTopology) -> json::mesh::Mode {
+    match topology {
+        PrimitiveTopology::PointList => json::mesh::Mode::Points,
+        PrimitiveTopology::LineList => json::mesh::Mode::Lines,
+        PrimitiveTopology::LineStrip => json::mesh::Mode::LineStrip,
+        PrimitiveTopology::TriangleList => json::mesh::Mode::Triangles,
+        PrimitiveTopology::TriangleStrip => json::mesh::Mode::TriangleStrip,
+    }
+}
+
+/// Maps the sampler of an image from wgpu to glTF.
+#[cfg(feature = "png")]
+fn sampler(descriptor: &SamplerDescriptor) -> json::texture::Sampler {
+    use json::texture::{MagFilter, MinFilter, WrappingMode};
+
+    let wrapping_mode = |address_mode| match address_mode {
+        AddressMode::Repeat => WrappingMode::Repeat,
+        AddressMode::MirrorRepeat => WrappingMode::MirroredRepeat,
+        _ => WrappingMode::ClampToEdge,
+    };
+    json::texture::Sampler {
+        mag_filter: Some(Valid(match descriptor.mag_filter {
+            FilterMode::Nearest => MagFilter::Nearest,
+            FilterMode::Linear => MagFilter::Linear,
+        })),
+        min_filter: Some(Valid(
+            match (descriptor.min_filter, descriptor.mipmap_filter) {
+                (FilterMode::Nearest, FilterMode::Nearest) => MinFilter::NearestMipmapNearest,
+                (FilterMode::Nearest, FilterMode::Linear) => MinFilter::NearestMipmapLinear,
+                (FilterMode::Linear, FilterMode::Nearest) => MinFilter::LinearMipmapNearest,
+                (FilterMode::Linear, FilterMode::Linear) => MinFilter::LinearMipmapLinear,
+            },
+        )),
+        name: None,
+        wrap_s: Valid(wrapping_mode(descriptor.address_mode_u)),
+        wrap_t: Valid(wrapping_mode(descriptor.address_mode_v)),
+        extensions: None,
+        extras: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_core::CorePlugin;
+    use bevy_math::{Vec2, Vec3};
+    use bevy_render::color::Color;
+    use bevy_transform::hierarchy::BuildWorldChildren;
+
+    #[test]
+    fn export_and_parse() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>()
+            .add_asset::<Image>();
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 2.0, 0.0]],
+        );
+        mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; 3]);
+        mesh.set_indices(Some(Indices::U16(vec![0, 1, 2])));
+        let mesh = app
+            .world
+            .get_resource_mut::<Assets<Mesh>>()
+            .unwrap()
+            .add(mesh);
+        let material = app
+            .world
+            .get_resource_mut::<Assets<StandardMaterial>>()
+            .unwrap()
+            .add(StandardMaterial {
+                base_color: Color::rgba(1.0, 0.5, 0.0, 1.0),
+                unlit: true,
+                ..Default::default()
+            });
+
+        let mut world = World::default();
+        let root = world
+            .spawn()
+            .insert_bundle((Name::new("root"), Transform::from_xyz(1.0, 2.0, 3.0)))
+            .with_children(|parent| {
+                parent.spawn().insert_bundle((
+                    Name::new("triangle"),
+                    Transform::default(),
+                    mesh.clone(),
+                    material.clone(),
+                ));
+                parent.spawn().insert_bundle((
+                    Transform::default(),
+                    mesh.clone(),
+                    material.clone(),
+                ));
+                parent.spawn().insert(PointLight::default());
+            })
+            .id();
+
+        let export = GltfExporter::new(
+            app.world.get_resource().unwrap(),
+            app.world.get_resource().unwrap(),
+            app.world.get_resource().unwrap(),
+        )
+        .with_settings(GltfExportSettings {
+            format: GltfExportFormat::Glb,
+            ..Default::default()
+        })
+        .export_entities(&world, &[root])
+        .unwrap();
+        assert!(export.external_files.is_empty());
+
+        let gltf = gltf::Gltf::from_slice(&export.document).unwrap();
+        let blob = gltf.blob.as_deref();
+        let scene = gltf.default_scene().unwrap();
+        let root = scene.nodes().next().unwrap();
+        assert_eq!(root.name(), Some("root"));
+        assert_eq!(root.transform().decomposed().0, [1.0, 2.0, 3.0]);
+        let children: Vec<_> = root.children().collect();
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[0].name(), Some("triangle"));
+        // entities sharing a mesh and a material share the glTF mesh
+        assert_eq!(gltf.meshes().count(), 1);
+        assert_eq!(gltf.materials().count(), 1);
+        assert!(children[2].light().is_some());
+
+        let primitive = children[0].mesh().unwrap().primitives().next().unwrap();
+        assert_eq!(primitive.bounding_box().max, [1.0, 2.0, 0.0]);
+        let reader = primitive.reader(|_| blob);
+        let positions: Vec<Vec3> = reader.read_positions().unwrap().map(Vec3::from).collect();
+        assert_eq!(positions[2], Vec3::new(0.0, 2.0, 0.0));
+        let indices: Vec<u32> = reader.read_indices().unwrap().into_u32().collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        let material = primitive.material();
+        assert!(material.unlit());
+        assert_eq!(
+            material.pbr_metallic_roughness().base_color_factor(),
+            [1.0, 0.5, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn export_material_extensions() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>()
+            .add_asset::<Image>();
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0, 0.0, 0.0]; 3]);
+        mesh.set_attribute(
+            Mesh::ATTRIBUTE_JOINT_INDEX,
+            VertexAttributeValues::Uint16x4(vec![[0; 4]; 3]),
+        );
+        let mesh = app
+            .world
+            .get_resource_mut::<Assets<Mesh>>()
+            .unwrap()
+            .add(mesh);
+        let material = app
+            .world
+            .get_resource_mut::<Assets<StandardMaterial>>()
+            .unwrap()
+            .add(StandardMaterial {
+                emissive: Color::rgb(4.0, 2.0, 1.0),
+                ..Default::default()
+            });
+        let mut world = World::default();
+        let root = world.spawn().insert_bundle((mesh, material)).id();
+
+        let export = GltfExporter::new(
+            app.world.get_resource().unwrap(),
+            app.world.get_resource().unwrap(),
+            app.world.get_resource().unwrap(),
+        )
+        .export_entities(&world, &[root])
+        .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&export.document).unwrap();
+        assert_eq!(
+            json["extensionsUsed"],
+            serde_json::json!(["KHR_materials_emissive_strength"])
+        );
+        let material = &json["materials"][0];
+        assert_eq!(
+            material["emissiveFactor"],
+            serde_json::json!([1.0, 0.5, 0.25])
+        );
+        assert_eq!(
+            material["extensions"]["KHR_materials_emissive_strength"]["emissiveStrength"],
+            4.0
+        );
+        // joints are only exported with skins
+        let attributes = &json["meshes"][0]["primitives"][0]["attributes"];
+        assert!(attributes.get("JOINTS_0").is_none());
+        assert!(gltf::Gltf::from_slice(&export.document).is_ok());
+    }
+
+    #[test]
+    fn texture_transform_of_uv_transform() {
+        let uv_transform =
+            Mat3::from_scale_angle_translation(Vec2::new(2.0, 0.5), -0.5, Vec2::new(0.25, 0.75));
+        let texture_transform = texture_transform(uv_transform);
+        assert!(Vec2::from(texture_transform.offset.0).abs_diff_eq(Vec2::new(0.25, 0.75), 1e-6));
+        assert!((texture_transform.rotation.0 - 0.5).abs() < 1e-6);
+        assert!(Vec2::from(texture_transform.scale.0).abs_diff_eq(Vec2::new(2.0, 0.5), 1e-6));
+    }
+}
@@ -0,0 +1,137 @@
+use bevy_app::App;
+use bevy_asset::LoadContext;
+use bevy_ecs::world::EntityMut;
+use bevy_pbr::StandardMaterial;
+use serde_json::{Map, Value};
+use std::sync::{Arc, RwLock};
+
+/// The extensions of a glTF object, as raw JSON values keyed by extension name.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GltfExtensions<'a> {
+    extensions: Option<&'a Map<String, Value>>,
+}
+
+impl<'a> GltfExtensions<'a> {
+    /// The extensions of the object at `index` in the top-level `collection` of a glTF document,
+    /// such as `"materials"` or `"nodes"`.
+    pub(crate) fn of(json: &'a Value, collection: &str, index: usize) -> Self {
+        GltfExtensions {
+            extensions: json
+                .get(collection)
+                .and_then(|objects| objects.get(index))
+                .and_then(|object| object.get("extensions"))
+                .and_then(Value::as_object),
+        }
+    }
+
+    /// Returns the JSON value of the extension `name`, if the object uses it
+    pub fn get(&self, name: &str) -> Option<&'a Value> {
+        self.extensions.and_then(|extensions| extensions.get(name))
+    }
+
+    /// Iterates over the names of the extensions used by the object
+    pub fn names(&self) -> impl Iterator<Item = &'a str> {
+        self.extensions
+            .into_iter()
+            .flat_map(|extensions| extensions.keys().map(String::as_str))
+    }
+}
+
+/// Handles glTF extensions that aren't supported by the [`GltfLoader`](crate::GltfLoader), such
+/// as vendor extensions.
+///
+/// Handlers are called after the loader has built the bevy representation of an object, in the
+/// order they were added, and can modify it from the extensions of the object.
+pub trait GltfExtensionHandler: Send + Sync + 'static {
+    /// Called for each material, before the [`StandardMaterial`] is added as a labeled asset
+    fn on_material(
+        &self,
+        _extensions: &GltfExtensions,
+        _material: &mut StandardMaterial,
+        _load_context: &mut LoadContext,
+    ) {
+    }
+
+    /// Called for the entity spawned for each node of a scene, before its children are spawned
+    fn on_node(
+        &self,
+        _extensions: &GltfExtensions,
+        _entity: &mut EntityMut,
+        _load_context: &mut LoadContext,
+    ) {
+    }
+}
+
+/// The [`GltfExtensionHandler`]s used by the [`GltfLoader`](crate::GltfLoader).
+///
+/// Handlers can be added after the loader is created, and are used by the files loaded from then
+/// on.
+#[derive(Clone, Default)]
+pub struct GltfExtensionHandlers {
+    handlers: Arc<RwLock<Vec<Arc<dyn GltfExtensionHandler>>>>,
+}
+
+impl GltfExtensionHandlers {
+    pub fn add(&self, handler: impl GltfExtensionHandler) {
+        self.handlers.write().unwrap().push(Arc::new(handler));
+    }
+
+    /// Returns the handlers added so far
+    pub(crate) fn handlers(&self) -> Vec<Arc<dyn GltfExtensionHandler>> {
+        self.handlers.read().unwrap().clone()
+    }
+}
+
+/// Adds a [`GltfExtensionHandler`] to an [`App`].
+pub trait AddGltfExtensionHandler {
+    fn add_gltf_extension_handler(&mut self, handler: impl GltfExtensionHandler) -> &mut Self;
+}
+
+impl AddGltfExtensionHandler for App {
+    fn add_gltf_extension_handler(&mut self, handler: impl GltfExtensionHandler) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(GltfExtensionHandlers::default)
+            .add(handler);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions_of_object() {
+        let json: Value = serde_json::from_str(
+            r#"{
+                "materials": [
+                    {},
+                    {
+                        "extensions": {
+                            "KHR_materials_emissive_strength": { "emissiveStrength": 4.0 },
+                            "VENDOR_custom": {}
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let extensions = GltfExtensions::of(&json, "materials", 1);
+        assert_eq!(
+            extensions
+                .get("KHR_materials_emissive_strength")
+                .and_then(|extension| extension.get("emissiveStrength"))
+                .and_then(Value::as_f64),
+            Some(4.0)
+        );
+        assert_eq!(
+            extensions.names().collect::<Vec<_>>(),
+            vec!["KHR_materials_emissive_strength", "VENDOR_custom"]
+        );
+        assert!(GltfExtensions::of(&json, "materials", 0)
+            .get("VENDOR_custom")
+            .is_none());
+        assert_eq!(GltfExtensions::of(&json, "nodes", 0).names().count(), 0);
+    }
+}
@@ -0,0 +1,203 @@
+use bevy_ecs::{entity::Entity, reflect::ReflectComponent, world::World};
+use bevy_log::{debug, warn};
+use bevy_reflect::{Reflect, ReflectDeserialize, ReflectMut, TypeRegistry};
+use serde_json::Value;
+use thiserror::Error;
+
+/// An error that occurs when creating a component from the extras of a glTF node.
+#[derive(Error, Debug)]
+enum ExtrasError {
+    #[error("{type_name} has no field {field}")]
+    UnknownField { type_name: String, field: String },
+    #[error("expected {expected} for {type_name}")]
+    UnexpectedValue {
+        type_name: String,
+        expected: &'static str,
+    },
+    #[error("{0} can't be created from JSON, it should be registered with `ReflectDeserialize`")]
+    NotDeserializable(String),
+    #[error("invalid value: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Inserts the components described by the `extras` of a glTF node into its `entity`.
+///
+/// Each key of the extras is the name of a registered component, and its value sets the fields
+/// of the component, like `{"Health": {"max": 100}}`. The fields that aren't set keep the value
+/// they have when the component is created with `FromWorld`. Extras that don't name a component
+/// are ignored.
+pub(crate) fn insert_extras_components(
+    world: &mut World,
+    entity: Entity,
+    extras: &Value,
+    type_registry: &TypeRegistry,
+) {
+    let extras = match extras.as_object() {
+        Some(extras) => extras,
+        None => return,
+    };
+    for (name, value) in extras {
+        let registration = match type_registry
+            .get_with_short_name(name)
+            .or_else(|| type_registry.get_with_name(name))
+        {
+            Some(registration) => registration,
+            None => {
+                debug!(
+                    "glTF extra {} ignored: no type is registered with its name",
+                    name
+                );
+                continue;
+            }
+        };
+        let reflect_component = match registration.data::<ReflectComponent>() {
+            Some(reflect_component) => reflect_component,
+            None => {
+                warn!(
+                    "glTF extra {} ignored: the type isn't a reflected component",
+                    name
+                );
+                continue;
+            }
+        };
+        let mut component = reflect_component.from_world(world);
+        match apply_json(&mut *component, value, type_registry) {
+            Ok(()) => reflect_component.add_component(world, entity, &*component),
+            Err(err) => warn!("glTF extra {} ignored: {}", name, err),
+        }
+    }
+}
+
+/// Sets `value` from `json`. Types registered with [`ReflectDeserialize`] are deserialized,
+/// and structs, tuple structs and tuples are set field by field.
+fn apply_json(
+    value: &mut dyn Reflect,
+    json: &Value,
+    type_registry: &TypeRegistry,
+) -> Result<(), ExtrasError> {
+    if let Some(reflect_deserialize) = type_registry
+        .get_with_name(value.type_name())
+        .and_then(|registration| registration.data::<ReflectDeserialize>())
+    {
+        let deserialized = reflect_deserialize.deserialize(json)?;
+        return value.set(deserialized).map_err(|deserialized| {
+            ExtrasError::NotDeserializable(deserialized.type_name().to_string())
+        });
+    }
+
+    let unexpected_value = |type_name: &str, expected| ExtrasError::UnexpectedValue {
+        type_name: type_name.to_string(),
+        expected,
+    };
+    match value.reflect_mut() {
+        ReflectMut::Struct(struct_value) => {
+            let fields = json
+                .as_object()
+                .ok_or_else(|| unexpected_value(struct_value.type_name(), "an object"))?;
+            for (name, field_json) in fields {
+                match struct_value.field_mut(name) {
+                    Some(field) => apply_json(field, field_json, type_registry)?,
+                    None => {
+                        return Err(ExtrasError::UnknownField {
+                            type_name: struct_value.type_name().to_string(),
+                            field: name.clone(),
+                        })
+                    }
+                }
+            }
+        }
+        ReflectMut::TupleStruct(tuple_struct) => match json.as_array() {
+            Some(fields) if fields.len() == tuple_struct.field_len() => {
+                for (index, field_json) in fields.iter().enumerate() {
+                    let field = tuple_struct.field_mut(index).unwrap();
+                    apply_json(field, field_json, type_registry)?;
+                }
+            }
+            // the value of a newtype can be given directly
+            _ if tuple_struct.field_len() == 1 => {
+                apply_json(tuple_struct.field_mut(0).unwrap(), json, type_registry)?;
+            }
+            _ => return Err(unexpected_value(tuple_struct.type_name(), "an array")),
+        },
+        ReflectMut::Tuple(tuple) => match json.as_array() {
+            Some(fields) if fields.len() == tuple.field_len() => {
+                for (index, field_json) in fields.iter().enumerate() {
+                    apply_json(tuple.field_mut(index).unwrap(), field_json, type_registry)?;
+                }
+            }
+            _ => return Err(unexpected_value(tuple.type_name(), "an array")),
+        },
+        _ => {
+            return Err(ExtrasError::NotDeserializable(
+                value.type_name().to_string(),
+            ))
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::component::Component;
+    use bevy_math::Vec3;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health {
+        max: f32,
+        regeneration: f32,
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Team(u32);
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct SpawnPoint {
+        name: String,
+        offset: Vec3,
+    }
+
+    #[test]
+    fn components_from_extras() {
+        let mut type_registry = TypeRegistry::default();
+        type_registry.register::<f32>();
+        type_registry.register::<u32>();
+        type_registry.register::<String>();
+        type_registry.register::<Vec3>();
+        type_registry.register::<Health>();
+        type_registry.register::<Team>();
+        type_registry.register::<SpawnPoint>();
+
+        let mut world = World::default();
+        let entity = world.spawn().id();
+        let extras: Value = serde_json::from_str(
+            r#"{
+                "Health": { "max": 100 },
+                "Team": 2,
+                "SpawnPoint": { "name": "start", "offset": [0.0, 1.0, 0.0] },
+                "blender_property": 1
+            }"#,
+        )
+        .unwrap();
+        insert_extras_components(&mut world, entity, &extras, &type_registry);
+
+        let health = world.get::<Health>(entity).unwrap();
+        assert_eq!(health.max, 100.0);
+        assert_eq!(health.regeneration, 0.0);
+        assert_eq!(world.get::<Team>(entity).unwrap().0, 2);
+        let spawn_point = world.get::<SpawnPoint>(entity).unwrap();
+        assert_eq!(spawn_point.name, "start");
+        assert_eq!(spawn_point.offset, Vec3::Y);
+
+        // components with invalid fields aren't inserted
+        let entity = world.spawn().id();
+        let extras: Value =
+            serde_json::from_str(r#"{ "Health": { "maximum": 100 }, "Team": "blue" }"#).unwrap();
+        insert_extras_components(&mut world, entity, &extras, &type_registry);
+        assert!(world.get::<Health>(entity).is_none());
+        assert!(world.get::<Team>(entity).is_none());
+    }
+}
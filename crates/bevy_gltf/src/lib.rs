@@ -1,10 +1,16 @@
 use bevy_utils::HashMap;
 
+mod exporter;
+mod extensions;
+mod extras;
 mod loader;
+pub use exporter::*;
+pub use extensions::*;
 pub use loader::*;
 
+use bevy_animation::AnimationClip;
 use bevy_app::prelude::*;
-use bevy_asset::{AddAsset, Handle};
+use bevy_asset::{AddAsset, Assets, Handle};
 use bevy_pbr::StandardMaterial;
 use bevy_reflect::TypeUuid;
 use bevy_render::mesh::Mesh;
@@ -21,6 +27,10 @@ impl Plugin for GltfPlugin {
             .add_asset::<GltfNode>()
             .add_asset::<GltfPrimitive>()
             .add_asset::<GltfMesh>();
+        // the animations are loaded even if the `AnimationPlugin` doesn't play them
+        if !app.world.contains_resource::<Assets<AnimationClip>>() {
+            app.add_asset::<AnimationClip>();
+        }
     }
 }
 
@@ -37,6 +47,8 @@ pub struct Gltf {
     pub nodes: Vec<Handle<GltfNode>>,
     pub named_nodes: HashMap<String, Handle<GltfNode>>,
     pub default_scene: Option<Handle<Scene>>,
+    pub animations: Vec<Handle<AnimationClip>>,
+    pub named_animations: HashMap<String, Handle<AnimationClip>>,
 }
 
 /// A glTF node with all of its child nodes, its [`GltfMesh`] and
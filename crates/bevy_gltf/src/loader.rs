@@ -1,18 +1,29 @@
 use anyhow::Result;
-use bevy_asset::{
-    AssetIoError, AssetLoader, AssetPath, BoxedFuture, Handle, LoadContext, LoadedAsset,
+use bevy_animation::{
+    AnimationClip, EntityPath, FieldKeyframes, FieldTarget, Interpolation, Keyframes, VariableCurve,
 };
+use bevy_asset::{AssetIoError, AssetLoader, BoxedFuture, Handle, LoadContext, LoadedAsset};
 use bevy_core::Name;
-use bevy_ecs::world::World;
+use bevy_ecs::{
+    entity::Entity,
+    world::{FromWorld, World},
+};
 use bevy_log::warn;
-use bevy_math::{Mat4, Vec3};
-use bevy_pbr::{AlphaMode, PbrBundle, StandardMaterial};
+use bevy_math::{Mat3, Mat4, Quat, Vec2, Vec3};
+use bevy_pbr::{
+    AlphaMode, DirectionalLight, DirectionalLightBundle, PbrBundle, PointLight, PointLightBundle,
+    StandardMaterial,
+};
+use bevy_reflect::TypeRegistryArc;
 use bevy_render::{
     camera::{
         Camera, CameraPlugin, CameraProjection, OrthographicProjection, PerspectiveProjection,
     },
     color::Color,
-    mesh::{Indices, Mesh, VertexAttributeValues},
+    mesh::{
+        Indices, Mesh, MorphTarget, MorphWeights, SkinnedMesh, SkinnedMeshInverseBindposes,
+        SkinnedMeshVertexJoints, VertexAttributeValues,
+    },
     primitives::{Aabb, Frustum},
     render_resource::{
         AddressMode, FilterMode, PrimitiveTopology, SamplerDescriptor, TextureFormat,
@@ -27,14 +38,21 @@ use bevy_transform::{
 };
 use bevy_utils::{HashMap, HashSet};
 use gltf::{
+    animation::util::ReadOutputs,
+    khr_lights_punctual::{Kind, Light},
     mesh::Mode,
-    texture::{MagFilter, MinFilter, WrappingMode},
+    texture::{MagFilter, MinFilter, TextureTransform, WrappingMode},
     Material, Primitive,
 };
-use std::{collections::VecDeque, path::Path};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::VecDeque, path::Path, sync::Arc};
 use thiserror::Error;
 
-use crate::{Gltf, GltfNode};
+use crate::{
+    extras::insert_extras_components, Gltf, GltfExtensionHandler, GltfExtensionHandlers,
+    GltfExtensions, GltfNode,
+};
 
 /// An error that occurs when loading a glTF file.
 #[derive(Error, Debug)]
@@ -55,19 +73,76 @@ pub enum GltfError {
     ImageError(#[from] TextureError),
     #[error("failed to load an asset path: {0}")]
     AssetIoError(#[from] AssetIoError),
+    #[error("missing keyframes in animation {0}")]
+    MissingAnimationSampler(usize),
+    #[error("invalid glTF JSON: {0}")]
+    Json(#[from] serde_json::Error),
 }
 
 /// Loads glTF files with all of their data as their corresponding bevy representations.
-#[derive(Default)]
-pub struct GltfLoader;
+///
+/// The extensions `KHR_lights_punctual`, `KHR_texture_transform` and
+/// `KHR_materials_emissive_strength` are supported, and other extensions can be handled by
+/// adding a [`GltfExtensionHandler`] to the [`GltfExtensionHandlers`] resource.
+///
+/// The `extras` of the nodes can describe reflected components to insert into the entities of
+/// the nodes, like `{"Health": {"max": 100}}` for a registered `Health` component. They are
+/// ignored when the app has no [`TypeRegistryArc`].
+///
+/// What is loaded can be chosen with [`GltfLoaderSettings`].
+pub struct GltfLoader {
+    extension_handlers: GltfExtensionHandlers,
+    type_registry: Option<TypeRegistryArc>,
+}
+
+impl FromWorld for GltfLoader {
+    fn from_world(world: &mut World) -> Self {
+        GltfLoader {
+            extension_handlers: world
+                .get_resource_or_insert_with(GltfExtensionHandlers::default)
+                .clone(),
+            type_registry: world.get_resource::<TypeRegistryArc>().cloned(),
+        }
+    }
+}
+
+/// Settings of the [`GltfLoader`], see
+/// [`AssetServer::load_with_settings`](bevy_asset::AssetServer::load_with_settings).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GltfLoaderSettings {
+    /// Whether the textures are loaded. When they aren't, the materials have no textures.
+    pub load_textures: bool,
+    /// Whether the animations are loaded. When they aren't, [`Gltf::animations`] is empty.
+    pub load_animations: bool,
+}
+
+impl Default for GltfLoaderSettings {
+    fn default() -> Self {
+        GltfLoaderSettings {
+            load_textures: true,
+            load_animations: true,
+        }
+    }
+}
 
 impl AssetLoader for GltfLoader {
+    type Settings = GltfLoaderSettings;
+
     fn load<'a>(
         &'a self,
         bytes: &'a [u8],
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<()>> {
-        Box::pin(async move { Ok(load_gltf(bytes, load_context).await?) })
+        Box::pin(async move {
+            let extension_handlers = self.extension_handlers.handlers();
+            Ok(load_gltf(
+                bytes,
+                load_context,
+                &extension_handlers,
+                self.type_registry.as_ref(),
+            )
+            .await?)
+        })
     }
 
     fn extensions(&self) -> &[&str] {
@@ -79,15 +154,23 @@ impl AssetLoader for GltfLoader {
 async fn load_gltf<'a, 'b>(
     bytes: &'a [u8],
     load_context: &'a mut LoadContext<'b>,
+    extension_handlers: &'a [Arc<dyn GltfExtensionHandler>],
+    type_registry: Option<&'a TypeRegistryArc>,
 ) -> Result<(), GltfError> {
+    let settings = load_context.settings::<GltfLoaderSettings>().clone();
     let gltf = gltf::Gltf::from_slice(bytes)?;
     let buffer_data = load_buffers(&gltf, load_context, load_context.path()).await?;
+    let json = raw_json(bytes)?;
+    let extensions = ExtensionContext {
+        json: &json,
+        handlers: extension_handlers,
+    };
 
     let mut materials = vec![];
     let mut named_materials = HashMap::default();
     let mut linear_textures = HashSet::default();
     for material in gltf.materials() {
-        let handle = load_material(&material, load_context);
+        let handle = load_material(&material, load_context, extensions);
         if let Some(name) = material.name() {
             named_materials.insert(name.to_string(), handle.clone());
         }
@@ -150,6 +233,26 @@ async fn load_gltf<'a, 'b>(
                 mesh.set_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
             }
 
+            // the joints are kept out of the mesh, which is rendered with the joints of its
+            // vertices only once skinned
+            let mut vertex_joints = match (reader.read_joints(0), reader.read_weights(0)) {
+                (Some(indices), Some(weights)) => Some(SkinnedMeshVertexJoints {
+                    indices: indices.into_u16().collect(),
+                    weights: weights.into_f32().collect(),
+                }),
+                _ => None,
+            };
+
+            let morph_targets: Vec<_> = reader
+                .read_morph_targets()
+                .map(|(positions, normals, tangents)| MorphTarget {
+                    positions: positions.map(|v| v.collect()),
+                    normals: normals.map(|v| v.collect()),
+                    tangents: tangents.map(|v| v.collect()),
+                })
+                .collect();
+            mesh.set_morph_targets(morph_targets);
+
             // if let Some(vertex_attribute) = reader
             //     .read_colors(0)
             //     .map(|v| VertexAttributeValues::Float32x4(v.into_rgba_f32().collect()))
@@ -162,6 +265,20 @@ async fn load_gltf<'a, 'b>(
             };
 
             if mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_none() {
+                if let (Some(vertex_joints), Some(Indices::U32(indices))) =
+                    (&mut vertex_joints, mesh.indices())
+                {
+                    *vertex_joints = SkinnedMeshVertexJoints {
+                        indices: indices
+                            .iter()
+                            .map(|i| vertex_joints.indices[*i as usize])
+                            .collect(),
+                        weights: indices
+                            .iter()
+                            .map(|i| vertex_joints.weights[*i as usize])
+                            .collect(),
+                    };
+                }
                 let vertex_count_before = mesh.count_vertices();
                 mesh.duplicate_vertices();
                 mesh.compute_flat_normals();
@@ -177,6 +294,12 @@ async fn load_gltf<'a, 'b>(
             }
 
             let mesh = load_context.set_labeled_asset(&primitive_label, LoadedAsset::new(mesh));
+            if let Some(vertex_joints) = vertex_joints {
+                load_context.set_labeled_asset(
+                    &primitive_joints_label(&primitive_label),
+                    LoadedAsset::new(vertex_joints),
+                );
+            }
             primitives.push(super::GltfPrimitive {
                 mesh,
                 material: primitive
@@ -245,7 +368,7 @@ async fn load_gltf<'a, 'b>(
 
     // TODO: use the threaded impl on wasm once wasm thread pool doesn't deadlock on it
     #[cfg(target_arch = "wasm32")]
-    for gltf_texture in gltf.textures() {
+    for gltf_texture in gltf.textures().filter(|_| settings.load_textures) {
         let (texture, label) =
             load_texture(gltf_texture, &buffer_data, &linear_textures, &load_context).await?;
         load_context.set_labeled_asset(&label, LoadedAsset::new(texture));
@@ -255,6 +378,9 @@ async fn load_gltf<'a, 'b>(
     load_context
         .task_pool()
         .scope(|scope| {
+            if !settings.load_textures {
+                return;
+            }
             gltf.textures().for_each(|gltf_texture| {
                 let linear_textures = &linear_textures;
                 let load_context: &LoadContext = load_context;
@@ -275,17 +401,119 @@ async fn load_gltf<'a, 'b>(
             load_context.set_labeled_asset(&label, LoadedAsset::new(texture));
         });
 
+    let skinned_mesh_inverse_bindposes: Vec<_> = gltf
+        .skins()
+        .map(|gltf_skin| {
+            let reader = gltf_skin.reader(|buffer| Some(&buffer_data[buffer.index()]));
+            let inverse_bindposes: Vec<Mat4> = match reader.read_inverse_bind_matrices() {
+                Some(matrices) => matrices.map(|mat| Mat4::from_cols_array_2d(&mat)).collect(),
+                // the inverse bind matrices default to identity matrices
+                None => vec![Mat4::IDENTITY; gltf_skin.joints().count()],
+            };
+            load_context.set_labeled_asset(
+                &skin_label(&gltf_skin),
+                LoadedAsset::new(SkinnedMeshInverseBindposes::from(inverse_bindposes)),
+            )
+        })
+        .collect();
+
+    let mut animations = vec![];
+    let mut named_animations = HashMap::default();
+    let node_paths = node_entity_paths(&gltf);
+    for animation in gltf.animations().filter(|_| settings.load_animations) {
+        let mut animation_clip = AnimationClip::default();
+        for channel in animation.channels() {
+            let node = channel.target().node();
+            let path = match node_paths.get(&node.index()) {
+                Some(path) => path,
+                None => {
+                    warn!(
+                        "Animation ignored for node {}: the node or one of its ancestors is \
+                        unnamed, or the node isn't part of a scene",
+                        node.index()
+                    );
+                    continue;
+                }
+            };
+            let reader = channel.reader(|buffer| Some(&buffer_data[buffer.index()]));
+            let keyframe_timestamps: Vec<f32> = match reader.read_inputs() {
+                Some(inputs) => inputs.collect(),
+                None => return Err(GltfError::MissingAnimationSampler(animation.index())),
+            };
+            let interpolation = match channel.sampler().interpolation() {
+                gltf::animation::Interpolation::Step => Interpolation::Step,
+                gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+            };
+            let keyframes = match reader.read_outputs() {
+                Some(ReadOutputs::Translations(translations)) => {
+                    Keyframes::Translation(translations.map(Vec3::from).collect())
+                }
+                Some(ReadOutputs::Rotations(rotations)) => Keyframes::Rotation(
+                    rotations
+                        .into_f32()
+                        .map(|rotation| Quat::from_vec4(rotation.into()))
+                        .collect(),
+                ),
+                Some(ReadOutputs::Scales(scales)) => {
+                    Keyframes::Scale(scales.map(Vec3::from).collect())
+                }
+                Some(ReadOutputs::MorphTargetWeights(weights)) => {
+                    let weights: Vec<f32> = weights.into_f32().collect();
+                    let primitives = node.mesh().into_iter().flat_map(|mesh| mesh.primitives());
+                    for primitive in primitives {
+                        add_morph_weight_curves(
+                            &mut animation_clip,
+                            path.child(primitive_name(&primitive)),
+                            &keyframe_timestamps,
+                            &weights,
+                            interpolation,
+                        );
+                    }
+                    continue;
+                }
+                None => return Err(GltfError::MissingAnimationSampler(animation.index())),
+            };
+            animation_clip.add_curve_to_path(
+                path.clone(),
+                VariableCurve {
+                    keyframe_timestamps,
+                    keyframes,
+                    interpolation,
+                },
+            );
+        }
+        let handle = load_context.set_labeled_asset(
+            &animation_label(&animation),
+            LoadedAsset::new(animation_clip),
+        );
+        if let Some(name) = animation.name() {
+            named_animations.insert(name.to_string(), handle.clone());
+        }
+        animations.push(handle);
+    }
+
     let mut scenes = vec![];
     let mut named_scenes = HashMap::default();
     for scene in gltf.scenes() {
         let mut err = None;
         let mut world = World::default();
+        let mut node_index_to_entity_map = HashMap::default();
+        let mut entity_to_skin_index_map = HashMap::default();
         world
             .spawn()
             .insert_bundle((Transform::identity(), GlobalTransform::identity()))
             .with_children(|parent| {
                 for node in scene.nodes() {
-                    let result = load_node(&node, parent, load_context, &buffer_data);
+                    let result = load_node(
+                        &node,
+                        parent,
+                        load_context,
+                        &buffer_data,
+                        extensions,
+                        &mut node_index_to_entity_map,
+                        &mut entity_to_skin_index_map,
+                    );
                     if result.is_err() {
                         err = Some(result);
                         return;
@@ -295,6 +523,32 @@ async fn load_gltf<'a, 'b>(
         if let Some(Err(err)) = err {
             return Err(err);
         }
+
+        // the joints of a skin are only known once all the nodes of the scene are spawned
+        for (&entity, &skin_index) in entity_to_skin_index_map.iter() {
+            let skin = gltf.skins().nth(skin_index).unwrap();
+            let joints = skin
+                .joints()
+                .filter_map(|joint| node_index_to_entity_map.get(&joint.index()).copied())
+                .collect();
+            let mut entity = world.entity_mut(entity);
+            let mut skinned_mesh = entity.get_mut::<SkinnedMesh>().unwrap();
+            skinned_mesh.inverse_bindposes = skinned_mesh_inverse_bindposes[skin_index].clone();
+            skinned_mesh.joints = joints;
+        }
+
+        if let Some(type_registry) = type_registry {
+            let type_registry = type_registry.read();
+            for (&node_index, &entity) in node_index_to_entity_map.iter() {
+                let extras = json
+                    .get("nodes")
+                    .and_then(|nodes| nodes.get(node_index))
+                    .and_then(|node| node.get("extras"));
+                if let Some(extras) = extras {
+                    insert_extras_components(&mut world, entity, extras, &type_registry);
+                }
+            }
+        }
         let scene_handle = load_context
             .set_labeled_asset(&scene_label(&scene), LoadedAsset::new(Scene::new(world)));
 
@@ -317,6 +571,8 @@ async fn load_gltf<'a, 'b>(
         named_materials,
         nodes,
         named_nodes,
+        animations,
+        named_animations,
     }));
 
     Ok(())
@@ -369,80 +625,116 @@ async fn load_texture<'a>(
     Ok((texture, texture_label(&gltf_texture)))
 }
 
-/// Loads a glTF material as a bevy [`StandardMaterial`] and returns it.
-fn load_material(material: &Material, load_context: &mut LoadContext) -> Handle<StandardMaterial> {
-    let material_label = material_label(material);
-
-    let pbr = material.pbr_metallic_roughness();
+/// The raw JSON of a glTF file, to read the extensions and extras that aren't parsed by [`gltf`],
+/// and the [`GltfExtensionHandler`]s to call with the extensions.
+#[derive(Clone, Copy)]
+struct ExtensionContext<'a> {
+    json: &'a Value,
+    handlers: &'a [Arc<dyn GltfExtensionHandler>],
+}
 
-    let color = pbr.base_color_factor();
-    let base_color_texture = if let Some(info) = pbr.base_color_texture() {
-        // TODO: handle info.tex_coord() (the *set* index for the right texcoords)
-        let label = texture_label(&info.texture());
-        let path = AssetPath::new_ref(load_context.path(), Some(&label));
-        Some(load_context.get_handle(path))
+/// Parses the JSON of a `.gltf` file, or the JSON chunk of a `.glb` file.
+fn raw_json(bytes: &[u8]) -> Result<Value, GltfError> {
+    if bytes.starts_with(b"glTF") {
+        let glb = gltf::Glb::from_slice(bytes)?;
+        Ok(serde_json::from_slice(&glb.json)?)
     } else {
-        None
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Loads a glTF material as a bevy [`StandardMaterial`] and returns it.
+fn load_material(
+    material: &Material,
+    load_context: &mut LoadContext,
+    extensions: ExtensionContext,
+) -> Handle<StandardMaterial> {
+    let material_label = material_label(material);
+    let material_extensions = match material.index() {
+        Some(index) => GltfExtensions::of(extensions.json, "materials", index),
+        // the default material has no extensions
+        None => GltfExtensions::default(),
     };
 
-    let normal_map_texture: Option<Handle<Image>> =
-        if let Some(normal_texture) = material.normal_texture() {
-            // TODO: handle normal_texture.scale
-            // TODO: handle normal_texture.tex_coord() (the *set* index for the right texcoords)
-            let label = texture_label(&normal_texture.texture());
-            let path = AssetPath::new_ref(load_context.path(), Some(&label));
-            Some(load_context.get_handle(path))
+    let pbr = material.pbr_metallic_roughness();
+    let load_textures = load_context.settings::<GltfLoaderSettings>().load_textures;
+    let texture_handle = |texture: gltf::Texture| -> Option<Handle<Image>> {
+        if load_textures {
+            Some(load_context.get_labeled_handle(&texture_label(&texture)))
         } else {
             None
-        };
-
-    let metallic_roughness_texture = if let Some(info) = pbr.metallic_roughness_texture() {
-        // TODO: handle info.tex_coord() (the *set* index for the right texcoords)
-        let label = texture_label(&info.texture());
-        let path = AssetPath::new_ref(load_context.path(), Some(&label));
-        Some(load_context.get_handle(path))
-    } else {
-        None
-    };
-
-    let occlusion_texture = if let Some(occlusion_texture) = material.occlusion_texture() {
-        // TODO: handle occlusion_texture.tex_coord() (the *set* index for the right texcoords)
-        // TODO: handle occlusion_texture.strength() (a scalar multiplier for occlusion strength)
-        let label = texture_label(&occlusion_texture.texture());
-        let path = AssetPath::new_ref(load_context.path(), Some(&label));
-        Some(load_context.get_handle(path))
-    } else {
-        None
+        }
     };
 
-    let emissive = material.emissive_factor();
-    let emissive_texture = if let Some(info) = material.emissive_texture() {
-        // TODO: handle occlusion_texture.tex_coord() (the *set* index for the right texcoords)
-        // TODO: handle occlusion_texture.strength() (a scalar multiplier for occlusion strength)
-        let label = texture_label(&info.texture());
-        let path = AssetPath::new_ref(load_context.path(), Some(&label));
-        Some(load_context.get_handle(path))
-    } else {
-        None
+    let color = pbr.base_color_factor();
+    // TODO: handle info.tex_coord() (the *set* index for the right texcoords)
+    let base_color_texture = pbr
+        .base_color_texture()
+        .and_then(|info| texture_handle(info.texture()));
+
+    // TODO: handle normal_texture.scale
+    // TODO: handle normal_texture.tex_coord() (the *set* index for the right texcoords)
+    let normal_map_texture = material
+        .normal_texture()
+        .and_then(|normal_texture| texture_handle(normal_texture.texture()));
+
+    // TODO: handle info.tex_coord() (the *set* index for the right texcoords)
+    let metallic_roughness_texture = pbr
+        .metallic_roughness_texture()
+        .and_then(|info| texture_handle(info.texture()));
+
+    // TODO: handle occlusion_texture.tex_coord() (the *set* index for the right texcoords)
+    // TODO: handle occlusion_texture.strength() (a scalar multiplier for occlusion strength)
+    let occlusion_texture = material
+        .occlusion_texture()
+        .and_then(|occlusion_texture| texture_handle(occlusion_texture.texture()));
+
+    let emissive_strength = material_extensions
+        .get("KHR_materials_emissive_strength")
+        .and_then(|extension| extension.get("emissiveStrength"))
+        .and_then(Value::as_f64)
+        .unwrap_or(1.0) as f32;
+    let emissive = material.emissive_factor().map(|c| c * emissive_strength);
+    // TODO: handle emissive_texture.tex_coord() (the *set* index for the right texcoords)
+    let emissive_texture = material
+        .emissive_texture()
+        .and_then(|info| texture_handle(info.texture()));
+
+    // a single transform is applied to all the textures, so the one of the base color is used
+    let uv_transform = pbr
+        .base_color_texture()
+        .and_then(|info| info.texture_transform())
+        .map_or(Mat3::IDENTITY, |transform| uv_transform(&transform));
+
+    let mut standard_material = StandardMaterial {
+        base_color: Color::rgba(color[0], color[1], color[2], color[3]),
+        base_color_texture,
+        perceptual_roughness: pbr.roughness_factor(),
+        metallic: pbr.metallic_factor(),
+        metallic_roughness_texture,
+        normal_map_texture,
+        double_sided: material.double_sided(),
+        occlusion_texture,
+        emissive: Color::rgba(emissive[0], emissive[1], emissive[2], 1.0),
+        emissive_texture,
+        unlit: material.unlit(),
+        alpha_mode: alpha_mode(material),
+        uv_transform,
+        ..Default::default()
     };
+    for handler in extensions.handlers {
+        handler.on_material(&material_extensions, &mut standard_material, load_context);
+    }
+    load_context.set_labeled_asset(&material_label, LoadedAsset::new(standard_material))
+}
 
-    load_context.set_labeled_asset(
-        &material_label,
-        LoadedAsset::new(StandardMaterial {
-            base_color: Color::rgba(color[0], color[1], color[2], color[3]),
-            base_color_texture,
-            perceptual_roughness: pbr.roughness_factor(),
-            metallic: pbr.metallic_factor(),
-            metallic_roughness_texture,
-            normal_map_texture,
-            double_sided: material.double_sided(),
-            occlusion_texture,
-            emissive: Color::rgba(emissive[0], emissive[1], emissive[2], 1.0),
-            emissive_texture,
-            unlit: material.unlit(),
-            alpha_mode: alpha_mode(material),
-            ..Default::default()
-        }),
+/// Returns the transform of the texture coordinates described by a `KHR_texture_transform`.
+fn uv_transform(transform: &TextureTransform) -> Mat3 {
+    // the rotation is counterclockwise in texture space, whose y axis points down
+    Mat3::from_scale_angle_translation(
+        Vec2::from(transform.scale()),
+        -transform.rotation(),
+        Vec2::from(transform.offset()),
     )
 }
 
@@ -452,6 +744,9 @@ fn load_node(
     world_builder: &mut WorldChildBuilder,
     load_context: &mut LoadContext,
     buffer_data: &[Vec<u8>],
+    extensions: ExtensionContext,
+    node_index_to_entity_map: &mut HashMap<usize, Entity>,
+    entity_to_skin_index_map: &mut HashMap<Entity, usize>,
 ) -> Result<(), GltfError> {
     let transform = gltf_node.transform();
     let mut gltf_error = None;
@@ -459,10 +754,10 @@ fn load_node(
         Transform::from_matrix(Mat4::from_cols_array_2d(&transform.matrix())),
         GlobalTransform::identity(),
     ));
-
-    if let Some(name) = gltf_node.name() {
-        node.insert(Name::new(name.to_string()));
+    if let Some(name) = node_name(gltf_node) {
+        node.insert(name);
     }
+    node_index_to_entity_map.insert(gltf_node.index(), node.id());
 
     // create camera node
     if let Some(camera) = gltf_node.camera() {
@@ -516,7 +811,16 @@ fn load_node(
         }
     }
 
+    let node_extensions = GltfExtensions::of(extensions.json, "nodes", gltf_node.index());
+    for handler in extensions.handlers {
+        handler.on_node(&node_extensions, &mut node, load_context);
+    }
+
     node.with_children(|parent| {
+        if let Some(light) = gltf_node.light() {
+            spawn_light(&light, parent);
+        }
+
         if let Some(mesh) = gltf_node.mesh() {
             // append primitives
             for primitive in mesh.primitives() {
@@ -527,32 +831,72 @@ fn load_node(
                 // added when iterating over all the gltf materials (since the default material is
                 // not explicitly listed in the gltf).
                 if !load_context.has_labeled_asset(&material_label) {
-                    load_material(&material, load_context);
+                    load_material(&material, load_context, extensions);
                 }
 
                 let primitive_label = primitive_label(&mesh, &primitive);
-                let mesh_asset_path =
-                    AssetPath::new_ref(load_context.path(), Some(&primitive_label));
-                let material_asset_path =
-                    AssetPath::new_ref(load_context.path(), Some(&material_label));
-
-                let bounds = primitive.bounding_box();
-                parent
-                    .spawn_bundle(PbrBundle {
-                        mesh: load_context.get_handle(mesh_asset_path),
-                        material: load_context.get_handle(material_asset_path),
-                        ..Default::default()
+
+                let mesh_handle: Handle<Mesh> = load_context.get_labeled_handle(&primitive_label);
+                let morph_target_count = primitive.morph_targets().len();
+                let morph_weights = if morph_target_count > 0 {
+                    let weights = match gltf_node.weights().or_else(|| mesh.weights()) {
+                        Some(weights) if weights.len() == morph_target_count => weights.to_vec(),
+                        _ => vec![0.0; morph_target_count],
+                    };
+                    Some(MorphWeights {
+                        mesh: mesh_handle.clone(),
+                        weights,
                     })
-                    .insert(Aabb::from_min_max(
+                } else {
+                    None
+                };
+                let is_deformed = gltf_node.skin().is_some() || morph_weights.is_some();
+
+                // the mesh of a skinned or morphed entity is created by deforming the primitive,
+                // and its bounds by `calculate_bounds` once it's deformed
+                let mut primitive_entity = parent.spawn_bundle(PbrBundle {
+                    mesh: if is_deformed {
+                        Handle::default()
+                    } else {
+                        mesh_handle.clone()
+                    },
+                    material: load_context.get_labeled_handle(&material_label),
+                    ..Default::default()
+                });
+                primitive_entity.insert(primitive_name(&primitive));
+                if let Some(morph_weights) = morph_weights {
+                    primitive_entity.insert(morph_weights);
+                }
+                if let Some(skin) = gltf_node.skin() {
+                    primitive_entity.insert(SkinnedMesh {
+                        mesh: mesh_handle,
+                        vertex_joints: load_context
+                            .get_labeled_handle(&primitive_joints_label(&primitive_label)),
+                        ..Default::default()
+                    });
+                    entity_to_skin_index_map.insert(primitive_entity.id(), skin.index());
+                }
+                if !is_deformed {
+                    let bounds = primitive.bounding_box();
+                    primitive_entity.insert(Aabb::from_min_max(
                         Vec3::from_slice(&bounds.min),
                         Vec3::from_slice(&bounds.max),
                     ));
+                }
             }
         }
 
         // append other nodes
         for child in gltf_node.children() {
-            if let Err(err) = load_node(&child, parent, load_context, buffer_data) {
+            if let Err(err) = load_node(
+                &child,
+                parent,
+                load_context,
+                buffer_data,
+                extensions,
+                node_index_to_entity_map,
+                entity_to_skin_index_map,
+            ) {
                 gltf_error = Some(err);
                 return;
             }
@@ -565,6 +909,131 @@ fn load_node(
     }
 }
 
+/// Spawns a light of the `KHR_lights_punctual` extension as a child of the entity of its node.
+///
+/// Like the lights of the extension, the spawned light points along the `-Z` axis of its node.
+fn spawn_light(light: &Light, parent: &mut WorldChildBuilder) {
+    let [r, g, b] = light.color();
+    let color = Color::rgb(r, g, b);
+    let mut light_entity = match light.kind() {
+        Kind::Directional => parent.spawn_bundle(DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                color,
+                // the intensity of directional lights is in lux
+                illuminance: light.intensity(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        kind => {
+            if let Kind::Spot { .. } = kind {
+                warn!(
+                    "Spot light {} is imported as a point light: spot lights aren't supported",
+                    light.index()
+                );
+            }
+            let mut point_light = PointLight {
+                color,
+                // the intensity of point lights is in candela, and converted to lumens
+                intensity: light.intensity() * 4.0 * std::f32::consts::PI,
+                ..Default::default()
+            };
+            if let Some(range) = light.range() {
+                point_light.range = range;
+            }
+            parent.spawn_bundle(PointLightBundle {
+                point_light,
+                ..Default::default()
+            })
+        }
+    };
+    if let Some(name) = light.name() {
+        light_entity.insert(Name::new(name.to_string()));
+    }
+}
+
+/// Returns the [`Name`] of the entity spawned for the `node`, targeting it in animations, if
+/// the node is named.
+fn node_name(node: &gltf::Node) -> Option<Name> {
+    node.name().map(|name| Name::new(name.to_string()))
+}
+
+/// Returns the [`Name`] of the entity spawned for the `primitive`, a child of the entity of its
+/// node.
+fn primitive_name(primitive: &Primitive) -> Name {
+    Name::new(format!("Primitive{}", primitive.index()))
+}
+
+/// Adds a curve animating the weight of each morph target of the primitive at `path`.
+///
+/// `weights` holds the weights of all the morph targets for each keyframe, or their in-tangents,
+/// weights and out-tangents with a cubic spline interpolation.
+fn add_morph_weight_curves(
+    animation_clip: &mut AnimationClip,
+    path: EntityPath,
+    keyframe_timestamps: &[f32],
+    weights: &[f32],
+    interpolation: Interpolation,
+) {
+    let values_per_keyframe = match interpolation {
+        Interpolation::CubicSpline => 3,
+        Interpolation::Step | Interpolation::Linear => 1,
+    };
+    let value_count = keyframe_timestamps.len() * values_per_keyframe;
+    if value_count == 0 {
+        return;
+    }
+    let morph_target_count = weights.len() / value_count;
+    for morph_target in 0..morph_target_count {
+        let values = weights
+            .chunks_exact(morph_target_count)
+            .map(|weights| weights[morph_target])
+            .collect();
+        animation_clip.add_curve_to_path(
+            path.clone(),
+            VariableCurve {
+                keyframe_timestamps: keyframe_timestamps.to_vec(),
+                keyframes: Keyframes::Field(
+                    FieldTarget::new(
+                        std::any::type_name::<MorphWeights>(),
+                        format!("weights[{}]", morph_target),
+                    ),
+                    FieldKeyframes::F32(values),
+                ),
+                interpolation,
+            },
+        );
+    }
+}
+
+/// Returns the [`EntityPath`] of each node of the scenes from the scene root, for the nodes
+/// that are named like all their ancestors.
+fn node_entity_paths(gltf: &gltf::Gltf) -> HashMap<usize, EntityPath> {
+    fn paths_recur(
+        node: gltf::Node,
+        parent_path: &EntityPath,
+        paths: &mut HashMap<usize, EntityPath>,
+    ) {
+        // the entities of unnamed nodes and of their descendants can't be targeted
+        let path = match node_name(&node) {
+            Some(name) => parent_path.child(name),
+            None => return,
+        };
+        for child in node.children() {
+            paths_recur(child, &path, paths);
+        }
+        paths.insert(node.index(), path);
+    }
+
+    let mut paths = HashMap::default();
+    for scene in gltf.scenes() {
+        for node in scene.nodes() {
+            paths_recur(node, &EntityPath::default(), &mut paths);
+        }
+    }
+    paths
+}
+
 /// Returns the label for the `mesh`.
 fn mesh_label(mesh: &gltf::Mesh) -> String {
     format!("Mesh{}", mesh.index())
@@ -575,6 +1044,11 @@ fn primitive_label(mesh: &gltf::Mesh, primitive: &Primitive) -> String {
     format!("Mesh{}/Primitive{}", mesh.index(), primitive.index())
 }
 
+/// Returns the label for the joints of the vertices of the primitive labeled `primitive_label`.
+fn primitive_joints_label(primitive_label: &str) -> String {
+    format!("{}/Joints", primitive_label)
+}
+
 /// Returns the label for the `material`.
 fn material_label(material: &gltf::Material) -> String {
     if let Some(index) = material.index() {
@@ -594,6 +1068,16 @@ fn node_label(node: &gltf::Node) -> String {
     format!("Node{}", node.index())
 }
 
+/// Returns the label for the `skin`.
+fn skin_label(skin: &gltf::Skin) -> String {
+    format!("Skin{}/InverseBindMatrices", skin.index())
+}
+
+/// Returns the label for the `animation`.
+fn animation_label(animation: &gltf::Animation) -> String {
+    format!("Animation{}", animation.index())
+}
+
 /// Returns the label for the `scene`.
 fn scene_label(scene: &gltf::Scene) -> String {
     format!("Scene{}", scene.index())
@@ -812,8 +1296,24 @@ impl<'a> DataUri<'a> {
 mod test {
     use std::path::PathBuf;
 
-    use super::resolve_node_hierarchy;
-    use crate::GltfNode;
+    use super::{add_morph_weight_curves, resolve_node_hierarchy};
+    use crate::{Gltf, GltfLoader, GltfNode};
+    use bevy_animation::{
+        AnimationClip, EntityPath, FieldKeyframes, Interpolation, Keyframes, VariableCurve,
+    };
+    use bevy_app::App;
+    use bevy_asset::{
+        AddAsset, AssetPath, AssetPlugin, AssetServer, Assets, Handle, LoadState,
+        MEMORY_ASSET_SOURCE,
+    };
+    use bevy_core::{CorePlugin, Name};
+    use bevy_ecs::{
+        component::Component,
+        reflect::ReflectComponent,
+        world::{FromWorld, World},
+    };
+    use bevy_reflect::Reflect;
+    use bevy_scene::Scene;
 
     impl GltfNode {
         fn empty() -> Self {
@@ -915,6 +1415,41 @@ mod test {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn morph_weight_curves() {
+        let mut animation_clip = AnimationClip::default();
+        let path = EntityPath::default();
+        // in-tangents, weights and out-tangents of 2 morph targets at 2 keyframes
+        let weights = [
+            0.0, 0.0, 0.1, 0.2, 1.0, 1.0, //
+            0.0, 0.0, 0.3, 0.4, 1.0, 1.0,
+        ];
+        add_morph_weight_curves(
+            &mut animation_clip,
+            path.clone(),
+            &[0.0, 1.0],
+            &weights,
+            Interpolation::CubicSpline,
+        );
+
+        let curves = animation_clip.curves_for_path(&path);
+        assert_eq!(curves.len(), 2);
+        let values = |curve: &VariableCurve| match &curve.keyframes {
+            Keyframes::Field(target, FieldKeyframes::F32(values)) => {
+                (target.path.clone(), values.clone())
+            }
+            keyframes => panic!("unexpected keyframes {:?}", keyframes),
+        };
+        assert_eq!(
+            values(&curves[0]),
+            ("weights[0]".to_string(), vec![0.0, 0.1, 1.0, 0.0, 0.3, 1.0])
+        );
+        assert_eq!(
+            values(&curves[1]),
+            ("weights[1]".to_string(), vec![0.0, 0.2, 1.0, 0.0, 0.4, 1.0])
+        );
+    }
+
     #[test]
     fn node_hierarchy_missing_node() {
         let result = resolve_node_hierarchy(
@@ -929,4 +1464,59 @@ mod test {
         assert_eq!(result[0].0, "l2");
         assert_eq!(result[0].1.children.len(), 0);
     }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(f32);
+
+    #[test]
+    fn loader_without_type_registry() {
+        let mut world = World::default();
+        let loader = GltfLoader::from_world(&mut world);
+        assert!(loader.type_registry.is_none());
+
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Gltf>()
+            .add_asset::<GltfNode>()
+            .add_asset::<Scene>()
+            // registered in the app, but unknown to the loader
+            .register_type::<Health>();
+        let asset_server = app.world.get_resource::<AssetServer>().unwrap().clone();
+        asset_server.add_loader(loader);
+        asset_server.memory_assets().insert(
+            "level.gltf",
+            br#"{
+                "asset": { "version": "2.0" },
+                "scenes": [{ "nodes": [0] }],
+                "nodes": [{ "name": "spawn", "extras": { "Health": 10.0 } }]
+            }"#
+            .as_ref(),
+        );
+        let gltf: Handle<Gltf> = asset_server
+            .load(AssetPath::new("level.gltf".into(), None).with_source(MEMORY_ASSET_SOURCE));
+        for _ in 0..100 {
+            app.update();
+            if asset_server.get_load_state(&gltf) != LoadState::Loading {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(asset_server.get_load_state(&gltf), LoadState::Loaded);
+
+        let scene = app
+            .world
+            .get_resource::<Assets<Gltf>>()
+            .unwrap()
+            .get(&gltf)
+            .unwrap()
+            .scenes[0]
+            .clone();
+        let mut scenes = app.world.get_resource_mut::<Assets<Scene>>().unwrap();
+        let scene_world = &mut scenes.get_mut(&scene).unwrap().world;
+        let names: Vec<_> = scene_world.query::<&Name>().iter(scene_world).collect();
+        assert_eq!(names, vec![&Name::new("spawn")]);
+        assert_eq!(scene_world.query::<&Health>().iter(scene_world).count(), 0);
+    }
 }
@@ -9,6 +9,7 @@ use bevy_app::{PluginGroup, PluginGroupBuilder};
 /// * [`WindowPlugin`](bevy_window::WindowPlugin)
 /// * [`AssetPlugin`](bevy_asset::AssetPlugin)
 /// * [`ScenePlugin`](bevy_scene::ScenePlugin)
+/// * [`AnimationPlugin`](bevy_animation::AnimationPlugin)
 /// * [`RenderPlugin`](bevy_render::RenderPlugin) - with feature `bevy_render`
 /// * [`SpritePlugin`](bevy_sprite::SpritePlugin) - with feature `bevy_sprite`
 /// * [`PbrPlugin`](bevy_pbr::PbrPlugin) - with feature `bevy_pbr`
@@ -32,6 +33,7 @@ impl PluginGroup for DefaultPlugins {
         group.add(bevy_window::WindowPlugin::default());
         group.add(bevy_asset::AssetPlugin::default());
         group.add(bevy_scene::ScenePlugin::default());
+        group.add(bevy_animation::AnimationPlugin::default());
 
         #[cfg(feature = "bevy_winit")]
         group.add(bevy_winit::WinitPlugin::default());
@@ -4,6 +4,11 @@ pub mod prelude;
 mod default_plugins;
 pub use default_plugins::*;
 
+pub mod animation {
+    //! Provides types and plugins for animations.
+    pub use bevy_animation::*;
+}
+
 pub mod app {
     //! Build bevy apps, create plugins, and read events.
     pub use bevy_app::*;
@@ -0,0 +1,256 @@
+use bevy_app::EventWriter;
+use bevy_ecs::system::{Res, ResMut};
+use bevy_utils::{
+    tracing::{
+        field::{Field, Visit},
+        Event, Level, Subscriber,
+    },
+    Instant,
+};
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// A log record captured by the [`LogPlugin`](crate::LogPlugin).
+///
+/// Captured records are sent as events every frame, and the most recent ones are kept in the
+/// [`CapturedLogs`] resource. Capture is enabled with [`LogSettings::capture_capacity`](crate::LogSettings::capture_capacity).
+#[derive(Debug, Clone)]
+pub struct LogEvent {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    /// Fields of the event other than its message, formatted with their `Debug` implementation
+    pub fields: Vec<(&'static str, String)>,
+    /// Names of the spans the event was recorded in, from the outermost to the innermost
+    pub spans: Vec<&'static str>,
+    /// Time elapsed between the setup of the [`LogPlugin`](crate::LogPlugin) and the event
+    pub time: Duration,
+}
+
+impl fmt::Display for LogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>10.3} {:>5} ", self.time.as_secs_f64(), self.level)?;
+        for span in &self.spans {
+            write!(f, "{}:", span)?;
+        }
+        write!(f, "{}: {}", self.target, self.message)?;
+        for (name, value) in &self.fields {
+            write!(f, " {}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// The most recent [`LogEvent`]s, oldest first.
+///
+/// Once `capacity` records are stored, the oldest ones are dropped to make room for new ones.
+#[derive(Debug)]
+pub struct CapturedLogs {
+    records: VecDeque<LogEvent>,
+    capacity: usize,
+}
+
+impl CapturedLogs {
+    pub fn new(capacity: usize) -> Self {
+        CapturedLogs {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, record: LogEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &LogEvent> {
+        self.records.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the number of records kept, dropping the oldest ones if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        while self.records.len() > capacity {
+            self.records.pop_front();
+        }
+        self.capacity = capacity;
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
+
+/// Records captured by the [`LogCaptureLayer`] since the last frame
+#[derive(Clone)]
+pub(crate) struct PendingLogs(Arc<Mutex<CapturedLogs>>);
+
+/// A tracing layer storing events so that they can be read from the ECS.
+pub(crate) struct LogCaptureLayer {
+    pending: PendingLogs,
+    start: Instant,
+}
+
+impl LogCaptureLayer {
+    /// Creates the layer, along with the buffer it writes to. At most `capacity` records are
+    /// buffered between two frames.
+    pub(crate) fn new(capacity: usize) -> (Self, PendingLogs) {
+        let pending = PendingLogs(Arc::new(Mutex::new(CapturedLogs::new(capacity))));
+        (
+            LogCaptureLayer {
+                pending: pending.clone(),
+                start: Instant::now(),
+            },
+            pending,
+        )
+    }
+}
+
+impl<S> Layer<S> for LogCaptureLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| scope.from_root().map(|span| span.name()).collect())
+            .unwrap_or_default();
+        let record = LogEvent {
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+            spans,
+            time: self.start.elapsed(),
+        };
+        if let Ok(mut pending) = self.pending.0.lock() {
+            pending.push(record);
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            name if name.starts_with("log.") => {}
+            name => self.fields.push((name, value.to_string())),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            // fields added by `tracing-log`, already taken into account by the normalized metadata
+            name if name.starts_with("log.") => {}
+            name => self.fields.push((name, format!("{:?}", value))),
+        }
+    }
+}
+
+/// Moves the records captured since the last frame to [`CapturedLogs`], and sends them as [`LogEvent`]s.
+pub(crate) fn capture_logs_system(
+    pending: Res<PendingLogs>,
+    mut captured: ResMut<CapturedLogs>,
+    mut events: EventWriter<LogEvent>,
+) {
+    let records = match pending.0.lock() {
+        Ok(mut pending) => std::mem::take(&mut pending.records),
+        Err(_) => return,
+    };
+    for record in records {
+        captured.push(record.clone());
+        events.send(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_utils::tracing::{info_span, subscriber::with_default, warn};
+    use tracing_subscriber::{prelude::*, Registry};
+
+    fn record(message: &str) -> LogEvent {
+        LogEvent {
+            level: Level::INFO,
+            target: "test".to_string(),
+            message: message.to_string(),
+            fields: Vec::new(),
+            spans: Vec::new(),
+            time: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn bounded() {
+        let mut logs = CapturedLogs::new(2);
+        logs.push(record("a"));
+        logs.push(record("b"));
+        logs.push(record("c"));
+        let messages: Vec<_> = logs.iter().map(|record| record.message.as_str()).collect();
+        assert_eq!(messages, vec!["b", "c"]);
+
+        logs.set_capacity(1);
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs.iter().next().unwrap().message, "c");
+
+        let mut logs = CapturedLogs::new(0);
+        logs.push(record("a"));
+        assert!(logs.is_empty());
+    }
+
+    #[test]
+    fn capture_layer() {
+        let (layer, pending) = LogCaptureLayer::new(16);
+        let subscriber = Registry::default().with(layer);
+        with_default(subscriber, || {
+            let _outer = info_span!("outer").entered();
+            let _inner = info_span!("inner").entered();
+            warn!(answer = 42, name = "bevy", "something {}", "happened");
+        });
+
+        let pending = pending.0.lock().unwrap();
+        assert_eq!(pending.len(), 1);
+        let record = pending.iter().next().unwrap();
+        assert_eq!(record.level, Level::WARN);
+        assert_eq!(record.target, module_path!());
+        assert_eq!(record.message, "something happened");
+        assert_eq!(
+            record.fields,
+            vec![("answer", "42".to_string()), ("name", "bevy".to_string())]
+        );
+        assert_eq!(record.spans, vec!["outer", "inner"]);
+    }
+}
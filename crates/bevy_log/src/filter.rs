@@ -0,0 +1,145 @@
+use bevy_utils::tracing::level_filters::LevelFilter;
+use thiserror::Error;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// An error that occurs when changing the [`LogFilter`].
+#[derive(Error, Debug)]
+pub enum LogFilterError {
+    #[error("invalid filter: {0}")]
+    InvalidFilter(#[from] tracing_subscriber::filter::ParseError),
+    #[error("the log subscriber is not available anymore")]
+    SubscriberDropped(#[from] reload::Error),
+}
+
+/// Changes the filter of the [`LogPlugin`](crate::LogPlugin) at runtime.
+///
+/// The filter is made of base directives, in the [`EnvFilter`] format, initially set from
+/// `RUST_LOG` or [`LogSettings`](crate::LogSettings), and of per-target levels overriding them.
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    base: String,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    pub(crate) fn new(handle: reload::Handle<EnvFilter, Registry>, base: String) -> Self {
+        LogFilter {
+            handle,
+            base,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Returns the directives currently in use.
+    pub fn directives(&self) -> String {
+        directives(&self.base, &self.targets)
+    }
+
+    /// Replaces the base directives of the filter. Per-target levels are kept.
+    pub fn set_filter(&mut self, filter: impl Into<String>) -> Result<(), LogFilterError> {
+        let filter = filter.into();
+        self.apply(&directives(&filter, &self.targets))?;
+        self.base = filter;
+        Ok(())
+    }
+
+    /// Sets the maximum level of the logs of `target` and of its submodules.
+    pub fn set_target_level(
+        &mut self,
+        target: impl Into<String>,
+        level: impl Into<LevelFilter>,
+    ) -> Result<(), LogFilterError> {
+        let target = target.into();
+        let level = level.into();
+        let mut targets = self.targets.clone();
+        match targets.iter_mut().find(|(t, _)| *t == target) {
+            Some((_, l)) => *l = level,
+            None => targets.push((target, level)),
+        }
+        self.apply(&directives(&self.base, &targets))?;
+        self.targets = targets;
+        Ok(())
+    }
+
+    /// Removes the level set by [`LogFilter::set_target_level`] for `target`.
+    pub fn clear_target_level(&mut self, target: &str) -> Result<(), LogFilterError> {
+        let mut targets = self.targets.clone();
+        targets.retain(|(t, _)| t != target);
+        self.apply(&directives(&self.base, &targets))?;
+        self.targets = targets;
+        Ok(())
+    }
+
+    /// Returns the levels set by [`LogFilter::set_target_level`].
+    pub fn target_levels(&self) -> impl Iterator<Item = (&str, LevelFilter)> {
+        self.targets
+            .iter()
+            .map(|(target, level)| (target.as_str(), *level))
+    }
+
+    fn apply(&self, directives: &str) -> Result<(), LogFilterError> {
+        let filter = EnvFilter::try_new(directives)?;
+        self.handle.reload(filter)?;
+        Ok(())
+    }
+}
+
+fn directives(base: &str, targets: &[(String, LevelFilter)]) -> String {
+    let mut directives = base.to_string();
+    for (target, level) in targets {
+        if !directives.is_empty() {
+            directives.push(',');
+        }
+        directives.push_str(&format!("{}={}", target, level));
+    }
+    directives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_utils::tracing::{self, subscriber::with_default, Level};
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn directives() {
+        assert_eq!(super::directives("", &[]), "");
+        assert_eq!(
+            super::directives(
+                "info,wgpu=error",
+                &[
+                    ("bevy_ecs".to_string(), LevelFilter::DEBUG),
+                    ("bevy_render".to_string(), LevelFilter::OFF)
+                ]
+            ),
+            "info,wgpu=error,bevy_ecs=debug,bevy_render=off"
+        );
+    }
+
+    #[test]
+    fn change_filter() {
+        let (layer, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let mut filter = LogFilter::new(handle, "info".to_string());
+        let subscriber = Registry::default().with(layer);
+        with_default(subscriber, || {
+            assert!(tracing::enabled!(target: "app", Level::INFO));
+            assert!(!tracing::enabled!(target: "app", Level::DEBUG));
+
+            filter.set_target_level("app", Level::DEBUG).unwrap();
+            assert!(tracing::enabled!(target: "app", Level::DEBUG));
+            assert!(!tracing::enabled!(target: "other", Level::DEBUG));
+
+            filter.set_target_level("app", LevelFilter::OFF).unwrap();
+            assert!(!tracing::enabled!(target: "app", Level::ERROR));
+            assert_eq!(filter.directives(), "info,app=off");
+
+            filter.clear_target_level("app").unwrap();
+            filter.set_filter("warn").unwrap();
+            assert!(tracing::enabled!(target: "app", Level::WARN));
+            assert!(!tracing::enabled!(target: "app", Level::INFO));
+
+            assert!(filter.set_filter("app=notalevel").is_err());
+            assert_eq!(filter.directives(), "warn");
+        });
+    }
+}
@@ -1,5 +1,9 @@
 #[cfg(target_os = "android")]
 mod android_tracing;
+mod capture;
+mod filter;
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+mod rotating_file;
 
 pub mod prelude {
     #[doc(hidden)]
@@ -8,15 +12,20 @@ pub mod prelude {
     };
 }
 pub use bevy_utils::tracing::{
-    debug, debug_span, error, error_span, info, info_span, trace, trace_span, warn, warn_span,
-    Level,
+    debug, debug_span, error, error_span, info, info_span, level_filters::LevelFilter, trace,
+    trace_span, warn, warn_span, Level,
 };
+pub use capture::{CapturedLogs, LogEvent};
+pub use filter::{LogFilter, LogFilterError};
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+pub use rotating_file::LogFileSettings;
 
-use bevy_app::{App, Plugin};
+use bevy_app::{App, CoreStage, Plugin};
+use capture::{capture_logs_system, LogCaptureLayer};
 use tracing_log::LogTracer;
 #[cfg(feature = "tracing-chrome")]
 use tracing_subscriber::fmt::{format::DefaultFields, FormattedFields};
-use tracing_subscriber::{prelude::*, registry::Registry, EnvFilter};
+use tracing_subscriber::{prelude::*, registry::Registry, reload, EnvFilter};
 
 /// Adds logging to Apps. This plugin is part of the `DefaultPlugins`. Adding
 /// this plugin will setup a collector appropriate to your target platform:
@@ -38,6 +47,7 @@ use tracing_subscriber::{prelude::*, registry::Registry, EnvFilter};
 ///         .insert_resource(LogSettings {
 ///             level: Level::DEBUG,
 ///             filter: "wgpu=error,bevy_render=info".to_string(),
+///             ..Default::default()
 ///         })
 ///         .add_plugins(DefaultPlugins)
 ///         .run();
@@ -47,7 +57,11 @@ use tracing_subscriber::{prelude::*, registry::Registry, EnvFilter};
 /// Log level can also be changed using the `RUST_LOG` environment variable.
 /// It has the same syntax has the field [`LogSettings::filter`], see [`EnvFilter`].
 /// If you define the `RUST_LOG` environment variable, the [`LogSettings`] resource
-/// will be ignored.
+/// will be ignored. The filter can be changed at runtime with the [`LogFilter`] resource.
+///
+/// If [`LogSettings::capture_capacity`] is set, log records are also sent as [`LogEvent`]s every
+/// frame, and the most recent ones are kept in the [`CapturedLogs`] resource. This can be used to
+/// display logs in game, or to attach them to bug reports.
 ///
 /// If you want to setup your own tracing collector, you should disable this
 /// plugin from `DefaultPlugins` with [`App::add_plugins_with`]:
@@ -72,6 +86,14 @@ pub struct LogSettings {
     /// Filters out logs that are "less than" the given level.
     /// This can be further filtered using the `filter` setting.
     pub level: Level,
+
+    /// If set, the given number of the most recent log records are kept in the
+    /// [`CapturedLogs`] resource, and records are sent as [`LogEvent`]s
+    pub capture_capacity: Option<usize>,
+
+    /// If set, logs are also written to a file, rotated according to the settings
+    #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+    pub file: Option<LogFileSettings>,
 }
 
 impl Default for LogSettings {
@@ -79,21 +101,42 @@ impl Default for LogSettings {
         Self {
             filter: "wgpu=error".to_string(),
             level: Level::INFO,
+            capture_capacity: None,
+            #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+            file: None,
         }
     }
 }
 
 impl Plugin for LogPlugin {
     fn build(&self, app: &mut App) {
-        let default_filter = {
-            let settings = app.world.get_resource_or_insert_with(LogSettings::default);
-            format!("{},{}", settings.level, settings.filter)
-        };
+        let settings = app.world.get_resource_or_insert_with(LogSettings::default);
+        let default_filter = format!("{},{}", settings.level, settings.filter);
+        let capture_capacity = settings.capture_capacity;
+        #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+        let file_settings = settings.file.clone();
+
         LogTracer::init().unwrap();
-        let filter_layer = EnvFilter::try_from_default_env()
-            .or_else(|_| EnvFilter::try_new(&default_filter))
-            .unwrap();
-        let subscriber = Registry::default().with(filter_layer);
+        let (env_filter, directives) = match EnvFilter::try_from_default_env() {
+            Ok(env_filter) => (
+                env_filter,
+                std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default(),
+            ),
+            Err(_) => (EnvFilter::try_new(&default_filter).unwrap(), default_filter),
+        };
+        let (filter_layer, filter_handle) = reload::Layer::new(env_filter);
+        app.insert_resource(LogFilter::new(filter_handle, directives));
+
+        app.add_event::<LogEvent>();
+        let capture_layer = capture_capacity.map(|capacity| {
+            let (capture_layer, pending) = LogCaptureLayer::new(capacity);
+            app.insert_resource(pending)
+                .insert_resource(CapturedLogs::new(capacity))
+                .add_system_to_stage(CoreStage::First, capture_logs_system);
+            capture_layer
+        });
+
+        let subscriber = Registry::default().with(filter_layer).with(capture_layer);
 
         #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
         {
@@ -123,6 +166,25 @@ impl Plugin for LogPlugin {
             let fmt_layer = tracing_subscriber::fmt::Layer::default();
             let subscriber = subscriber.with(fmt_layer);
 
+            let file_layer = file_settings.and_then(|file_settings| {
+                match rotating_file::RotatingFile::open(file_settings.clone()) {
+                    Ok(file) => Some(
+                        tracing_subscriber::fmt::Layer::default()
+                            .with_ansi(false)
+                            .with_writer(std::sync::Mutex::new(file)),
+                    ),
+                    Err(err) => {
+                        eprintln!(
+                            "Could not open log file {}: {}",
+                            file_settings.path.display(),
+                            err
+                        );
+                        None
+                    }
+                }
+            });
+            let subscriber = subscriber.with(file_layer);
+
             #[cfg(feature = "tracing-chrome")]
             let subscriber = subscriber.with(chrome_layer);
             #[cfg(feature = "tracing-tracy")]
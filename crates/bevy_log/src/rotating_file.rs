@@ -0,0 +1,126 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Settings of the file sink of the [`LogPlugin`](crate::LogPlugin).
+///
+/// Logs are appended to `path`. Once the file grows past `max_size` bytes, it is renamed to
+/// `path.1`, previously rotated files are shifted (`path.1` to `path.2`, ...), and a new file
+/// is started. At most `max_files` rotated files are kept.
+#[derive(Debug, Clone)]
+pub struct LogFileSettings {
+    pub path: PathBuf,
+    pub max_size: u64,
+    pub max_files: usize,
+}
+
+impl LogFileSettings {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        LogFileSettings {
+            path: path.into(),
+            max_size: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
+
+/// A writer appending to a file, rotating it according to [`LogFileSettings`]
+pub(crate) struct RotatingFile {
+    settings: LogFileSettings,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub(crate) fn open(settings: LogFileSettings) -> io::Result<Self> {
+        if let Some(parent) = settings.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&settings.path)?;
+        let size = file.metadata()?.len();
+        Ok(RotatingFile {
+            settings,
+            file,
+            size,
+        })
+    }
+
+    fn rotated_path(path: &Path, index: usize) -> PathBuf {
+        let mut path = path.as_os_str().to_owned();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let path = &self.settings.path;
+        if self.settings.max_files == 0 {
+            fs::remove_file(path)?;
+        } else {
+            let oldest = Self::rotated_path(path, self.settings.max_files);
+            if oldest.exists() {
+                fs::remove_file(oldest)?;
+            }
+            for index in (1..self.settings.max_files).rev() {
+                let from = Self::rotated_path(path, index);
+                if from.exists() {
+                    fs::rename(from, Self::rotated_path(path, index + 1))?;
+                }
+            }
+            fs::rename(path, Self::rotated_path(path, 1))?;
+        }
+        self.file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.settings.max_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation() {
+        let dir = std::env::temp_dir().join(format!("bevy_log_rotation_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("app.log");
+        let mut file = RotatingFile::open(LogFileSettings {
+            path: path.clone(),
+            max_size: 10,
+            max_files: 2,
+        })
+        .unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "fourth\n");
+        assert_eq!(read(RotatingFile::rotated_path(&path, 1)), "third\n");
+        assert_eq!(read(RotatingFile::rotated_path(&path, 2)), "second\n");
+        assert!(!RotatingFile::rotated_path(&path, 3).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -76,6 +76,9 @@ impl Plugin for PbrPlugin {
             .init_resource::<PointLightShadowMap>()
             .init_resource::<AmbientLight>()
             .init_resource::<VisiblePointLights>()
+            .register_type::<PointLight>()
+            .register_type::<DirectionalLight>()
+            .register_type::<CubemapVisibleEntities>()
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 // NOTE: Clusters need to have been added before update_clusters is run so
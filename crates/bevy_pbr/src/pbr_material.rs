@@ -1,7 +1,7 @@
 use crate::{AlphaMode, MaterialPipeline, SpecializedMaterial, PBR_SHADER_HANDLE};
 use bevy_asset::{AssetServer, Handle};
 use bevy_ecs::system::{lifetimeless::SRes, SystemParamItem};
-use bevy_math::Vec4;
+use bevy_math::{Mat3, Vec4};
 use bevy_reflect::TypeUuid;
 use bevy_render::{
     color::Color,
@@ -50,6 +50,9 @@ pub struct StandardMaterial {
     pub double_sided: bool,
     pub unlit: bool,
     pub alpha_mode: AlphaMode,
+    /// Transforms the texture coordinates of the mesh before sampling the textures, with the
+    /// texture coordinates as the `x` and `y` of a point
+    pub uv_transform: Mat3,
 }
 
 impl Default for StandardMaterial {
@@ -78,6 +81,7 @@ impl Default for StandardMaterial {
             double_sided: false,
             unlit: false,
             alpha_mode: AlphaMode::Opaque,
+            uv_transform: Mat3::IDENTITY,
         }
     }
 }
@@ -127,6 +131,7 @@ pub struct StandardMaterialUniformData {
     // Use a color for user friendliness even though we technically don't use the alpha channel
     // Might be used in the future for exposure correction in HDR
     pub emissive: Vec4,
+    pub uv_transform: Mat3,
     /// Linear perceptual roughness, clamped to [0.089, 1.0] in the shader
     /// Defaults to minimum of 0.089
     pub roughness: f32,
@@ -249,6 +254,7 @@ impl RenderAsset for StandardMaterial {
         let value = StandardMaterialUniformData {
             base_color: material.base_color.as_linear_rgba_f32().into(),
             emissive: material.emissive.into(),
+            uv_transform: material.uv_transform,
             roughness: material.perceptual_roughness,
             metallic: material.metallic,
             reflectance: material.reflectance,
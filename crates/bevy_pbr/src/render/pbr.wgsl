@@ -41,6 +41,7 @@ var<uniform> mesh: Mesh;
 struct StandardMaterial {
     base_color: vec4<f32>;
     emissive: vec4<f32>;
+    uv_transform: mat3x3<f32>;
     perceptual_roughness: f32;
     metallic: f32;
     reflectance: f32;
@@ -452,9 +453,10 @@ struct FragmentInput {
 
 [[stage(fragment)]]
 fn fragment(in: FragmentInput) -> [[location(0)]] vec4<f32> {
+    let uv = (material.uv_transform * vec3<f32>(in.uv, 1.0)).xy;
     var output_color: vec4<f32> = material.base_color;
     if ((material.flags & STANDARD_MATERIAL_FLAGS_BASE_COLOR_TEXTURE_BIT) != 0u) {
-        output_color = output_color * textureSample(base_color_texture, base_color_sampler, in.uv);
+        output_color = output_color * textureSample(base_color_texture, base_color_sampler, uv);
     }
 
     // // NOTE: Unlit bit not set means == 0 is true, so the true case is if lit
@@ -462,14 +464,14 @@ fn fragment(in: FragmentInput) -> [[location(0)]] vec4<f32> {
         // TODO use .a for exposure compensation in HDR
         var emissive: vec4<f32> = material.emissive;
         if ((material.flags & STANDARD_MATERIAL_FLAGS_EMISSIVE_TEXTURE_BIT) != 0u) {
-            emissive = vec4<f32>(emissive.rgb * textureSample(emissive_texture, emissive_sampler, in.uv).rgb, 1.0);
+            emissive = vec4<f32>(emissive.rgb * textureSample(emissive_texture, emissive_sampler, uv).rgb, 1.0);
         }
 
         // calculate non-linear roughness from linear perceptualRoughness
         var metallic: f32 = material.metallic;
         var perceptual_roughness: f32 = material.perceptual_roughness;
         if ((material.flags & STANDARD_MATERIAL_FLAGS_METALLIC_ROUGHNESS_TEXTURE_BIT) != 0u) {
-            let metallic_roughness = textureSample(metallic_roughness_texture, metallic_roughness_sampler, in.uv);
+            let metallic_roughness = textureSample(metallic_roughness_texture, metallic_roughness_sampler, uv);
             // Sampling from GLTF standard channels for now
             metallic = metallic * metallic_roughness.b;
             perceptual_roughness = perceptual_roughness * metallic_roughness.g;
@@ -478,7 +480,7 @@ fn fragment(in: FragmentInput) -> [[location(0)]] vec4<f32> {
 
         var occlusion: f32 = 1.0;
         if ((material.flags & STANDARD_MATERIAL_FLAGS_OCCLUSION_TEXTURE_BIT) != 0u) {
-            occlusion = textureSample(occlusion_texture, occlusion_sampler, in.uv).r;
+            occlusion = textureSample(occlusion_texture, occlusion_sampler, uv).r;
         }
 
         var N: vec3<f32> = normalize(in.world_normal);
@@ -505,7 +507,7 @@ fn fragment(in: FragmentInput) -> [[location(0)]] vec4<f32> {
 #ifdef VERTEX_TANGENTS
 #ifdef STANDARDMATERIAL_NORMAL_MAP
         let TBN = mat3x3<f32>(T, B, N);
-        N = TBN * normalize(textureSample(normal_map_texture, normal_map_sampler, in.uv).rgb * 2.0 - 1.0);
+        N = TBN * normalize(textureSample(normal_map_texture, normal_map_sampler, uv).rgb * 2.0 - 1.0);
 #endif
 #endif
 
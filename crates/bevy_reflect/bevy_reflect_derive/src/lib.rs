@@ -127,10 +127,31 @@ pub fn derive_reflect(input: TokenStream) -> TokenStream {
     }
 
     let registration_data = &reflect_attrs.data;
+    let field_names = match derive_type {
+        DeriveType::Struct | DeriveType::UnitStruct => Some(
+            active_fields
+                .iter()
+                .map(|(field, index)| {
+                    field
+                        .ident
+                        .as_ref()
+                        .map(|i| i.to_string())
+                        .unwrap_or_else(|| index.to_string())
+                })
+                .collect::<Vec<String>>(),
+        ),
+        DeriveType::TupleStruct => Some(
+            (0..active_fields.len())
+                .map(|index| index.to_string())
+                .collect(),
+        ),
+        DeriveType::Value => None,
+    };
     let get_type_registration_impl = impl_get_type_registration(
         type_name,
         &bevy_reflect_path,
         registration_data,
+        field_names,
         &ast.generics,
     );
 
@@ -564,6 +585,7 @@ pub fn impl_reflect_value(input: TokenStream) -> TokenStream {
         ty,
         &bevy_reflect_path,
         registration_data,
+        None,
         &reflect_value_def.generics,
     );
     impl_value(
@@ -711,15 +733,22 @@ fn impl_get_type_registration(
     type_name: &Ident,
     bevy_reflect_path: &Path,
     registration_data: &[Ident],
+    field_names: Option<Vec<String>>,
     generics: &Generics,
 ) -> proc_macro2::TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let field_names = field_names.map(|field_names| {
+        quote! {
+            registration.insert(#bevy_reflect_path::ReflectFieldNames::new(&[#(#field_names),*]));
+        }
+    });
     quote! {
         #[allow(unused_mut)]
         impl #impl_generics #bevy_reflect_path::GetTypeRegistration for #type_name #ty_generics #where_clause {
             fn get_type_registration() -> #bevy_reflect_path::TypeRegistration {
                 let mut registration = #bevy_reflect_path::TypeRegistration::of::<#type_name #ty_generics>();
                 #(registration.insert::<#registration_data>(#bevy_reflect_path::FromType::<#type_name #ty_generics>::from_type());)*
+                #field_names
                 registration
             }
         }
@@ -2,6 +2,7 @@
 
 mod list;
 mod map;
+mod migration;
 mod path;
 mod reflect;
 mod struct_trait;
@@ -35,6 +36,7 @@ pub mod prelude {
 pub use impls::*;
 pub use list::*;
 pub use map::*;
+pub use migration::*;
 pub use path::*;
 pub use reflect::*;
 pub use struct_trait::*;
@@ -58,7 +60,8 @@ mod tests {
 
     use super::*;
     use crate as bevy_reflect;
-    use crate::serde::{ReflectDeserializer, ReflectSerializer};
+    use crate::serde::{ReflectDeserializer, ReflectSerializer, SkipUnregisteredDeserializer};
+    use std::cell::RefCell;
 
     #[test]
     fn reflect_struct() {
@@ -194,6 +197,41 @@ mod tests {
         assert_eq!(values, vec![1]);
     }
 
+    #[test]
+    fn reflect_field_names() {
+        #[derive(Reflect)]
+        struct Foo {
+            a: u32,
+            #[reflect(ignore)]
+            _b: u32,
+            c: u32,
+        }
+
+        #[derive(Reflect)]
+        struct Bar(u32, u32);
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Foo>();
+        registry.register::<Bar>();
+        registry.register::<u32>();
+        let field_names = |type_id| {
+            registry
+                .get(type_id)
+                .unwrap()
+                .data::<ReflectFieldNames>()
+                .map(ReflectFieldNames::names)
+        };
+        assert_eq!(
+            field_names(std::any::TypeId::of::<Foo>()),
+            Some(&["a", "c"][..])
+        );
+        assert_eq!(
+            field_names(std::any::TypeId::of::<Bar>()),
+            Some(&["0", "1"][..])
+        );
+        assert_eq!(field_names(std::any::TypeId::of::<u32>()), None);
+    }
+
     #[test]
     fn reflect_complex_patch() {
         #[derive(Reflect, Eq, PartialEq, Debug, FromReflect)]
@@ -372,6 +410,55 @@ mod tests {
         assert!(foo.reflect_partial_eq(&dynamic_struct).unwrap());
     }
 
+    #[test]
+    fn skip_unregistered_types() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<u32>();
+
+        let ron = r#"{
+            "type": "Foo",
+            "struct": {
+                "a": { "type": "u32", "value": 1 },
+                "b": { "type": "Bar", "value": (x: 2) },
+                "c": {
+                    "type": "(u32, Baz)",
+                    "tuple": [
+                        { "type": "u32", "value": 3 },
+                        { "type": "Baz", "value": Baz },
+                    ],
+                },
+                "d": {
+                    "type": "Vec<Bar>",
+                    "list": [
+                        { "type": "u32", "value": 4 },
+                        { "type": "Bar", "value": (x: 5) },
+                    ],
+                },
+            },
+        }"#;
+        let mut deserializer = Deserializer::from_str(ron).unwrap();
+        let unregistered = RefCell::new(Vec::new());
+        let value = SkipUnregisteredDeserializer::new(&registry, &unregistered)
+            .deserialize(&mut deserializer)
+            .unwrap()
+            .unwrap();
+        let dynamic_struct = value.take::<DynamicStruct>().unwrap();
+        assert_eq!(dynamic_struct.get_field::<u32>("a"), Some(&1));
+        assert!(dynamic_struct.field("b").is_none());
+        assert!(dynamic_struct.field("c").is_none());
+        assert_eq!(
+            dynamic_struct.get_field::<DynamicList>("d").unwrap().len(),
+            1
+        );
+        assert_eq!(unregistered.into_inner(), vec!["Bar", "Baz", "Bar"]);
+
+        let mut deserializer =
+            Deserializer::from_str(r#"{ "type": "Bar", "value": (x: 2) }"#).unwrap();
+        assert!(ReflectDeserializer::new(&registry)
+            .deserialize(&mut deserializer)
+            .is_err());
+    }
+
     #[test]
     fn reflect_take() {
         #[derive(Reflect, Debug, PartialEq)]
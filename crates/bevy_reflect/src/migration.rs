@@ -0,0 +1,106 @@
+use crate::DynamicStruct;
+use thiserror::Error;
+
+/// Updates the reflected data of a struct saved by a version of its type to the next version.
+pub type Migration = fn(&mut DynamicStruct);
+
+/// Type data holding the version of a reflected struct, and the [`Migration`]s updating the data
+/// saved by its previous versions.
+///
+/// Types start at version 0, and each migration added with
+/// [`TypeRegistry::register_migration`](crate::TypeRegistry::register_migration) increments
+/// their version.
+#[derive(Clone, Default)]
+pub struct ReflectVersion {
+    migrations: Vec<Migration>,
+}
+
+/// An error that occurs when migrating reflected data
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("version {version} is newer than the current version {current}")]
+pub struct UnknownVersion {
+    pub version: u32,
+    pub current: u32,
+}
+
+impl ReflectVersion {
+    /// The current version of the type
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    pub fn add_migration(&mut self, migration: Migration) {
+        self.migrations.push(migration);
+    }
+
+    /// Updates `data`, saved by the given `version` of the type, to the current version
+    pub fn migrate(&self, data: &mut DynamicStruct, version: u32) -> Result<(), UnknownVersion> {
+        let migrations = self
+            .migrations
+            .get(version as usize..)
+            .ok_or(UnknownVersion {
+                version,
+                current: self.version(),
+            })?;
+        for migration in migrations {
+            migration(data);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bevy_reflect;
+    use crate::{GetField, Reflect, Struct, TypeRegistry};
+
+    #[derive(Reflect)]
+    struct Player {
+        name: String,
+        health: f32,
+    }
+
+    #[test]
+    fn migrate_struct() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Player>();
+        // version 1 renamed `hp` to `health`
+        registry.register_migration::<Player>(|data| {
+            if let Some(hp) = data.remove("hp") {
+                data.insert_boxed("health", hp);
+            }
+        });
+        // version 2 stores the health as a fraction
+        registry.register_migration::<Player>(|data| {
+            if let Some(health) = data.get_field_mut::<f32>("health") {
+                *health /= 100.0;
+            }
+        });
+
+        let version = registry
+            .get_type_data::<ReflectVersion>(std::any::TypeId::of::<Player>())
+            .unwrap();
+        assert_eq!(version.version(), 2);
+
+        let mut data = DynamicStruct::default();
+        data.insert("name", "a".to_string());
+        data.insert("hp", 50.0f32);
+        version.migrate(&mut data, 0).unwrap();
+        assert!(data.field("hp").is_none());
+        assert_eq!(data.get_field::<f32>("health"), Some(&0.5));
+        assert_eq!(data.field_len(), 2);
+
+        let mut data = DynamicStruct::default();
+        data.insert("health", 50.0f32);
+        version.migrate(&mut data, 2).unwrap();
+        assert_eq!(data.get_field::<f32>("health"), Some(&50.0));
+        assert_eq!(
+            version.migrate(&mut data, 3),
+            Err(UnknownVersion {
+                version: 3,
+                current: 2
+            })
+        );
+    }
+}
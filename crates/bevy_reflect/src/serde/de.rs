@@ -3,7 +3,8 @@ use crate::{
     Reflect, ReflectDeserialize, TypeRegistry,
 };
 use erased_serde::Deserializer;
-use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::cell::RefCell;
 
 pub trait DeserializeValue {
     fn deserialize(
@@ -25,22 +26,60 @@ impl<'a> ReflectDeserializer<'a> {
 impl<'a, 'de> DeserializeSeed<'de> for ReflectDeserializer<'a> {
     type Value = Box<dyn Reflect>;
 
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = SkipUnregisteredDeserializer {
+            registry: self.registry,
+            unregistered: None,
+        }
+        .deserialize(deserializer)?;
+        // values are only skipped when there is a list to report their types to
+        Ok(value.unwrap())
+    }
+}
+
+/// Deserializes a reflected value like [`ReflectDeserializer`], but skips the values of the
+/// types that aren't registered instead of failing, and reports their type names in
+/// `unregistered`. Skipped struct fields, list elements and map entries are left out, and the
+/// tuples and tuple structs with a skipped field are skipped as a whole. `None` is returned when
+/// the value itself is skipped.
+pub struct SkipUnregisteredDeserializer<'a> {
+    registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
+}
+
+impl<'a> SkipUnregisteredDeserializer<'a> {
+    pub fn new(registry: &'a TypeRegistry, unregistered: &'a RefCell<Vec<String>>) -> Self {
+        SkipUnregisteredDeserializer {
+            registry,
+            unregistered: Some(unregistered),
+        }
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for SkipUnregisteredDeserializer<'a> {
+    type Value = Option<Box<dyn Reflect>>;
+
     fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         deserializer.deserialize_any(ReflectVisitor {
             registry: self.registry,
+            unregistered: self.unregistered,
         })
     }
 }
 
 struct ReflectVisitor<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> Visitor<'de> for ReflectVisitor<'a> {
-    type Value = Box<dyn Reflect>;
+    type Value = Option<Box<dyn Reflect>>;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
         formatter.write_str("reflect value")
@@ -50,91 +89,91 @@ impl<'a, 'de> Visitor<'de> for ReflectVisitor<'a> {
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v))
+        Ok(Some(Box::new(v)))
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Box::new(v.to_string()))
+        Ok(Some(Box::new(v.to_string())))
     }
 
     fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
@@ -153,8 +192,9 @@ impl<'a, 'de> Visitor<'de> for ReflectVisitor<'a> {
                         .ok_or_else(|| de::Error::missing_field(type_fields::TYPE))?;
                     let map = map.next_value_seed(MapDeserializer {
                         registry: self.registry,
+                        unregistered: self.unregistered,
                     })?;
-                    return Ok(Box::new(map));
+                    return Ok(Some(Box::new(map)));
                 }
                 type_fields::STRUCT => {
                     let type_name = type_name
@@ -162,19 +202,23 @@ impl<'a, 'de> Visitor<'de> for ReflectVisitor<'a> {
                         .ok_or_else(|| de::Error::missing_field(type_fields::TYPE))?;
                     let mut dynamic_struct = map.next_value_seed(StructDeserializer {
                         registry: self.registry,
+                        unregistered: self.unregistered,
                     })?;
                     dynamic_struct.set_name(type_name);
-                    return Ok(Box::new(dynamic_struct));
+                    return Ok(Some(Box::new(dynamic_struct)));
                 }
                 type_fields::TUPLE_STRUCT => {
                     let type_name = type_name
                         .take()
                         .ok_or_else(|| de::Error::missing_field(type_fields::TYPE))?;
-                    let mut tuple_struct = map.next_value_seed(TupleStructDeserializer {
+                    let tuple_struct = map.next_value_seed(TupleStructDeserializer {
                         registry: self.registry,
+                        unregistered: self.unregistered,
                     })?;
-                    tuple_struct.set_name(type_name);
-                    return Ok(Box::new(tuple_struct));
+                    return Ok(tuple_struct.map(|mut tuple_struct| {
+                        tuple_struct.set_name(type_name);
+                        Box::new(tuple_struct) as Box<dyn Reflect>
+                    }));
                 }
                 type_fields::TUPLE => {
                     let _type_name = type_name
@@ -182,8 +226,9 @@ impl<'a, 'de> Visitor<'de> for ReflectVisitor<'a> {
                         .ok_or_else(|| de::Error::missing_field(type_fields::TYPE))?;
                     let tuple = map.next_value_seed(TupleDeserializer {
                         registry: self.registry,
+                        unregistered: self.unregistered,
                     })?;
-                    return Ok(Box::new(tuple));
+                    return Ok(tuple.map(|tuple| Box::new(tuple) as Box<dyn Reflect>));
                 }
                 type_fields::LIST => {
                     let _type_name = type_name
@@ -191,20 +236,29 @@ impl<'a, 'de> Visitor<'de> for ReflectVisitor<'a> {
                         .ok_or_else(|| de::Error::missing_field(type_fields::TYPE))?;
                     let list = map.next_value_seed(ListDeserializer {
                         registry: self.registry,
+                        unregistered: self.unregistered,
                     })?;
-                    return Ok(Box::new(list));
+                    return Ok(Some(Box::new(list)));
                 }
                 type_fields::VALUE => {
                     let type_name = type_name
                         .take()
                         .ok_or_else(|| de::Error::missing_field(type_fields::TYPE))?;
                     let registration =
-                        self.registry.get_with_name(&type_name).ok_or_else(|| {
-                            de::Error::custom(format_args!(
-                                "No registration found for {}",
-                                type_name
-                            ))
-                        })?;
+                        match (self.registry.get_with_name(&type_name), self.unregistered) {
+                            (Some(registration), _) => registration,
+                            (None, Some(unregistered)) => {
+                                map.next_value::<IgnoredAny>()?;
+                                unregistered.borrow_mut().push(type_name);
+                                return Ok(None);
+                            }
+                            (None, None) => {
+                                return Err(de::Error::custom(format_args!(
+                                    "No registration found for {}",
+                                    type_name
+                                )))
+                            }
+                        };
                     let deserialize_reflect =
                         registration.data::<ReflectDeserialize>().ok_or_else(|| {
                             de::Error::custom(format_args!(
@@ -215,7 +269,7 @@ impl<'a, 'de> Visitor<'de> for ReflectVisitor<'a> {
                     let value = map.next_value_seed(DeserializeReflectDeserializer {
                         reflect_deserialize: deserialize_reflect,
                     })?;
-                    return Ok(value);
+                    return Ok(Some(value));
                 }
                 _ => return Err(de::Error::unknown_field(key.as_str(), &[])),
             }
@@ -242,6 +296,7 @@ impl<'a, 'de> DeserializeSeed<'de> for DeserializeReflectDeserializer<'a> {
 
 struct ListDeserializer<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for ListDeserializer<'a> {
@@ -253,12 +308,14 @@ impl<'a, 'de> DeserializeSeed<'de> for ListDeserializer<'a> {
     {
         deserializer.deserialize_seq(ListVisitor {
             registry: self.registry,
+            unregistered: self.unregistered,
         })
     }
 }
 
 struct ListVisitor<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> Visitor<'de> for ListVisitor<'a> {
@@ -273,10 +330,13 @@ impl<'a, 'de> Visitor<'de> for ListVisitor<'a> {
         V: SeqAccess<'de>,
     {
         let mut list = DynamicList::default();
-        while let Some(value) = seq.next_element_seed(ReflectDeserializer {
+        while let Some(value) = seq.next_element_seed(SkipUnregisteredDeserializer {
             registry: self.registry,
+            unregistered: self.unregistered,
         })? {
-            list.push_box(value);
+            if let Some(value) = value {
+                list.push_box(value);
+            }
         }
         Ok(list)
     }
@@ -284,6 +344,7 @@ impl<'a, 'de> Visitor<'de> for ListVisitor<'a> {
 
 struct MapDeserializer<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for MapDeserializer<'a> {
@@ -295,12 +356,14 @@ impl<'a, 'de> DeserializeSeed<'de> for MapDeserializer<'a> {
     {
         deserializer.deserialize_map(MapVisitor {
             registry: self.registry,
+            unregistered: self.unregistered,
         })
     }
 }
 
 struct MapVisitor<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> Visitor<'de> for MapVisitor<'a> {
@@ -315,13 +378,17 @@ impl<'a, 'de> Visitor<'de> for MapVisitor<'a> {
         V: MapAccess<'de>,
     {
         let mut dynamic_map = DynamicMap::default();
-        while let Some(key) = map.next_key_seed(ReflectDeserializer {
+        while let Some(key) = map.next_key_seed(SkipUnregisteredDeserializer {
             registry: self.registry,
+            unregistered: self.unregistered,
         })? {
-            let value = map.next_value_seed(ReflectDeserializer {
+            let value = map.next_value_seed(SkipUnregisteredDeserializer {
                 registry: self.registry,
+                unregistered: self.unregistered,
             })?;
-            dynamic_map.insert_boxed(key, value);
+            if let (Some(key), Some(value)) = (key, value) {
+                dynamic_map.insert_boxed(key, value);
+            }
         }
 
         Ok(dynamic_map)
@@ -330,6 +397,7 @@ impl<'a, 'de> Visitor<'de> for MapVisitor<'a> {
 
 struct StructDeserializer<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for StructDeserializer<'a> {
@@ -341,12 +409,14 @@ impl<'a, 'de> DeserializeSeed<'de> for StructDeserializer<'a> {
     {
         deserializer.deserialize_map(StructVisitor {
             registry: self.registry,
+            unregistered: self.unregistered,
         })
     }
 }
 
 struct StructVisitor<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> Visitor<'de> for StructVisitor<'a> {
@@ -362,10 +432,13 @@ impl<'a, 'de> Visitor<'de> for StructVisitor<'a> {
     {
         let mut dynamic_struct = DynamicStruct::default();
         while let Some(key) = map.next_key::<String>()? {
-            let value = map.next_value_seed(ReflectDeserializer {
+            let value = map.next_value_seed(SkipUnregisteredDeserializer {
                 registry: self.registry,
+                unregistered: self.unregistered,
             })?;
-            dynamic_struct.insert_boxed(&key, value);
+            if let Some(value) = value {
+                dynamic_struct.insert_boxed(&key, value);
+            }
         }
 
         Ok(dynamic_struct)
@@ -374,10 +447,11 @@ impl<'a, 'de> Visitor<'de> for StructVisitor<'a> {
 
 struct TupleStructDeserializer<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for TupleStructDeserializer<'a> {
-    type Value = DynamicTupleStruct;
+    type Value = Option<DynamicTupleStruct>;
 
     fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
@@ -385,16 +459,18 @@ impl<'a, 'de> DeserializeSeed<'de> for TupleStructDeserializer<'a> {
     {
         deserializer.deserialize_seq(TupleStructVisitor {
             registry: self.registry,
+            unregistered: self.unregistered,
         })
     }
 }
 
 struct TupleStructVisitor<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> Visitor<'de> for TupleStructVisitor<'a> {
-    type Value = DynamicTupleStruct;
+    type Value = Option<DynamicTupleStruct>;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
         formatter.write_str("tuple struct value")
@@ -404,11 +480,16 @@ impl<'a, 'de> Visitor<'de> for TupleStructVisitor<'a> {
     where
         V: SeqAccess<'de>,
     {
-        let mut tuple_struct = DynamicTupleStruct::default();
-        while let Some(value) = seq.next_element_seed(ReflectDeserializer {
+        let mut tuple_struct = Some(DynamicTupleStruct::default());
+        while let Some(value) = seq.next_element_seed(SkipUnregisteredDeserializer {
             registry: self.registry,
+            unregistered: self.unregistered,
         })? {
-            tuple_struct.insert_boxed(value);
+            match (tuple_struct.as_mut(), value) {
+                (Some(tuple_struct), Some(value)) => tuple_struct.insert_boxed(value),
+                // the other fields are still read, to skip the whole tuple struct
+                _ => tuple_struct = None,
+            }
         }
         Ok(tuple_struct)
     }
@@ -416,10 +497,11 @@ impl<'a, 'de> Visitor<'de> for TupleStructVisitor<'a> {
 
 struct TupleDeserializer<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for TupleDeserializer<'a> {
-    type Value = DynamicTuple;
+    type Value = Option<DynamicTuple>;
 
     fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
@@ -427,16 +509,18 @@ impl<'a, 'de> DeserializeSeed<'de> for TupleDeserializer<'a> {
     {
         deserializer.deserialize_seq(TupleVisitor {
             registry: self.registry,
+            unregistered: self.unregistered,
         })
     }
 }
 
 struct TupleVisitor<'a> {
     registry: &'a TypeRegistry,
+    unregistered: Option<&'a RefCell<Vec<String>>>,
 }
 
 impl<'a, 'de> Visitor<'de> for TupleVisitor<'a> {
-    type Value = DynamicTuple;
+    type Value = Option<DynamicTuple>;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
         formatter.write_str("tuple value")
@@ -446,11 +530,16 @@ impl<'a, 'de> Visitor<'de> for TupleVisitor<'a> {
     where
         V: SeqAccess<'de>,
     {
-        let mut tuple = DynamicTuple::default();
-        while let Some(value) = seq.next_element_seed(ReflectDeserializer {
+        let mut tuple = Some(DynamicTuple::default());
+        while let Some(value) = seq.next_element_seed(SkipUnregisteredDeserializer {
             registry: self.registry,
+            unregistered: self.unregistered,
         })? {
-            tuple.insert_boxed(value);
+            match (tuple.as_mut(), value) {
+                (Some(tuple), Some(value)) => tuple.insert_boxed(value),
+                // the other fields are still read, to skip the whole tuple
+                _ => tuple = None,
+            }
         }
         Ok(tuple)
     }
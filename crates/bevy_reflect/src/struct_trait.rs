@@ -111,6 +111,18 @@ impl DynamicStruct {
             self.insert_boxed(name, Box::new(value));
         }
     }
+
+    /// Removes the field `name`, and returns its value if it existed
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn Reflect>> {
+        let index = self.field_indices.remove(name)?;
+        self.field_names.remove(index);
+        for field_index in self.field_indices.values_mut() {
+            if *field_index > index {
+                *field_index -= 1;
+            }
+        }
+        Some(self.fields.remove(index))
+    }
 }
 
 impl Struct for DynamicStruct {
@@ -1,4 +1,4 @@
-use crate::Reflect;
+use crate::{Migration, Reflect, ReflectVersion};
 use bevy_utils::{HashMap, HashSet};
 use downcast_rs::{impl_downcast, Downcast};
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
@@ -38,6 +38,28 @@ impl TypeRegistry {
         self.add_registration(T::get_type_registration());
     }
 
+    /// Adds a [`Migration`] updating the data saved by the current version of `T` to the next
+    /// version, which becomes the current version. See [`ReflectVersion`].
+    ///
+    /// # Panics
+    /// Panics if `T` isn't registered.
+    pub fn register_migration<T: Reflect>(&mut self, migration: Migration) {
+        let registration = self.get_mut(TypeId::of::<T>()).unwrap_or_else(|| {
+            panic!(
+                "{} must be registered before its migrations",
+                std::any::type_name::<T>()
+            )
+        });
+        match registration.data_mut::<ReflectVersion>() {
+            Some(version) => version.add_migration(migration),
+            None => {
+                let mut version = ReflectVersion::default();
+                version.add_migration(migration);
+                registration.insert(version);
+            }
+        }
+    }
+
     pub fn add_registration(&mut self, registration: TypeRegistration) {
         let short_name = registration.short_name.to_string();
         if self.short_name_to_id.contains_key(&short_name)
@@ -254,6 +276,21 @@ impl<T: for<'a> Deserialize<'a> + Reflect> FromType<T> for ReflectDeserialize {
     }
 }
 
+/// Type data holding the names of the reflected fields of a struct, or their indices for a tuple
+/// struct, registered by `#[derive(Reflect)]`
+#[derive(Clone, Debug)]
+pub struct ReflectFieldNames(&'static [&'static str]);
+
+impl ReflectFieldNames {
+    pub fn new(names: &'static [&'static str]) -> Self {
+        ReflectFieldNames(names)
+    }
+
+    pub fn names(&self) -> &'static [&'static str] {
+        self.0
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::TypeRegistration;
@@ -33,9 +33,9 @@ use crate::{
     camera::CameraPlugin,
     color::Color,
     mesh::MeshPlugin,
-    primitives::Frustum,
+    primitives::{CubemapFrusta, Frustum},
     render_graph::RenderGraph,
-    render_resource::{RenderPipelineCache, Shader, ShaderLoader},
+    render_resource::{RenderPipelineCache, Shader, ShaderLoader, ShaderValidationProcessor},
     renderer::render_system,
     texture::ImagePlugin,
     view::{ViewPlugin, WindowRenderPlugin},
@@ -139,9 +139,11 @@ impl Plugin for RenderPlugin {
             .insert_resource(queue.clone())
             .add_asset::<Shader>()
             .init_asset_loader::<ShaderLoader>()
+            .add_asset_processor(ShaderValidationProcessor)
             .init_resource::<ScratchRenderWorld>()
             .register_type::<Color>()
-            .register_type::<Frustum>();
+            .register_type::<Frustum>()
+            .register_type::<CubemapFrusta>();
         let render_pipeline_cache = RenderPipelineCache::new(device.clone());
         let asset_server = app.world.get_resource::<AssetServer>().unwrap().clone();
 
@@ -1,6 +1,7 @@
 mod conversions;
 
 use crate::{
+    mesh::MorphTarget,
     primitives::Aabb,
     render_asset::{PrepareAssetError, RenderAsset},
     render_resource::Buffer,
@@ -30,6 +31,7 @@ pub struct Mesh {
     /// which allows easy stable VertexBuffers (i.e. same buffer order)
     attributes: BTreeMap<Cow<'static, str>, VertexAttributeValues>,
     indices: Option<Indices>,
+    morph_targets: Vec<MorphTarget>,
 }
 
 /// Contains geometry in the form of a mesh.
@@ -76,6 +78,7 @@ impl Mesh {
             primitive_topology,
             attributes: Default::default(),
             indices: None,
+            morph_targets: Vec::new(),
         }
     }
 
@@ -108,6 +111,14 @@ impl Mesh {
         self.attributes.get_mut(&name.into())
     }
 
+    /// Removes the data of the vertex attribute with the specified `name`, returning it.
+    pub fn remove_attribute(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+    ) -> Option<VertexAttributeValues> {
+        self.attributes.remove(&name.into())
+    }
+
     /// Sets the vertex indices of the mesh. They describe how triangles are constructed out of the
     /// vertex attributes and are therefore only useful for the [`PrimitiveTopology`] variants
     /// that use triangles.
@@ -125,6 +136,17 @@ impl Mesh {
         self.indices.as_mut()
     }
 
+    /// Sets the morph targets of the mesh, which are blended on the CPU by
+    /// [`morph_mesh`](crate::mesh::morph_mesh).
+    pub fn set_morph_targets(&mut self, morph_targets: Vec<MorphTarget>) {
+        self.morph_targets = morph_targets;
+    }
+
+    /// Retrieves the morph targets of the mesh.
+    pub fn morph_targets(&self) -> &[MorphTarget] {
+        &self.morph_targets
+    }
+
     /// Computes and returns the index data of the mesh as bytes.
     /// This is used to transform the index data into a GPU friendly format.
     pub fn get_index_buffer_bytes(&self) -> Option<&[u8]> {
@@ -263,6 +285,18 @@ impl Mesh {
                 VertexAttributeValues::Unorm8x4(vec) => *vec = duplicate(vec, indices),
             }
         }
+        for target in self.morph_targets.iter_mut() {
+            for displacements in [
+                &mut target.positions,
+                &mut target.normals,
+                &mut target.tangents,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                *displacements = duplicate(displacements, indices.iter());
+            }
+        }
     }
 
     /// Calculates the [`Mesh::ATTRIBUTE_NORMAL`] of a mesh.
@@ -1,13 +1,19 @@
 #[allow(clippy::module_inception)]
 mod mesh;
+mod morph;
 /// Generation for some primitive shape meshes.
 pub mod shape;
+mod skinning;
 
 pub use mesh::*;
+pub use morph::*;
+pub use skinning::*;
 
-use crate::render_asset::RenderAssetPlugin;
-use bevy_app::{App, Plugin};
+use crate::{render_asset::RenderAssetPlugin, view::VisibilitySystems};
+use bevy_app::{App, CoreStage, Plugin};
 use bevy_asset::AddAsset;
+use bevy_ecs::schedule::ParallelSystemDescriptorCoercion;
+use bevy_transform::TransformSystem;
 
 /// Adds the [`Mesh`] as an asset and makes sure that they are extracted and prepared for the GPU.
 pub struct MeshPlugin;
@@ -15,6 +21,23 @@ pub struct MeshPlugin;
 impl Plugin for MeshPlugin {
     fn build(&self, app: &mut App) {
         app.add_asset::<Mesh>()
-            .add_plugin(RenderAssetPlugin::<Mesh>::default());
+            .add_asset::<SkinnedMeshInverseBindposes>()
+            .add_asset::<SkinnedMeshVertexJoints>()
+            .register_type::<SkinnedMesh>()
+            .register_type::<MorphWeights>()
+            .add_plugin(RenderAssetPlugin::<Mesh>::default())
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                skin_meshes_system
+                    .after(TransformSystem::TransformPropagate)
+                    .before(VisibilitySystems::CalculateBounds)
+                    .before(VisibilitySystems::CheckVisibility),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                morph_meshes_system
+                    .before(VisibilitySystems::CalculateBounds)
+                    .before(VisibilitySystems::CheckVisibility),
+            );
     }
 }
@@ -0,0 +1,226 @@
+use crate::{
+    mesh::{store_deformed_mesh, Mesh, SkinnedMesh, VertexAttributeValues},
+    primitives::Aabb,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{
+    component::Component,
+    prelude::{ChangeTrackers, Without},
+    reflect::ReflectComponent,
+    system::{Query, ResMut},
+};
+use bevy_math::Vec3;
+use bevy_reflect::Reflect;
+use bevy_utils::tracing::warn;
+use thiserror::Error;
+
+/// A morph target (or blend shape) of a [`Mesh`]: displacements of its vertex attributes that are
+/// added to the mesh, scaled by the weight of the target.
+///
+/// Each set of displacements has one value per vertex of the mesh. Tangent displacements don't
+/// affect the handedness stored in the `w` component of the tangents.
+#[derive(Debug, Clone, Default)]
+pub struct MorphTarget {
+    pub positions: Option<Vec<[f32; 3]>>,
+    pub normals: Option<Vec<[f32; 3]>>,
+    pub tangents: Option<Vec<[f32; 3]>>,
+}
+
+/// The weights of the morph targets of a mesh.
+///
+/// Each frame the weights changed, the morph targets of [`MorphWeights::mesh`] are blended on
+/// the CPU, and the result is stored in the [`Handle<Mesh>`] of the entity, which is created the
+/// first time the mesh is morphed. If the entity has a [`SkinnedMesh`], [`SkinnedMesh::mesh`] is
+/// morphed instead before being skinned.
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+pub struct MorphWeights {
+    /// The mesh with the morph targets
+    pub mesh: Handle<Mesh>,
+    /// The weight of each morph target of the mesh
+    pub weights: Vec<f32>,
+}
+
+/// An error that occurs when morphing a [`Mesh`]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MorphError {
+    #[error("the mesh has {targets} morph targets, but {weights} weights were given")]
+    WeightCount { targets: usize, weights: usize },
+    #[error("morph target {0} doesn't have a displacement for each vertex")]
+    InvalidTarget(usize),
+}
+
+/// Adds the displacements of the morph targets of `mesh` scaled by `weights`, and returns the
+/// morphed mesh without its morph targets.
+///
+/// Normals and tangents are normalized after being displaced.
+pub fn morph_mesh(mesh: &Mesh, weights: &[f32]) -> Result<Mesh, MorphError> {
+    fn displace(
+        values: &mut [[f32; 3]],
+        displacements: &[[f32; 3]],
+        weight: f32,
+    ) -> Result<(), ()> {
+        if values.len() != displacements.len() {
+            return Err(());
+        }
+        for (value, displacement) in values.iter_mut().zip(displacements.iter()) {
+            *value = (Vec3::from(*value) + Vec3::from(*displacement) * weight).into();
+        }
+        Ok(())
+    }
+
+    let targets = mesh.morph_targets();
+    if targets.len() != weights.len() {
+        return Err(MorphError::WeightCount {
+            targets: targets.len(),
+            weights: weights.len(),
+        });
+    }
+
+    let mut morphed = mesh.clone();
+    morphed.set_morph_targets(Vec::new());
+    let mut positions = match morphed.remove_attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => Some(positions),
+        _ => None,
+    };
+    let mut normals = match morphed.remove_attribute(Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) => Some(normals),
+        _ => None,
+    };
+    let tangents = match morphed.remove_attribute(Mesh::ATTRIBUTE_TANGENT) {
+        Some(VertexAttributeValues::Float32x4(tangents)) => Some(tangents),
+        _ => None,
+    };
+    let mut tangent_directions: Option<Vec<[f32; 3]>> = tangents.as_ref().map(|tangents| {
+        tangents
+            .iter()
+            .map(|tangent| [tangent[0], tangent[1], tangent[2]])
+            .collect()
+    });
+
+    for (index, (target, weight)) in targets.iter().zip(weights.iter()).enumerate() {
+        if *weight == 0.0 {
+            continue;
+        }
+        for (values, displacements) in [
+            (&mut positions, &target.positions),
+            (&mut normals, &target.normals),
+            (&mut tangent_directions, &target.tangents),
+        ] {
+            if let (Some(values), Some(displacements)) = (values, displacements) {
+                displace(values, displacements, *weight)
+                    .map_err(|_| MorphError::InvalidTarget(index))?;
+            }
+        }
+    }
+
+    if let Some(positions) = positions {
+        morphed.set_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    }
+    if let Some(mut normals) = normals {
+        for normal in normals.iter_mut() {
+            *normal = Vec3::from(*normal).normalize_or_zero().into();
+        }
+        morphed.set_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    }
+    if let (Some(mut tangents), Some(tangent_directions)) = (tangents, tangent_directions) {
+        for (tangent, direction) in tangents.iter_mut().zip(tangent_directions.iter()) {
+            let direction = Vec3::from(*direction).normalize_or_zero();
+            *tangent = [direction.x, direction.y, direction.z, tangent[3]];
+        }
+        morphed.set_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
+    }
+    Ok(morphed)
+}
+
+/// Morphs the meshes of the [`MorphWeights`] that changed since the last frame. Skinned meshes
+/// are morphed by [`skin_meshes_system`](crate::mesh::skin_meshes_system).
+#[allow(clippy::type_complexity)]
+pub fn morph_meshes_system(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut morphed_meshes: Query<
+        (
+            &MorphWeights,
+            ChangeTrackers<MorphWeights>,
+            &mut Handle<Mesh>,
+            Option<&mut Aabb>,
+        ),
+        Without<SkinnedMesh>,
+    >,
+) {
+    for (morph_weights, morph_weights_changes, mut mesh_handle, aabb) in morphed_meshes.iter_mut() {
+        let is_morphed = mesh_handle.id != morph_weights.mesh.id && meshes.contains(&*mesh_handle);
+        if is_morphed && !morph_weights_changes.is_changed() {
+            continue;
+        }
+        let morphed = match meshes.get(&morph_weights.mesh) {
+            Some(mesh) => match morph_mesh(mesh, &morph_weights.weights) {
+                Ok(morphed) => morphed,
+                Err(err) => {
+                    warn!("failed to morph mesh: {}", err);
+                    continue;
+                }
+            },
+            None => continue,
+        };
+        store_deformed_mesh(&mut meshes, &mut mesh_handle, is_morphed, morphed, aabb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_resource::PrimitiveTopology;
+
+    #[test]
+    fn morph_mesh_with_weights() {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        );
+        mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; 3]);
+        mesh.set_morph_targets(vec![
+            MorphTarget {
+                positions: Some(vec![[0.0, 0.0, 2.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]),
+                normals: Some(vec![[0.0, 1.0, -1.0]; 3]),
+                tangents: None,
+            },
+            MorphTarget {
+                positions: Some(vec![[1.0, 0.0, 0.0]; 3]),
+                ..Default::default()
+            },
+        ]);
+
+        let morphed = morph_mesh(&mesh, &[0.5, 1.0]).unwrap();
+        assert!(morphed.morph_targets().is_empty());
+        match morphed.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => assert_eq!(
+                positions,
+                &vec![[1.0, 0.0, 1.0], [2.0, 0.0, 0.0], [1.0, 1.0, 0.0]]
+            ),
+            _ => panic!("missing positions"),
+        }
+        let normal = match morphed.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) => Vec3::from(normals[0]),
+            _ => panic!("missing normals"),
+        };
+        assert!(normal.abs_diff_eq(Vec3::new(0.0, 1.0, 1.0).normalize(), 1e-6));
+
+        assert_eq!(
+            morph_mesh(&mesh, &[1.0]).unwrap_err(),
+            MorphError::WeightCount {
+                targets: 2,
+                weights: 1
+            }
+        );
+        mesh.set_morph_targets(vec![MorphTarget {
+            positions: Some(vec![[1.0, 0.0, 0.0]]),
+            ..Default::default()
+        }]);
+        assert_eq!(
+            morph_mesh(&mesh, &[1.0]).unwrap_err(),
+            MorphError::InvalidTarget(0)
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use crate::{
+    mesh::{morph_mesh, Mesh, MorphWeights, VertexAttributeValues},
+    primitives::Aabb,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{
+    component::Component,
+    entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    prelude::{ChangeTrackers, Mut},
+    reflect::{ReflectComponent, ReflectMapEntities},
+    system::{Query, Res, ResMut},
+};
+use bevy_math::{Mat4, Vec3};
+use bevy_reflect::{Reflect, TypeUuid};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::tracing::warn;
+use std::{borrow::Cow, ops::Deref};
+use thiserror::Error;
+
+/// The inverse bind matrices of the joints of a [`SkinnedMesh`], transforming the vertices of
+/// the mesh in bind pose to the space of each joint
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "eeaa2f74-399e-4cca-b82f-9eef04216303"]
+pub struct SkinnedMeshInverseBindposes(Box<[Mat4]>);
+
+impl From<Vec<Mat4>> for SkinnedMeshInverseBindposes {
+    fn from(value: Vec<Mat4>) -> Self {
+        Self(value.into_boxed_slice())
+    }
+}
+
+impl Deref for SkinnedMeshInverseBindposes {
+    type Target = [Mat4];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The joints influencing each vertex of a [`SkinnedMesh`].
+///
+/// They are kept apart from the [`Mesh`], whose vertex layout must match the one of the
+/// pipeline rendering it.
+#[derive(Debug, Clone, Default, TypeUuid)]
+#[uuid = "640c792e-379e-465e-80be-ca8e5fed87f1"]
+pub struct SkinnedMeshVertexJoints {
+    /// The indices in [`SkinnedMesh::joints`] of the joints of each vertex
+    pub indices: Vec<[u16; 4]>,
+    /// The weights of the joints of each vertex
+    pub weights: Vec<[f32; 4]>,
+}
+
+/// A mesh deformed by the transforms of joint entities.
+///
+/// Each frame, the bind pose [`SkinnedMesh::mesh`] is skinned on the CPU with the joint
+/// matrices of the current joint transforms, and the result is stored in the [`Handle<Mesh>`]
+/// of the entity, which is created the first time the mesh is skinned.
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component, MapEntities)]
+pub struct SkinnedMesh {
+    /// The mesh in bind pose
+    pub mesh: Handle<Mesh>,
+    pub vertex_joints: Handle<SkinnedMeshVertexJoints>,
+    pub inverse_bindposes: Handle<SkinnedMeshInverseBindposes>,
+    /// The joint entities, indexed by the joint indices of the mesh
+    pub joints: Vec<Entity>,
+}
+
+impl MapEntities for SkinnedMesh {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for joint in self.joints.iter_mut() {
+            *joint = entity_map.get(*joint)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SkinnedMesh {
+    /// Computes the joint matrices transforming the vertices of the bind pose mesh to their
+    /// skinned positions, in the space of the skinned mesh entity.
+    ///
+    /// `joint_transform` returns the [`GlobalTransform`] of a joint entity. Returns `None` if a
+    /// joint has no transform, or if the number of joints doesn't match the number of inverse
+    /// bind matrices.
+    pub fn joint_matrices(
+        &self,
+        mesh_transform: &GlobalTransform,
+        inverse_bindposes: &SkinnedMeshInverseBindposes,
+        mut joint_transform: impl FnMut(Entity) -> Option<GlobalTransform>,
+    ) -> Option<Vec<Mat4>> {
+        if self.joints.len() != inverse_bindposes.len() {
+            return None;
+        }
+        let inverse_mesh_transform = mesh_transform.compute_matrix().inverse();
+        self.joints
+            .iter()
+            .zip(inverse_bindposes.iter())
+            .map(|(joint, inverse_bindpose)| {
+                let joint_transform = joint_transform(*joint)?.compute_matrix();
+                Some(inverse_mesh_transform * joint_transform * *inverse_bindpose)
+            })
+            .collect()
+    }
+}
+
+/// An error that occurs when skinning a [`Mesh`]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SkinningError {
+    #[error("the mesh has {0} vertices, but the joints of {1} vertices are given")]
+    VertexCountMismatch(usize, usize),
+    #[error("the mesh references joint {0}, but there are only {1} joints")]
+    InvalidJoint(usize, usize),
+}
+
+/// Skins the vertices of `mesh` with `joint_matrices`, and returns the skinned mesh.
+///
+/// Positions, normals and tangents are transformed by the joint matrices of each vertex,
+/// weighted by its joint weights.
+pub fn skin_mesh(
+    mesh: &Mesh,
+    vertex_joints: &SkinnedMeshVertexJoints,
+    joint_matrices: &[Mat4],
+) -> Result<Mesh, SkinningError> {
+    let vertex_count = mesh.count_vertices();
+    if vertex_joints.indices.len() != vertex_count || vertex_joints.weights.len() != vertex_count {
+        return Err(SkinningError::VertexCountMismatch(
+            vertex_count,
+            vertex_joints.indices.len().min(vertex_joints.weights.len()),
+        ));
+    }
+
+    // the skinning matrix of each vertex
+    let matrices = vertex_joints
+        .indices
+        .iter()
+        .zip(vertex_joints.weights.iter())
+        .map(|(indices, weights)| {
+            let total_weight: f32 = weights.iter().sum();
+            if total_weight <= 0.0 {
+                return Ok(Mat4::IDENTITY);
+            }
+            let mut matrix = Mat4::ZERO;
+            for (index, weight) in indices.iter().zip(weights.iter()) {
+                if *weight == 0.0 {
+                    continue;
+                }
+                let joint_matrix =
+                    joint_matrices
+                        .get(*index as usize)
+                        .ok_or(SkinningError::InvalidJoint(
+                            *index as usize,
+                            joint_matrices.len(),
+                        ))?;
+                matrix += *joint_matrix * (*weight / total_weight);
+            }
+            Ok(matrix)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut skinned = mesh.clone();
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        skinned.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for (position, matrix) in positions.iter_mut().zip(matrices.iter()) {
+            *position = matrix.transform_point3(Vec3::from(*position)).into();
+        }
+    }
+    if let Some(VertexAttributeValues::Float32x3(normals)) =
+        skinned.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+    {
+        for (normal, matrix) in normals.iter_mut().zip(matrices.iter()) {
+            *normal = matrix
+                .transform_vector3(Vec3::from(*normal))
+                .normalize_or_zero()
+                .into();
+        }
+    }
+    if let Some(VertexAttributeValues::Float32x4(tangents)) =
+        skinned.attribute_mut(Mesh::ATTRIBUTE_TANGENT)
+    {
+        for (tangent, matrix) in tangents.iter_mut().zip(matrices.iter()) {
+            let direction = matrix
+                .transform_vector3(Vec3::new(tangent[0], tangent[1], tangent[2]))
+                .normalize_or_zero();
+            *tangent = [direction.x, direction.y, direction.z, tangent[3]];
+        }
+    }
+    Ok(skinned)
+}
+
+/// Skins the [`SkinnedMesh`]es whose joints moved since the last frame. If the entity has
+/// [`MorphWeights`], the mesh is morphed before being skinned.
+#[allow(clippy::type_complexity)]
+pub fn skin_meshes_system(
+    mut meshes: ResMut<Assets<Mesh>>,
+    inverse_bindposes: Res<Assets<SkinnedMeshInverseBindposes>>,
+    vertex_joints: Res<Assets<SkinnedMeshVertexJoints>>,
+    mut skinned_meshes: Query<(
+        &SkinnedMesh,
+        ChangeTrackers<SkinnedMesh>,
+        &GlobalTransform,
+        ChangeTrackers<GlobalTransform>,
+        Option<(&MorphWeights, ChangeTrackers<MorphWeights>)>,
+        &mut Handle<Mesh>,
+        Option<&mut Aabb>,
+    )>,
+    joints: Query<(&GlobalTransform, ChangeTrackers<GlobalTransform>)>,
+) {
+    for (
+        skinned_mesh,
+        skinned_mesh_changes,
+        transform,
+        transform_changes,
+        morph_weights,
+        mut mesh_handle,
+        aabb,
+    ) in skinned_meshes.iter_mut()
+    {
+        let is_skinned = mesh_handle.id != skinned_mesh.mesh.id && meshes.contains(&*mesh_handle);
+        let joints_changed = skinned_mesh.joints.iter().any(|joint| {
+            joints
+                .get(*joint)
+                .map_or(false, |(_, changes)| changes.is_changed())
+        });
+        let morph_weights_changed = morph_weights
+            .as_ref()
+            .map_or(false, |(_, changes)| changes.is_changed());
+        if is_skinned
+            && !skinned_mesh_changes.is_changed()
+            && !transform_changes.is_changed()
+            && !joints_changed
+            && !morph_weights_changed
+        {
+            continue;
+        }
+
+        let (bind_pose_mesh, inverse_bindposes, vertex_joints) = match (
+            meshes.get(&skinned_mesh.mesh),
+            inverse_bindposes.get(&skinned_mesh.inverse_bindposes),
+            vertex_joints.get(&skinned_mesh.vertex_joints),
+        ) {
+            (Some(mesh), Some(inverse_bindposes), Some(vertex_joints)) => {
+                (mesh, inverse_bindposes, vertex_joints)
+            }
+            _ => continue,
+        };
+        let joint_matrices =
+            match skinned_mesh.joint_matrices(transform, inverse_bindposes, |joint| {
+                joints.get(joint).ok().map(|(transform, _)| *transform)
+            }) {
+                Some(joint_matrices) => joint_matrices,
+                None => continue,
+            };
+        let bind_pose_mesh = match morph_weights {
+            Some((morph_weights, _)) => match morph_mesh(bind_pose_mesh, &morph_weights.weights) {
+                Ok(morphed) => Cow::Owned(morphed),
+                Err(err) => {
+                    warn!("failed to morph mesh: {}", err);
+                    continue;
+                }
+            },
+            None => Cow::Borrowed(bind_pose_mesh),
+        };
+        let skinned = match skin_mesh(&bind_pose_mesh, vertex_joints, &joint_matrices) {
+            Ok(skinned) => skinned,
+            Err(err) => {
+                warn!("failed to skin mesh: {}", err);
+                continue;
+            }
+        };
+        store_deformed_mesh(&mut meshes, &mut mesh_handle, is_skinned, skinned, aabb);
+    }
+}
+
+/// Stores a mesh deformed on the CPU in `mesh_handle`, creating a new mesh unless the handle
+/// already holds the deformed mesh of a previous frame, and updates its bounds
+pub(crate) fn store_deformed_mesh(
+    meshes: &mut Assets<Mesh>,
+    mesh_handle: &mut Handle<Mesh>,
+    is_deformed: bool,
+    deformed: Mesh,
+    aabb: Option<Mut<Aabb>>,
+) {
+    if let (Some(mut aabb), Some(deformed_aabb)) = (aabb, deformed.compute_aabb()) {
+        *aabb = deformed_aabb;
+    }
+    if is_deformed {
+        *meshes.get_mut(&*mesh_handle).unwrap() = deformed;
+    } else {
+        *mesh_handle = meshes.add(deformed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_resource::PrimitiveTopology;
+    use bevy_math::Quat;
+    use bevy_transform::components::Transform;
+
+    #[test]
+    fn skin_mesh_with_joint_matrices() {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 1.0, 0.0], [0.0, 2.0, 0.0], [0.0, 3.0, 0.0]],
+        );
+        mesh.set_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[1.0, 0.0, 0.0]; 3]);
+        let vertex_joints = SkinnedMeshVertexJoints {
+            indices: vec![[0, 0, 0, 0], [0, 1, 0, 0], [1, 0, 0, 0]],
+            weights: vec![
+                [1.0, 0.0, 0.0, 0.0],
+                [0.5, 0.5, 0.0, 0.0],
+                [1.0, 0.0, 0.0, 0.0],
+            ],
+        };
+
+        // two joints: the root at the origin, and a joint at y = 2 rotated a quarter turn
+        let root = Entity::new(0);
+        let elbow = Entity::new(1);
+        let skinned_mesh = SkinnedMesh {
+            joints: vec![root, elbow],
+            ..Default::default()
+        };
+        let inverse_bindposes = SkinnedMeshInverseBindposes::from(vec![
+            Mat4::IDENTITY,
+            Mat4::from_translation(Vec3::new(0.0, -2.0, 0.0)),
+        ]);
+        let mesh_transform = GlobalTransform::from_translation(Vec3::new(10.0, 0.0, 0.0));
+        let joint_transform = |joint| {
+            Some(if joint == root {
+                GlobalTransform::from_translation(Vec3::new(10.0, 0.0, 0.0))
+            } else {
+                GlobalTransform::from(
+                    Transform::from_xyz(10.0, 2.0, 0.0)
+                        .with_rotation(Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2)),
+                )
+            })
+        };
+        let joint_matrices = skinned_mesh
+            .joint_matrices(&mesh_transform, &inverse_bindposes, joint_transform)
+            .unwrap();
+        assert!(joint_matrices[0].abs_diff_eq(Mat4::IDENTITY, 1e-6));
+
+        let skinned = skin_mesh(&mesh, &vertex_joints, &joint_matrices).unwrap();
+        let positions = match skinned.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions,
+            _ => panic!("missing positions"),
+        };
+        let expected = [[0.0, 1.0, 0.0], [0.0, 2.0, 0.0], [1.0, 2.0, 0.0]];
+        for (position, expected) in positions.iter().zip(expected.iter()) {
+            assert!(Vec3::from(*position).abs_diff_eq(Vec3::from(*expected), 1e-5));
+        }
+        let normals = match skinned.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) => normals,
+            _ => panic!("missing normals"),
+        };
+        assert!(Vec3::from(normals[2]).abs_diff_eq(Vec3::new(0.0, -1.0, 0.0), 1e-5));
+
+        // a missing joint transform or an out of range joint index fail
+        assert!(skinned_mesh
+            .joint_matrices(&mesh_transform, &inverse_bindposes, |_| None)
+            .is_none());
+        assert_eq!(
+            skin_mesh(&mesh, &vertex_joints, &joint_matrices[..1]).unwrap_err(),
+            SkinningError::InvalidJoint(1, 1)
+        );
+        let missing_joints = SkinnedMeshVertexJoints {
+            indices: vertex_joints.indices[..2].to_vec(),
+            weights: vertex_joints.weights[..2].to_vec(),
+        };
+        assert_eq!(
+            skin_mesh(&mesh, &missing_joints, &joint_matrices).unwrap_err(),
+            SkinningError::VertexCountMismatch(3, 2)
+        );
+    }
+}
@@ -402,6 +402,7 @@ impl RenderPipelineCache {
                     }
                 }
                 AssetEvent::Removed { handle } => cache.remove_shader(handle),
+                AssetEvent::LoadedWithDependencies { .. } => {}
             }
         }
     }
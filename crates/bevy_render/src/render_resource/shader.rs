@@ -1,12 +1,18 @@
-use bevy_asset::{AssetLoader, Handle, LoadContext, LoadedAsset};
+use bevy_asset::{AssetLoader, Handle, LoadContext, LoadedAsset, Processor};
 use bevy_reflect::{TypeUuid, Uuid};
 use bevy_utils::{tracing::error, BoxedFuture, HashMap};
 use naga::back::wgsl::WriterFlags;
 use naga::{valid::ModuleInfo, Module};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Deserialize;
 use std::{
-    borrow::Cow, collections::HashSet, marker::Copy, ops::Deref, path::PathBuf, str::FromStr,
+    borrow::Cow,
+    collections::HashSet,
+    marker::Copy,
+    ops::Deref,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
 use thiserror::Error;
 use wgpu::{ShaderModuleDescriptor, ShaderSource};
@@ -220,6 +226,8 @@ impl ShaderReflection {
 pub struct ShaderLoader;
 
 impl AssetLoader for ShaderLoader {
+    type Settings = ();
+
     fn load<'a>(
         &'a self,
         bytes: &'a [u8],
@@ -264,6 +272,77 @@ impl AssetLoader for ShaderLoader {
     }
 }
 
+/// Settings of the [`ShaderValidationProcessor`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShaderValidationSettings {
+    pub validate: bool,
+}
+
+impl Default for ShaderValidationSettings {
+    fn default() -> Self {
+        ShaderValidationSettings { validate: true }
+    }
+}
+
+/// An asset [`Processor`](bevy_asset::Processor) failing on shaders that don't compile, so
+/// that errors are caught when processing assets rather than when running the app.
+///
+/// Shaders using preprocessor directives, such as `#import` or `#ifdef`, depend on the
+/// pipeline they are used in and are not validated. Processed shaders are left unchanged.
+#[derive(Default)]
+pub struct ShaderValidationProcessor;
+
+impl Processor for ShaderValidationProcessor {
+    type Settings = ShaderValidationSettings;
+
+    fn process<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        settings: &'a Self::Settings,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, anyhow::Error>> {
+        Box::pin(async move {
+            if settings.validate {
+                let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+                let shader = match ext {
+                    "spv" => Some(ProcessedShader::SpirV(Cow::Owned(bytes.to_vec()))),
+                    _ => {
+                        let source = std::str::from_utf8(bytes)?;
+                        let has_directives = source
+                            .lines()
+                            .any(|line| line.trim_start().starts_with('#'));
+                        match ext {
+                            _ if has_directives => None,
+                            "wgsl" => Some(ProcessedShader::Wgsl(Cow::Owned(source.to_string()))),
+                            "vert" => Some(ProcessedShader::Glsl(
+                                Cow::Owned(source.to_string()),
+                                naga::ShaderStage::Vertex,
+                            )),
+                            "frag" => Some(ProcessedShader::Glsl(
+                                Cow::Owned(source.to_string()),
+                                naga::ShaderStage::Fragment,
+                            )),
+                            _ => None,
+                        }
+                    }
+                };
+                if let Some(shader) = shader {
+                    shader.reflect()?;
+                }
+            }
+            Ok(bytes.to_vec())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["spv", "wgsl", "vert", "frag"]
+    }
+
+    fn name(&self) -> &str {
+        "shader_validation"
+    }
+}
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum ProcessShaderError {
     #[error("Too many '# endif' lines. Each endif should be preceded by an if statement.")]
@@ -1210,4 +1289,26 @@ fn in_main() { }
             .unwrap();
         assert_eq!(result.get_wgsl_source().unwrap(), EXPECTED);
     }
+
+    #[test]
+    fn validation_processor() {
+        use crate::render_resource::{ShaderValidationProcessor, ShaderValidationSettings};
+        use bevy_asset::Processor;
+        use futures_lite::future::block_on;
+        use std::path::Path;
+
+        let process = |source: &str, validate: bool| {
+            block_on(ShaderValidationProcessor.process(
+                source.as_bytes(),
+                &ShaderValidationSettings { validate },
+                Path::new("shader.wgsl"),
+            ))
+        };
+        let valid = "[[stage(compute), workgroup_size(1)]]\nfn main() {}\n";
+        assert_eq!(process(valid, true).unwrap(), valid.as_bytes());
+        assert!(process("fn main() { oops }", true).is_err());
+        assert!(process("fn main() { oops }", false).is_ok());
+        // shaders with preprocessor directives are not validated
+        assert!(process("#import bevy_pbr::mesh\nfn main() { oops }", true).is_ok());
+    }
 }
@@ -1,7 +1,9 @@
 use anyhow::Result;
 use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
 use bevy_utils::BoxedFuture;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use wgpu::{FilterMode, TextureFormat};
 
 use crate::texture::{Image, ImageType, TextureError};
 
@@ -11,7 +13,45 @@ pub struct ImageTextureLoader;
 
 const FILE_EXTENSIONS: &[&str] = &["png", "dds", "tga", "jpg", "jpeg", "bmp"];
 
+/// Settings of the [`ImageTextureLoader`], see
+/// [`AssetServer::load_with_settings`](bevy_asset::AssetServer::load_with_settings).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageLoaderSettings {
+    /// Whether the colors of the image are in sRGB space, like in color textures, or in linear
+    /// space, like in normal maps. Only affects 8-bit RGBA images.
+    pub is_srgb: bool,
+    /// The filtering used when sampling the image. `None` keeps the default sampler.
+    pub filter: Option<ImageFilter>,
+}
+
+impl Default for ImageLoaderSettings {
+    fn default() -> Self {
+        ImageLoaderSettings {
+            is_srgb: true,
+            filter: None,
+        }
+    }
+}
+
+/// The filtering of an image loaded by the [`ImageTextureLoader`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFilter {
+    Nearest,
+    Linear,
+}
+
+impl From<ImageFilter> for FilterMode {
+    fn from(filter: ImageFilter) -> Self {
+        match filter {
+            ImageFilter::Nearest => FilterMode::Nearest,
+            ImageFilter::Linear => FilterMode::Linear,
+        }
+    }
+}
+
 impl AssetLoader for ImageTextureLoader {
+    type Settings = ImageLoaderSettings;
+
     fn load<'a>(
         &'a self,
         bytes: &'a [u8],
@@ -21,12 +61,29 @@ impl AssetLoader for ImageTextureLoader {
             // use the file extension for the image type
             let ext = load_context.path().extension().unwrap().to_str().unwrap();
 
-            let dyn_img = Image::from_buffer(bytes, ImageType::Extension(ext)).map_err(|err| {
-                FileTextureError {
-                    error: err,
-                    path: format!("{}", load_context.path().display()),
-                }
-            })?;
+            let mut dyn_img =
+                Image::from_buffer(bytes, ImageType::Extension(ext)).map_err(|err| {
+                    FileTextureError {
+                        error: err,
+                        path: format!("{}", load_context.path().display()),
+                    }
+                })?;
+
+            let settings = load_context.settings::<ImageLoaderSettings>();
+            if !settings.is_srgb {
+                let format = &mut dyn_img.texture_descriptor.format;
+                *format = match *format {
+                    TextureFormat::Rgba8UnormSrgb => TextureFormat::Rgba8Unorm,
+                    TextureFormat::Bgra8UnormSrgb => TextureFormat::Bgra8Unorm,
+                    format => format,
+                };
+            }
+            if let Some(filter) = settings.filter {
+                let sampler = &mut dyn_img.sampler_descriptor;
+                sampler.mag_filter = filter.into();
+                sampler.min_filter = filter.into();
+                sampler.mipmap_filter = filter.into();
+            }
 
             load_context.set_default_asset(LoadedAsset::new(dyn_img));
             Ok(())
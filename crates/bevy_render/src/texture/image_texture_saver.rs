@@ -0,0 +1,68 @@
+use anyhow::Result;
+use bevy_asset::AssetSaver;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::texture::{image_texture_conversion::texture_to_image, Image};
+
+/// Saves [`Image`]s as PNG files.
+#[derive(Clone, Default)]
+pub struct ImageTextureSaver;
+
+/// An error that occurs when saving an [`Image`].
+#[derive(Error, Debug)]
+pub enum ImageSaveError {
+    #[error("images of format {0:?} can't be saved")]
+    UnsupportedFormat(wgpu::TextureFormat),
+    #[error("failed to encode the image: {0}")]
+    ImageError(#[from] image::ImageError),
+}
+
+impl AssetSaver for ImageTextureSaver {
+    type Asset = Image;
+
+    fn save(&self, image: &Image, _path: &Path) -> Result<Vec<u8>> {
+        let dyn_img = texture_to_image(image).ok_or(ImageSaveError::UnsupportedFormat(
+            image.texture_descriptor.format,
+        ))?;
+        let mut bytes = Vec::new();
+        dyn_img
+            .write_to(&mut bytes, image::ImageOutputFormat::Png)
+            .map_err(ImageSaveError::from)?;
+        Ok(bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["png"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::ImageType;
+    use wgpu::{Extent3d, TextureDimension, TextureFormat};
+
+    #[test]
+    fn save_and_load() {
+        let image = Image::new(
+            Extent3d {
+                width: 2,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            vec![255, 0, 0, 255, 0, 0, 255, 128],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        let bytes = ImageTextureSaver
+            .save(&image, Path::new("image.png"))
+            .unwrap();
+        let loaded = Image::from_buffer(&bytes, ImageType::Extension("png")).unwrap();
+        assert_eq!(loaded.data, image.data);
+        assert_eq!(
+            loaded.texture_descriptor.size,
+            image.texture_descriptor.size
+        );
+    }
+}
@@ -3,6 +3,8 @@ mod hdr_texture_loader;
 #[allow(clippy::module_inception)]
 mod image;
 mod image_texture_loader;
+#[cfg(feature = "png")]
+mod image_texture_saver;
 mod texture_cache;
 
 pub(crate) mod image_texture_conversion;
@@ -11,6 +13,8 @@ pub use self::image::*;
 #[cfg(feature = "hdr")]
 pub use hdr_texture_loader::*;
 pub use image_texture_loader::*;
+#[cfg(feature = "png")]
+pub use image_texture_saver::*;
 pub use texture_cache::*;
 
 use crate::{render_asset::RenderAssetPlugin, RenderApp, RenderStage};
@@ -25,7 +29,8 @@ impl Plugin for ImagePlugin {
     fn build(&self, app: &mut App) {
         #[cfg(feature = "png")]
         {
-            app.init_asset_loader::<ImageTextureLoader>();
+            app.init_asset_loader::<ImageTextureLoader>()
+                .init_asset_saver::<ImageTextureSaver>();
         }
 
         app.add_plugin(RenderAssetPlugin::<Image>::default())
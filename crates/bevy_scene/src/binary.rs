@@ -0,0 +1,278 @@
+use crate::{
+    serde::{SceneDeserializer, SceneSerializer},
+    DynamicScene,
+};
+use bevy_reflect::{Reflect, ReflectFieldNames, ReflectRef, TypeRegistry, TypeRegistryArc};
+use bevy_utils::tracing::warn;
+use serde::{de::DeserializeSeed, Serialize};
+use std::collections::BTreeSet;
+use thiserror::Error;
+
+/// The bytes binary scenes start with
+pub const BINARY_SCENE_MAGIC: &[u8; 4] = b"BSCN";
+/// The version of the binary scene format written by [`serialize_binary`]
+pub const BINARY_SCENE_VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+
+/// An error that occurs when reading or writing a binary scene
+#[derive(Error, Debug)]
+pub enum BinarySceneError {
+    #[error("the data is not a binary scene")]
+    InvalidHeader,
+    #[error("unsupported binary scene version {0}")]
+    UnsupportedVersion(u32),
+    #[error("failed to encode scene: {0}")]
+    Encode(#[from] rmp_serde::encode::Error),
+    #[error("failed to decode scene: {0}")]
+    Decode(#[from] rmp_serde::decode::Error),
+}
+
+/// Returns `true` if `bytes` start like a binary scene
+pub fn is_binary_scene(bytes: &[u8]) -> bool {
+    bytes.starts_with(BINARY_SCENE_MAGIC)
+}
+
+/// Hashes the names of the structs and tuple structs used by `scene`, with the names of their
+/// fields in `type_registry`.
+///
+/// The hash is stored in the header of binary scenes, to detect the scenes saved by an
+/// application whose types had different fields. Types that the scene doesn't use don't change
+/// the hash, and it is stable across runs and platforms.
+pub fn scene_types_hash(scene: &DynamicScene, type_registry: &TypeRegistry) -> u64 {
+    let mut type_names = BTreeSet::new();
+    let values = scene.resources.iter().chain(
+        scene
+            .entities
+            .iter()
+            .flat_map(|entity| entity.components.iter()),
+    );
+    for value in values {
+        collect_struct_type_names(&**value, &mut type_names);
+    }
+
+    // FNV-1a
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let mut write = |name: &str| {
+        for byte in name.bytes().chain(Some(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    for type_name in type_names {
+        write(type_name);
+        let field_names = type_registry
+            .get_with_name(type_name)
+            .and_then(|registration| registration.data::<ReflectFieldNames>());
+        for field_name in field_names.map_or(&[][..], ReflectFieldNames::names) {
+            write(field_name);
+        }
+        write("");
+    }
+    hash
+}
+
+/// Adds the type names of the structs and tuple structs in `value` to `type_names`
+fn collect_struct_type_names<'a>(value: &'a dyn Reflect, type_names: &mut BTreeSet<&'a str>) {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            type_names.insert(value.type_name());
+            for field in value.iter_fields() {
+                collect_struct_type_names(field, type_names);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            type_names.insert(value.type_name());
+            for field in value.iter_fields() {
+                collect_struct_type_names(field, type_names);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for field in value.iter_fields() {
+                collect_struct_type_names(field, type_names);
+            }
+        }
+        ReflectRef::List(value) => {
+            for item in value.iter() {
+                collect_struct_type_names(item, type_names);
+            }
+        }
+        ReflectRef::Map(value) => {
+            for (key, value) in value.iter() {
+                collect_struct_type_names(key, type_names);
+                collect_struct_type_names(value, type_names);
+            }
+        }
+        ReflectRef::Value(_) => {}
+    }
+}
+
+/// Serializes `scene` in the binary scene format: a header made of [`BINARY_SCENE_MAGIC`], the
+/// format version and the [`scene_types_hash`] of the scene, followed by the scene encoded with
+/// MessagePack. The scene is written with the same structure as RON scenes.
+pub fn serialize_binary(
+    scene: &DynamicScene,
+    registry: &TypeRegistryArc,
+) -> Result<Vec<u8>, BinarySceneError> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(BINARY_SCENE_MAGIC);
+    bytes.extend_from_slice(&BINARY_SCENE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&scene_types_hash(scene, &registry.read()).to_le_bytes());
+    // structs are written as maps, since reflected values are deserialized without knowing
+    // their type
+    let mut serializer = rmp_serde::Serializer::new(&mut bytes).with_struct_map();
+    SceneSerializer::new(scene, registry).serialize(&mut serializer)?;
+    Ok(bytes)
+}
+
+/// Deserializes a scene written by [`serialize_binary`].
+///
+/// A mismatch of the [`scene_types_hash`] isn't an error: a warning is logged, and the scene is
+/// loaded like RON scenes whose types changed. Its data is migrated if the types registered
+/// migrations, and the fields that no longer exist are ignored when the scene is spawned.
+pub fn deserialize_binary(
+    bytes: &[u8],
+    type_registry: &TypeRegistry,
+) -> Result<DynamicScene, BinarySceneError> {
+    if !is_binary_scene(bytes) || bytes.len() < HEADER_LEN {
+        return Err(BinarySceneError::InvalidHeader);
+    }
+    let mut version = [0; 4];
+    version.copy_from_slice(&bytes[4..8]);
+    let version = u32::from_le_bytes(version);
+    if version != BINARY_SCENE_VERSION {
+        return Err(BinarySceneError::UnsupportedVersion(version));
+    }
+    let mut hash = [0; 8];
+    hash.copy_from_slice(&bytes[8..HEADER_LEN]);
+
+    let mut deserializer = rmp_serde::Deserializer::from_read_ref(&bytes[HEADER_LEN..]);
+    let scene = SceneDeserializer { type_registry }.deserialize(&mut deserializer)?;
+    if u64::from_le_bytes(hash) != scene_types_hash(&scene, type_registry) {
+        warn!("binary scene was saved with different fields for its types, they may not load");
+    }
+    Ok(scene)
+}
+
+/// Converts a RON scene to a binary scene
+pub fn ron_to_binary(ron: &[u8], registry: &TypeRegistryArc) -> anyhow::Result<Vec<u8>> {
+    let mut deserializer = ron::de::Deserializer::from_bytes(ron)?;
+    let scene = SceneDeserializer {
+        type_registry: &registry.read(),
+    }
+    .deserialize(&mut deserializer)?;
+    Ok(serialize_binary(&scene, registry)?)
+}
+
+/// Converts a binary scene to a RON scene
+pub fn binary_to_ron(bytes: &[u8], registry: &TypeRegistryArc) -> anyhow::Result<String> {
+    let scene = deserialize_binary(bytes, &registry.read())?;
+    Ok(scene.serialize_ron(registry)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{
+        component::Component,
+        reflect::{ReflectComponent, ReflectResource},
+        world::World,
+    };
+    use bevy_reflect::Reflect;
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Position {
+        x: f32,
+        y: f32,
+        name: String,
+    }
+
+    #[derive(Reflect, Default)]
+    #[reflect(Resource)]
+    struct Level(u32);
+
+    #[test]
+    fn binary_round_trip() {
+        let registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = registry.write();
+            type_registry.register::<f32>();
+            type_registry.register::<u32>();
+            type_registry.register::<String>();
+            type_registry.register::<Position>();
+            type_registry.register::<Level>();
+        }
+        let mut world = World::new();
+        world.insert_resource(Level(4));
+        for i in 0..3 {
+            world.spawn().insert(Position {
+                x: i as f32,
+                y: -1.5,
+                name: format!("entity {}", i),
+            });
+        }
+        let scene = DynamicScene::from_world(&world, &registry);
+
+        let ron = scene.serialize_ron(&registry).unwrap();
+        let binary = ron_to_binary(ron.as_bytes(), &registry).unwrap();
+        assert!(is_binary_scene(&binary));
+        assert!(binary.len() < ron.len());
+        assert_eq!(binary, serialize_binary(&scene, &registry).unwrap());
+        assert_eq!(binary_to_ron(&binary, &registry).unwrap(), ron);
+
+        let mut world = World::new();
+        world.insert_resource(registry.clone());
+        let scene = deserialize_binary(&binary, &registry.read()).unwrap();
+        scene
+            .write_to_world(&mut world, &mut Default::default())
+            .unwrap();
+        assert_eq!(world.get_resource::<Level>().unwrap().0, 4);
+        let mut positions = world.query::<&Position>();
+        assert_eq!(positions.iter(&world).count(), 3);
+        assert!(positions
+            .iter(&world)
+            .any(|position| position.name == "entity 2" && position.x == 2.0));
+
+        assert!(matches!(
+            deserialize_binary(ron.as_bytes(), &registry.read()),
+            Err(BinarySceneError::InvalidHeader)
+        ));
+        let mut other_version = binary.clone();
+        other_version[4] = 2;
+        assert!(matches!(
+            deserialize_binary(&other_version, &registry.read()),
+            Err(BinarySceneError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn scene_types_hash_only_depends_on_used_types() {
+        let mut type_registry = TypeRegistry::default();
+        type_registry.register::<f32>();
+        type_registry.register::<String>();
+        type_registry.register::<Position>();
+        let mut world = World::new();
+        world.spawn().insert(Position::default());
+        let registry = TypeRegistryArc::default();
+        *registry.write() = type_registry;
+        let scene = DynamicScene::from_world(&world, &registry);
+        let hash = scene_types_hash(&scene, &registry.read());
+
+        // the hash of a loaded scene matches the hash of the saved scene
+        let binary = serialize_binary(&scene, &registry).unwrap();
+        let loaded = deserialize_binary(&binary, &registry.read()).unwrap();
+        assert_eq!(scene_types_hash(&loaded, &registry.read()), hash);
+
+        // registering types the scene doesn't use keeps the hash
+        registry.write().register::<Level>();
+        assert_eq!(scene_types_hash(&scene, &registry.read()), hash);
+
+        // changing the fields of a type used by the scene changes the hash
+        registry
+            .write()
+            .get_mut(std::any::TypeId::of::<Position>())
+            .unwrap()
+            .insert(ReflectFieldNames::new(&["x", "y"]));
+        assert_ne!(scene_types_hash(&scene, &registry.read()), hash);
+    }
+}
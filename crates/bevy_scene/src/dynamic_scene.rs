@@ -1,16 +1,29 @@
-use crate::{serde::SceneSerializer, Scene, SceneSpawnError};
+use crate::{
+    migration::apply_scene_data, serde::SceneSerializer, BinarySceneError, Scene, SceneSpawnError,
+};
 use anyhow::Result;
 use bevy_ecs::{
     entity::EntityMap,
-    reflect::{ReflectComponent, ReflectMapEntities},
+    reflect::{ReflectComponent, ReflectMapEntities, ReflectResource},
     world::World,
 };
 use bevy_reflect::{Reflect, TypeRegistryArc, TypeUuid};
+use bevy_utils::{tracing::warn, HashMap};
 use serde::Serialize;
 
+/// A collection of reflected resources and entities, that can be serialized and written to a
+/// [`World`].
+///
+/// Components and resources don't need to set all their fields: the fields missing from the scene
+/// keep the value given by the [`FromWorld`](bevy_ecs::world::FromWorld) implementation of their
+/// type. The components, resources and fields of the scene that don't match the registered types
+/// are ignored with a warning. `Entity` fields of components registered with
+/// [`ReflectMapEntities`] refer to the entities of the scene, and are mapped to the spawned
+/// entities when the scene is written to a world.
 #[derive(Default, TypeUuid)]
 #[uuid = "749479b1-fb8c-4ff8-a775-623aa76014f5"]
 pub struct DynamicScene {
+    pub resources: Vec<Box<dyn Reflect>>,
     pub entities: Vec<Entity>,
 }
 
@@ -24,9 +37,18 @@ impl DynamicScene {
         Self::from_world(&scene.world, type_registry)
     }
 
+    /// Creates a scene from the reflected components and resources of `world`
     pub fn from_world(world: &World, type_registry: &TypeRegistryArc) -> Self {
         let mut scene = DynamicScene::default();
         let type_registry = type_registry.read();
+        for registration in type_registry.iter() {
+            if let Some(resource) = registration
+                .data::<ReflectResource>()
+                .and_then(|reflect_resource| reflect_resource.reflect_resource(world))
+            {
+                scene.resources.push(resource.clone_value());
+            }
+        }
         for archetype in world.archetypes().iter() {
             let entities_offset = scene.entities.len();
             for entity in archetype.entities() {
@@ -58,6 +80,8 @@ impl DynamicScene {
         scene
     }
 
+    /// Writes the resources and entities of the scene to `world`. The entities of the scene that
+    /// aren't in `entity_map` are spawned and added to it, and the others are updated.
     pub fn write_to_world(
         &self,
         world: &mut World,
@@ -65,39 +89,74 @@ impl DynamicScene {
     ) -> Result<(), SceneSpawnError> {
         let registry = world.get_resource::<TypeRegistryArc>().unwrap().clone();
         let type_registry = registry.read();
+
+        for resource in self.resources.iter() {
+            let reflect_resource = match type_registry
+                .get_with_name(resource.type_name())
+                .and_then(|registration| registration.data::<ReflectResource>())
+            {
+                Some(reflect_resource) => reflect_resource,
+                None => {
+                    warn!(
+                        "scene resource {} ignored: the type isn't registered with `#[reflect(Resource)]`",
+                        resource.type_name()
+                    );
+                    continue;
+                }
+            };
+            let mut new_resource = reflect_resource.from_world(world);
+            apply_scene_data(&mut *new_resource, &**resource);
+            reflect_resource.insert_resource(world, &*new_resource);
+        }
+
+        // the entities written with each component that maps entities
+        let mut entities_to_map = HashMap::<_, Vec<_>>::default();
         for scene_entity in self.entities.iter() {
             let entity = *entity_map
                 .entry(bevy_ecs::entity::Entity::new(scene_entity.entity))
                 .or_insert_with(|| world.spawn().id());
             for component in scene_entity.components.iter() {
-                let registration = type_registry
+                let (registration, reflect_component) = match type_registry
                     .get_with_name(component.type_name())
-                    .ok_or_else(|| SceneSpawnError::UnregisteredType {
-                        type_name: component.type_name().to_string(),
-                    })?;
-                let reflect_component =
-                    registration.data::<ReflectComponent>().ok_or_else(|| {
-                        SceneSpawnError::UnregisteredComponent {
-                            type_name: component.type_name().to_string(),
-                        }
-                    })?;
+                    .and_then(|registration| {
+                        Some((registration, registration.data::<ReflectComponent>()?))
+                    }) {
+                    Some(component_registration) => component_registration,
+                    None => {
+                        warn!(
+                            "scene component {} ignored: the type isn't registered with `#[reflect(Component)]`",
+                            component.type_name()
+                        );
+                        continue;
+                    }
+                };
+                // start from a new component, so that the fields missing from the scene are
+                // reset when the component is updated, like when it is added
+                let mut new_component = reflect_component.from_world(world);
+                apply_scene_data(&mut *new_component, &**component);
                 if world
                     .entity(entity)
                     .contains_type_id(registration.type_id())
                 {
-                    reflect_component.apply_component(world, entity, &**component);
+                    reflect_component.apply_component(world, entity, &*new_component);
                 } else {
-                    reflect_component.add_component(world, entity, &**component);
+                    reflect_component.add_component(world, entity, &*new_component);
+                }
+                if registration.data::<ReflectMapEntities>().is_some() {
+                    entities_to_map
+                        .entry(registration.type_id())
+                        .or_default()
+                        .push(entity);
                 }
             }
         }
 
-        for registration in type_registry.iter() {
-            if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
-                map_entities_reflect
-                    .map_entities(world, entity_map)
-                    .unwrap();
-            }
+        for (type_id, entities) in entities_to_map {
+            type_registry
+                .get(type_id)
+                .and_then(|registration| registration.data::<ReflectMapEntities>())
+                .unwrap()
+                .map_specific_entities(world, entity_map, &entities)?;
         }
 
         Ok(())
@@ -107,6 +166,15 @@ impl DynamicScene {
     pub fn serialize_ron(&self, registry: &TypeRegistryArc) -> Result<String, ron::Error> {
         serialize_ron(SceneSerializer::new(self, registry))
     }
+
+    /// Serializes the scene in the binary scene format, see
+    /// [`serialize_binary`](crate::serialize_binary)
+    pub fn serialize_binary(
+        &self,
+        registry: &TypeRegistryArc,
+    ) -> Result<Vec<u8>, BinarySceneError> {
+        crate::serialize_binary(self, registry)
+    }
 }
 
 pub fn serialize_ron<S>(serialize: S) -> Result<String, ron::Error>
@@ -122,3 +190,178 @@ where
     serialize.serialize(&mut ron_serializer)?;
     Ok(String::from_utf8(buf).unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::SceneDeserializer;
+    use bevy_ecs::{
+        component::Component,
+        entity::{EntityMap, MapEntities, MapEntitiesError},
+        reflect::ReflectResource,
+        world::FromWorld,
+    };
+    use serde::de::DeserializeSeed;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health {
+        max: f32,
+        current: f32,
+    }
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component, MapEntities)]
+    struct Target(bevy_ecs::entity::Entity);
+
+    impl FromWorld for Target {
+        fn from_world(_world: &mut World) -> Self {
+            Target(bevy_ecs::entity::Entity::new(u32::MAX))
+        }
+    }
+
+    impl MapEntities for Target {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            self.0 = entity_map.get(self.0)?;
+            Ok(())
+        }
+    }
+
+    #[derive(Reflect, Default)]
+    #[reflect(Resource)]
+    struct Score {
+        points: u32,
+        name: String,
+    }
+
+    fn type_registry() -> TypeRegistryArc {
+        let type_registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<f32>();
+            type_registry.register::<u32>();
+            type_registry.register::<String>();
+            type_registry.register::<bevy_ecs::entity::Entity>();
+            type_registry.register::<Health>();
+            type_registry.register::<Target>();
+            type_registry.register::<Score>();
+        }
+        type_registry
+    }
+
+    fn deserialize(ron: &str, type_registry: &TypeRegistryArc) -> DynamicScene {
+        let mut deserializer = ron::de::Deserializer::from_str(ron).unwrap();
+        SceneDeserializer {
+            type_registry: &type_registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trip_with_resources_and_entity_references() {
+        let type_registry = type_registry();
+        let mut world = World::new();
+        world.insert_resource(Score {
+            points: 3,
+            name: "blue".to_string(),
+        });
+        let enemy = world
+            .spawn()
+            .insert(Health {
+                max: 10.0,
+                current: 5.0,
+            })
+            .id();
+        world.spawn().insert(Target(enemy));
+
+        let ron = DynamicScene::from_world(&world, &type_registry)
+            .serialize_ron(&type_registry)
+            .unwrap();
+        let scene = deserialize(&ron, &type_registry);
+        assert_eq!(scene.resources.len(), 1);
+        assert_eq!(scene.entities.len(), 2);
+
+        let mut world = World::new();
+        world.insert_resource(type_registry);
+        // offset the ids of the spawned entities from the ids of the scene
+        world.spawn();
+        world.spawn();
+        let mut entity_map = EntityMap::default();
+        scene.write_to_world(&mut world, &mut entity_map).unwrap();
+
+        let score = world.get_resource::<Score>().unwrap();
+        assert_eq!(score.points, 3);
+        assert_eq!(score.name, "blue");
+        let enemy = entity_map.get(enemy).unwrap();
+        assert_ne!(enemy.id(), 0);
+        assert_eq!(world.get::<Health>(enemy).unwrap().current, 5.0);
+        let mut targets = world.query::<&Target>();
+        assert_eq!(targets.iter(&world).next().unwrap().0, enemy);
+
+        // writing the scene again only maps the components of the scene
+        let parent = world.spawn().id();
+        for entity in entity_map.values() {
+            if entity != enemy {
+                world.entity_mut(entity).insert(Target(parent));
+            }
+        }
+        scene.write_to_world(&mut world, &mut entity_map).unwrap();
+        assert_eq!(targets.iter(&world).next().unwrap().0, enemy);
+    }
+
+    #[test]
+    fn missing_fields_and_entity_list() {
+        let type_registry = type_registry();
+        let scene = deserialize(
+            r#"(
+                resources: [
+                    {
+                        "type": "bevy_scene::dynamic_scene::tests::Score",
+                        "struct": { "points": { "type": "u32", "value": 7 } },
+                    },
+                ],
+                entities: [
+                    (
+                        entity: 0,
+                        components: [
+                            {
+                                "type": "bevy_scene::dynamic_scene::tests::Health",
+                                "struct": { "max": { "type": "f32", "value": 2.0 } },
+                            },
+                        ],
+                    ),
+                ],
+            )"#,
+            &type_registry,
+        );
+
+        let mut world = World::new();
+        world.insert_resource(type_registry.clone());
+        let mut entity_map = EntityMap::default();
+        scene.write_to_world(&mut world, &mut entity_map).unwrap();
+        let score = world.get_resource::<Score>().unwrap();
+        assert_eq!(score.points, 7);
+        assert_eq!(score.name, "");
+        let entity = entity_map.get(bevy_ecs::entity::Entity::new(0)).unwrap();
+        world.get_mut::<Health>(entity).unwrap().current = 1.0;
+        // missing fields are reset when the scene is written again
+        scene.write_to_world(&mut world, &mut entity_map).unwrap();
+        let health = world.get::<Health>(entity).unwrap();
+        assert_eq!(health.max, 2.0);
+        assert_eq!(health.current, 0.0);
+
+        // scenes without resources are a list of entities
+        let scene = deserialize(
+            r#"[
+                (
+                    entity: 3,
+                    components: [],
+                ),
+            ]"#,
+            &type_registry,
+        );
+        assert!(scene.resources.is_empty());
+        assert_eq!(scene.entities[0].entity, 3);
+    }
+}
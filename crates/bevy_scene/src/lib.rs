@@ -1,20 +1,27 @@
+mod binary;
 mod command;
 mod dynamic_scene;
+mod migration;
+mod prefab;
 mod scene;
 mod scene_loader;
+mod scene_saver;
 mod scene_spawner;
 pub mod serde;
 
+pub use binary::*;
 pub use command::*;
 pub use dynamic_scene::*;
+pub use prefab::*;
 pub use scene::*;
 pub use scene_loader::*;
+pub use scene_saver::*;
 pub use scene_spawner::*;
 
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        DynamicScene, Scene, SceneSpawner, SpawnSceneAsChildCommands, SpawnSceneCommands,
+        DynamicScene, Prefab, Scene, SceneSpawner, SpawnSceneAsChildCommands, SpawnSceneCommands,
     };
 }
 
@@ -29,7 +36,10 @@ impl Plugin for ScenePlugin {
     fn build(&self, app: &mut App) {
         app.add_asset::<DynamicScene>()
             .add_asset::<Scene>()
+            .add_asset::<Prefab>()
             .init_asset_loader::<SceneLoader>()
+            .init_asset_loader::<PrefabLoader>()
+            .init_asset_saver::<SceneSaver>()
             .init_resource::<SceneSpawner>()
             .add_system_to_stage(
                 CoreStage::PreUpdate,
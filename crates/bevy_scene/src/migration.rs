@@ -0,0 +1,356 @@
+use bevy_reflect::{
+    DynamicStruct, Reflect, ReflectMut, ReflectRef, ReflectVersion, TypeRegistration, TypeRegistry,
+};
+use bevy_utils::{tracing::warn, HashMap};
+
+/// Updates the reflected `values` of a scene, saved with the given `versions` of their types,
+/// with the migrations registered for the types. Types missing from `versions` were saved at
+/// version 0.
+pub(crate) fn migrate(
+    values: &mut [Box<dyn Reflect>],
+    versions: &HashMap<String, u32>,
+    type_registry: &TypeRegistry,
+) {
+    for value in values.iter_mut() {
+        let type_name = value.type_name().to_string();
+        let reflect_version = match type_registry
+            .get_with_name(&type_name)
+            .and_then(|registration| registration.data::<ReflectVersion>())
+        {
+            Some(reflect_version) => reflect_version,
+            None => continue,
+        };
+        let version = versions.get(&type_name).copied().unwrap_or(0);
+        if version == reflect_version.version() {
+            continue;
+        }
+        match value.any_mut().downcast_mut::<DynamicStruct>() {
+            Some(data) => {
+                if let Err(err) = reflect_version.migrate(data, version) {
+                    warn!("failed to migrate scene data of {}: {}", type_name, err);
+                }
+            }
+            None => warn!(
+                "failed to migrate scene data of {}: only structs can be migrated",
+                type_name
+            ),
+        }
+    }
+}
+
+/// Applies the scene `data` of a component or resource to `value`. The fields of `data` that don't
+/// match the fields of `value` are ignored with a warning.
+pub(crate) fn apply_scene_data(value: &mut dyn Reflect, data: &dyn Reflect) {
+    let mut issues = Vec::new();
+    let type_name = value.type_name().to_string();
+    apply_reporting(
+        value,
+        data,
+        &TypeRegistration::get_short_name(&type_name),
+        &mut issues,
+    );
+    for issue in issues {
+        warn!("scene data ignored: {}", issue);
+    }
+}
+
+/// Applies `data` to `value` like [`Reflect::apply`], but reports the fields of `data` that
+/// `value` doesn't have, or that have a different type, instead of panicking. The other fields are
+/// applied.
+fn apply_reporting(
+    value: &mut dyn Reflect,
+    data: &dyn Reflect,
+    path: &str,
+    issues: &mut Vec<String>,
+) {
+    match (value.reflect_mut(), data.reflect_ref()) {
+        (ReflectMut::Struct(value), ReflectRef::Struct(data)) => {
+            for (index, field) in data.iter_fields().enumerate() {
+                let name = data.name_at(index).unwrap();
+                let field_path = format!("{}.{}", path, name);
+                match value.field_mut(name) {
+                    Some(value_field) => apply_reporting(value_field, field, &field_path, issues),
+                    None => issues.push(format!("unknown field {}", field_path)),
+                }
+            }
+            return;
+        }
+        (ReflectMut::TupleStruct(value), ReflectRef::TupleStruct(data)) => {
+            for (index, field) in data.iter_fields().enumerate() {
+                let field_path = format!("{}.{}", path, index);
+                match value.field_mut(index) {
+                    Some(value_field) => apply_reporting(value_field, field, &field_path, issues),
+                    None => issues.push(format!("unknown field {}", field_path)),
+                }
+            }
+            return;
+        }
+        (ReflectMut::Tuple(value), ReflectRef::Tuple(data)) => {
+            for (index, field) in data.iter_fields().enumerate() {
+                let field_path = format!("{}.{}", path, index);
+                match value.field_mut(index) {
+                    Some(value_field) => apply_reporting(value_field, field, &field_path, issues),
+                    None => issues.push(format!("unknown field {}", field_path)),
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let compatible = match (value.reflect_ref(), data.reflect_ref()) {
+        (ReflectRef::List(_), ReflectRef::List(_)) | (ReflectRef::Map(_), ReflectRef::Map(_)) => {
+            true
+        }
+        (ReflectRef::Value(_), ReflectRef::Value(_)) => value.type_name() == data.type_name(),
+        _ => false,
+    };
+    if compatible {
+        value.apply(data);
+    } else {
+        issues.push(format!(
+            "{} is a {}, but the scene gives a {}",
+            path,
+            value.type_name(),
+            data.type_name()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::{PrefabDeserializer, SceneDeserializer};
+    use bevy_ecs::{
+        component::Component,
+        entity::{Entity, EntityMap},
+        reflect::ReflectComponent,
+        world::World,
+    };
+    use bevy_reflect::{GetField, TypeRegistryArc};
+    use serde::de::DeserializeSeed;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Player {
+        name: String,
+        health: f32,
+    }
+
+    #[derive(Reflect, Default)]
+    struct Stats {
+        health: f32,
+        position: (f32, f32),
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn apply_and_report_issues() {
+        let mut data = DynamicStruct::default();
+        data.set_name(std::any::type_name::<Stats>().to_string());
+        data.insert("health", 3u32);
+        data.insert("position", (1.0f32, 2.0f32, 3.0f32));
+        data.insert("tags", vec!["a".to_string()]);
+        data.insert("speed", 1.0f32);
+
+        let mut stats = Stats::default();
+        let mut issues = Vec::new();
+        apply_reporting(&mut stats, &data, "Stats", &mut issues);
+        assert_eq!(stats.health, 0.0);
+        assert_eq!(stats.position, (1.0, 2.0));
+        assert_eq!(stats.tags, vec!["a".to_string()]);
+        assert_eq!(
+            issues,
+            vec![
+                "Stats.health is a f32, but the scene gives a u32".to_string(),
+                "unknown field Stats.position.2".to_string(),
+                "unknown field Stats.speed".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn load_scene_saved_by_previous_version() {
+        let registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = registry.write();
+            type_registry.register::<f32>();
+            type_registry.register::<String>();
+            type_registry.register::<Player>();
+            // version 1 renamed `hp` to `health`
+            type_registry.register_migration::<Player>(|data| {
+                if let Some(hp) = data.remove("hp") {
+                    data.insert_boxed("health", hp);
+                }
+            });
+        }
+
+        // saved at version 0, with a field that doesn't exist anymore
+        let mut deserializer = ron::de::Deserializer::from_str(
+            r#"[
+                (
+                    entity: 0,
+                    components: [
+                        {
+                            "type": "bevy_scene::migration::tests::Player",
+                            "struct": {
+                                "name": { "type": "alloc::string::String", "value": "a" },
+                                "hp": { "type": "f32", "value": 3.0 },
+                                "mana": { "type": "f32", "value": 1.0 },
+                            },
+                        },
+                    ],
+                ),
+            ]"#,
+        )
+        .unwrap();
+        let scene = SceneDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        if let ReflectRef::Struct(player) = scene.entities[0].components[0].reflect_ref() {
+            assert_eq!(player.get_field::<f32>("health"), Some(&3.0));
+            assert!(player.field("hp").is_none());
+        } else {
+            panic!("expected a struct");
+        }
+
+        let mut world = World::new();
+        world.insert_resource(registry.clone());
+        let mut entity_map = EntityMap::default();
+        scene.write_to_world(&mut world, &mut entity_map).unwrap();
+        let player = world
+            .get::<Player>(entity_map.get(Entity::new(0)).unwrap())
+            .unwrap();
+        assert_eq!(player.name, "a");
+        assert_eq!(player.health, 3.0);
+
+        // the current version is saved, and no migration runs when loading it again
+        let ron = scene.serialize_ron(&registry).unwrap();
+        assert!(ron.contains(r#""bevy_scene::migration::tests::Player": 1"#));
+        let mut deserializer = ron::de::Deserializer::from_str(&ron).unwrap();
+        let scene = SceneDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        if let ReflectRef::Struct(player) = scene.entities[0].components[0].reflect_ref() {
+            assert_eq!(player.get_field::<f32>("health"), Some(&3.0));
+        }
+    }
+
+    #[test]
+    fn load_prefab_saved_by_previous_version() {
+        let registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = registry.write();
+            type_registry.register::<f32>();
+            type_registry.register::<Player>();
+            // version 1 renamed `hp` to `health`
+            type_registry.register_migration::<Player>(|data| {
+                if let Some(hp) = data.remove("hp") {
+                    data.insert_boxed("health", hp);
+                }
+            });
+        }
+
+        // saved at version 0, overriding a field that was renamed since
+        let mut deserializer = ron::de::Deserializer::from_str(
+            r#"(
+                base: "player.scn.ron",
+                entities: [
+                    (
+                        entity: 0,
+                        components: [
+                            {
+                                "type": "bevy_scene::migration::tests::Player",
+                                "struct": { "hp": { "type": "f32", "value": 3.0 } },
+                            },
+                        ],
+                    ),
+                ],
+            )"#,
+        )
+        .unwrap();
+        let prefab = PrefabDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        let player_override = |prefab: &crate::Prefab| {
+            if let ReflectRef::Struct(player) = prefab.entities[0].components[0].reflect_ref() {
+                assert_eq!(player.get_field::<f32>("health"), Some(&3.0));
+                assert!(player.field("hp").is_none());
+            } else {
+                panic!("expected a struct");
+            }
+        };
+        player_override(&prefab);
+
+        // the current version is saved, and no migration runs when loading it again
+        let ron = prefab.serialize_ron(&registry).unwrap();
+        assert!(ron.contains(r#""bevy_scene::migration::tests::Player": 1"#));
+        let mut deserializer = ron::de::Deserializer::from_str(&ron).unwrap();
+        let prefab = PrefabDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        assert_eq!(prefab.base, "player.scn.ron".into());
+        player_override(&prefab);
+    }
+
+    #[test]
+    fn load_scene_with_unregistered_types() {
+        let registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = registry.write();
+            type_registry.register::<f32>();
+            type_registry.register::<String>();
+            type_registry.register::<Player>();
+        }
+
+        let mut deserializer = ron::de::Deserializer::from_str(
+            r#"[
+                (
+                    entity: 0,
+                    components: [
+                        {
+                            "type": "game::Inventory",
+                            "value": (slots: [1, 2], gold: 10),
+                        },
+                        {
+                            "type": "bevy_scene::migration::tests::Player",
+                            "struct": {
+                                "name": { "type": "alloc::string::String", "value": "a" },
+                                "pet": { "type": "game::Pet", "value": Dog("rex") },
+                                "health": { "type": "f32", "value": 3.0 },
+                            },
+                        },
+                        {
+                            "type": "game::Quest",
+                            "struct": {
+                                "step": { "type": "f32", "value": 1.0 },
+                            },
+                        },
+                    ],
+                ),
+            ]"#,
+        )
+        .unwrap();
+        let scene = SceneDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        assert_eq!(scene.entities[0].components.len(), 1);
+        if let ReflectRef::Struct(player) = scene.entities[0].components[0].reflect_ref() {
+            assert_eq!(player.get_field::<String>("name"), Some(&"a".to_string()));
+            assert_eq!(player.get_field::<f32>("health"), Some(&3.0));
+            assert!(player.field("pet").is_none());
+        } else {
+            panic!("expected a struct");
+        }
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{
+    migration::apply_scene_data, scene_loader::SCENE_EXTENSIONS, serde::PrefabSerializer,
+    serialize_ron, DynamicScene, InstanceId, SceneSpawnError,
+};
+use bevy_asset::{AssetPath, Handle};
+use bevy_ecs::{
+    component::Component,
+    entity::{Entity, EntityMap},
+    reflect::{ReflectComponent, ReflectMapEntities},
+    system::Command,
+    world::World,
+};
+use bevy_reflect::{Reflect, TypeRegistration, TypeRegistry, TypeRegistryArc, TypeUuid};
+use bevy_transform::{components::Children, hierarchy::AddChild};
+use bevy_utils::{tracing::warn, HashMap};
+use thiserror::Error;
+
+/// A scene defined as changes to a base [`DynamicScene`]. The base can't be another prefab.
+///
+/// Each [`PrefabEntity`] either overrides the entity of the base scene with the same id, or adds
+/// an entity when the base scene has no entity with its id. The instances of a prefab spawned by
+/// the [`SceneSpawner`](crate::SceneSpawner) are updated when the prefab or its base scene
+/// change, and keep their overrides.
+#[derive(Debug, TypeUuid)]
+#[uuid = "2f0b8a6c-87a3-4a7e-9d5b-6e0f3c8b1d42"]
+pub struct Prefab {
+    /// The path of the base scene
+    pub base: AssetPath<'static>,
+    pub entities: Vec<PrefabEntity>,
+}
+
+/// The changes made by a [`Prefab`] to an entity of its base scene, or an entity added by the
+/// prefab.
+#[derive(Debug, Default)]
+pub struct PrefabEntity {
+    pub entity: u32,
+    /// The entity this entity is added as a child of
+    pub parent: Option<u32>,
+    /// Components added to the entity, or applied to its components from the base scene. They
+    /// only need to set the fields that are overridden.
+    pub components: Vec<Box<dyn Reflect>>,
+    /// The type names of the components of the base scene removed from the entity
+    pub removed_components: Vec<String>,
+}
+
+/// Links the entities spawned for an instance of a [`Prefab`] to the prefab.
+#[derive(Component, Debug, Clone)]
+pub struct PrefabInstance {
+    pub prefab: Handle<Prefab>,
+    pub instance_id: InstanceId,
+}
+
+/// An error that occurs when loading a [`Prefab`]
+#[derive(Error, Debug)]
+pub enum PrefabError {
+    #[error("the base {0:?} of the prefab isn't a scene file: prefabs can only be based on a `DynamicScene`")]
+    InvalidBase(AssetPath<'static>),
+}
+
+impl Prefab {
+    /// Checks that the base of the prefab is a [`DynamicScene`] loaded from a scene file, and not
+    /// another prefab or a labeled asset.
+    pub fn check_base(&self) -> Result<(), PrefabError> {
+        let file_name = self
+            .base
+            .path()
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let is_scene_file = SCENE_EXTENSIONS
+            .iter()
+            .any(|extension| file_name.ends_with(&format!(".{}", extension)));
+        if is_scene_file && self.base.label().is_none() {
+            Ok(())
+        } else {
+            Err(PrefabError::InvalidBase(self.base.clone()))
+        }
+    }
+
+    /// Writes the `base` scene of the prefab to `world`, then applies the changes of the prefab.
+    /// The entities that aren't in `entity_map` are spawned and added to it, and the others are
+    /// updated.
+    pub fn write_to_world(
+        &self,
+        base: &DynamicScene,
+        world: &mut World,
+        entity_map: &mut EntityMap,
+    ) -> Result<(), SceneSpawnError> {
+        base.write_to_world(world, entity_map)?;
+
+        let registry = world.get_resource::<TypeRegistryArc>().unwrap().clone();
+        let type_registry = registry.read();
+        let mut entities_to_map = HashMap::<_, Vec<_>>::default();
+        for prefab_entity in self.entities.iter() {
+            let entity = *entity_map
+                .entry(Entity::new(prefab_entity.entity))
+                .or_insert_with(|| world.spawn().id());
+            let base_entity = base
+                .entities
+                .iter()
+                .find(|base_entity| base_entity.entity == prefab_entity.entity);
+            for component in prefab_entity.components.iter() {
+                let (registration, reflect_component) =
+                    match component_registration(&type_registry, component.type_name()) {
+                        Some(component_registration) => component_registration,
+                        None => continue,
+                    };
+                // overrides are applied on top of the scene data of the base component rather than
+                // the component written to the world, whose entities are already mapped
+                let mut new_component = reflect_component.from_world(world);
+                let base_component = base_entity.and_then(|base_entity| {
+                    base_entity
+                        .components
+                        .iter()
+                        .find(|base_component| base_component.type_name() == registration.name())
+                });
+                if let Some(base_component) = base_component {
+                    apply_scene_data(&mut *new_component, &**base_component);
+                }
+                apply_scene_data(&mut *new_component, &**component);
+                if world
+                    .entity(entity)
+                    .contains_type_id(registration.type_id())
+                {
+                    reflect_component.apply_component(world, entity, &*new_component);
+                } else {
+                    reflect_component.add_component(world, entity, &*new_component);
+                }
+                if registration.data::<ReflectMapEntities>().is_some() {
+                    entities_to_map
+                        .entry(registration.type_id())
+                        .or_default()
+                        .push(entity);
+                }
+            }
+            for type_name in prefab_entity.removed_components.iter() {
+                let (registration, reflect_component) =
+                    match component_registration(&type_registry, type_name) {
+                        Some(component_registration) => component_registration,
+                        None => continue,
+                    };
+                if world
+                    .entity(entity)
+                    .contains_type_id(registration.type_id())
+                {
+                    reflect_component.remove_component(world, entity);
+                }
+            }
+        }
+
+        for (type_id, entities) in entities_to_map {
+            type_registry
+                .get(type_id)
+                .and_then(|registration| registration.data::<ReflectMapEntities>())
+                .unwrap()
+                .map_specific_entities(world, entity_map, &entities)?;
+        }
+
+        for prefab_entity in self.entities.iter() {
+            if let Some(parent) = prefab_entity.parent {
+                let parent = entity_map.get(Entity::new(parent))?;
+                let child = entity_map.get(Entity::new(prefab_entity.entity))?;
+                // writing the base scene again resets the children of its entities
+                if !world
+                    .get::<Children>(parent)
+                    .map_or(false, |children| children.contains(&child))
+                {
+                    AddChild { parent, child }.write(world);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // TODO: move to AssetSaver when it is implemented
+    pub fn serialize_ron(&self, registry: &TypeRegistryArc) -> Result<String, ron::Error> {
+        serialize_ron(PrefabSerializer::new(self, registry))
+    }
+}
+
+/// Returns the registration of a reflected component, or logs a warning if there's none
+fn component_registration<'a>(
+    type_registry: &'a TypeRegistry,
+    type_name: &str,
+) -> Option<(&'a TypeRegistration, &'a ReflectComponent)> {
+    let component_registration = type_registry
+        .get_with_name(type_name)
+        .and_then(|registration| Some((registration, registration.data::<ReflectComponent>()?)));
+    if component_registration.is_none() {
+        warn!(
+            "prefab component {} ignored: the type isn't registered with `#[reflect(Component)]`",
+            type_name
+        );
+    }
+    component_registration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::PrefabDeserializer;
+    use bevy_ecs::{
+        entity::{MapEntities, MapEntitiesError},
+        reflect::ReflectComponent,
+        world::FromWorld,
+    };
+    use bevy_reflect::DynamicStruct;
+    use bevy_transform::components::Parent;
+    use serde::de::DeserializeSeed;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health {
+        max: f32,
+        current: f32,
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Armor(f32);
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component, MapEntities)]
+    struct Hunter {
+        target: Entity,
+        range: f32,
+    }
+
+    impl FromWorld for Hunter {
+        fn from_world(_world: &mut World) -> Self {
+            Hunter {
+                target: Entity::new(u32::MAX),
+                range: 0.0,
+            }
+        }
+    }
+
+    impl MapEntities for Hunter {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            self.target = entity_map.get(self.target)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_prefab_with_overrides() {
+        let type_registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<f32>();
+            type_registry.register::<Health>();
+            type_registry.register::<Armor>();
+            type_registry.register::<Parent>();
+            type_registry.register::<Children>();
+        }
+        let mut base = World::new();
+        base.spawn().insert(Health {
+            max: 10.0,
+            current: 10.0,
+        });
+        base.spawn().insert(Armor(1.0));
+        let base = DynamicScene::from_world(&base, &type_registry);
+
+        let mut deserializer = ron::de::Deserializer::from_str(
+            r#"(
+                base: "enemy.scn.ron",
+                entities: [
+                    (
+                        entity: 0,
+                        components: [
+                            {
+                                "type": "bevy_scene::prefab::tests::Health",
+                                "struct": { "current": { "type": "f32", "value": 4.0 } },
+                            },
+                        ],
+                    ),
+                    (
+                        entity: 1,
+                        removed_components: ["bevy_scene::prefab::tests::Armor"],
+                    ),
+                    (
+                        entity: 10,
+                        parent: 0,
+                        components: [
+                            {
+                                "type": "bevy_scene::prefab::tests::Armor",
+                                "tuple_struct": [{ "type": "f32", "value": 3.0 }],
+                            },
+                        ],
+                    ),
+                ],
+            )"#,
+        )
+        .unwrap();
+        let prefab = PrefabDeserializer {
+            type_registry: &type_registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        assert_eq!(prefab.base, AssetPath::from("enemy.scn.ron"));
+        assert!(prefab.check_base().is_ok());
+
+        let mut world = World::new();
+        world.insert_resource(type_registry);
+        let mut entity_map = EntityMap::default();
+        for _ in 0..2 {
+            prefab
+                .write_to_world(&base, &mut world, &mut entity_map)
+                .unwrap();
+        }
+
+        let root = entity_map.get(Entity::new(0)).unwrap();
+        let health = world.get::<Health>(root).unwrap();
+        assert_eq!(health.max, 10.0);
+        assert_eq!(health.current, 4.0);
+        assert!(world
+            .get::<Armor>(entity_map.get(Entity::new(1)).unwrap())
+            .is_none());
+        let child = entity_map.get(Entity::new(10)).unwrap();
+        assert_eq!(world.get::<Armor>(child).unwrap().0, 3.0);
+        assert_eq!(world.get::<Parent>(child).unwrap().0, root);
+        assert_eq!(&**world.get::<Children>(root).unwrap(), &[child]);
+    }
+
+    #[test]
+    fn override_fields_of_components_mapping_entities() {
+        let type_registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<f32>();
+            type_registry.register::<Entity>();
+            type_registry.register::<Armor>();
+            type_registry.register::<Hunter>();
+        }
+        let mut base = World::new();
+        let prey = base.spawn().insert(Armor(1.0)).id();
+        base.spawn().insert(Hunter {
+            target: prey,
+            range: 5.0,
+        });
+        let base = DynamicScene::from_world(&base, &type_registry);
+
+        let mut hunter = DynamicStruct::default();
+        hunter.set_name(std::any::type_name::<Hunter>().to_string());
+        hunter.insert("range", 8.0f32);
+        let prefab = Prefab {
+            base: "hunt.scn.ron".into(),
+            entities: vec![PrefabEntity {
+                entity: 1,
+                components: vec![Box::new(hunter)],
+                ..Default::default()
+            }],
+        };
+
+        let mut world = World::new();
+        world.insert_resource(type_registry);
+        // offset the ids of the spawned entities from the ids of the scene
+        for _ in 0..3 {
+            world.spawn();
+        }
+        let mut entity_map = EntityMap::default();
+        for _ in 0..2 {
+            prefab
+                .write_to_world(&base, &mut world, &mut entity_map)
+                .unwrap();
+        }
+
+        let hunter = world
+            .get::<Hunter>(entity_map.get(Entity::new(1)).unwrap())
+            .unwrap();
+        assert_eq!(hunter.range, 8.0);
+        assert_eq!(hunter.target, entity_map.get(prey).unwrap());
+    }
+
+    #[test]
+    fn reject_bases_that_are_not_scenes() {
+        for base in [
+            "enemy.prefab",
+            "enemy.prefab.ron",
+            "enemy.gltf#Scene0",
+            "enemy.scn#Root",
+        ] {
+            let prefab = Prefab {
+                base: AssetPath::from(base).to_owned(),
+                entities: Vec::new(),
+            };
+            assert!(matches!(
+                prefab.check_base(),
+                Err(PrefabError::InvalidBase(_))
+            ));
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::serde::SceneDeserializer;
+use crate::{
+    deserialize_binary, is_binary_scene,
+    serde::{PrefabDeserializer, SceneDeserializer},
+};
 use anyhow::Result;
 use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
 use bevy_ecs::world::{FromWorld, World};
@@ -6,6 +9,10 @@ use bevy_reflect::TypeRegistryArc;
 use bevy_utils::BoxedFuture;
 use serde::de::DeserializeSeed;
 
+/// The extensions of the files the [`SceneLoader`] loads [`DynamicScene`](crate::DynamicScene)s
+/// from
+pub(crate) const SCENE_EXTENSIONS: &[&str] = &["scn", "scn.ron", "scn.bin"];
+
 #[derive(Debug)]
 pub struct SceneLoader {
     type_registry: TypeRegistryArc,
@@ -21,6 +28,52 @@ impl FromWorld for SceneLoader {
 }
 
 impl AssetLoader for SceneLoader {
+    type Settings = ();
+
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let scene = if is_binary_scene(bytes) {
+                deserialize_binary(bytes, &self.type_registry.read())?
+            } else {
+                let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
+                let scene_deserializer = SceneDeserializer {
+                    type_registry: &*self.type_registry.read(),
+                };
+                scene_deserializer.deserialize(&mut deserializer)?
+            };
+            load_context.set_default_asset(LoadedAsset::new(scene));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        SCENE_EXTENSIONS
+    }
+}
+
+/// Loads [`Prefab`](crate::Prefab)s, and the base scene they depend on. Prefabs whose base isn't
+/// a scene file fail to load.
+#[derive(Debug)]
+pub struct PrefabLoader {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for PrefabLoader {
+    fn from_world(world: &mut World) -> Self {
+        let type_registry = world.get_resource::<TypeRegistryArc>().unwrap();
+        PrefabLoader {
+            type_registry: (&*type_registry).clone(),
+        }
+    }
+}
+
+impl AssetLoader for PrefabLoader {
+    type Settings = ();
+
     fn load<'a>(
         &'a self,
         bytes: &'a [u8],
@@ -28,16 +81,18 @@ impl AssetLoader for SceneLoader {
     ) -> BoxedFuture<'a, Result<()>> {
         Box::pin(async move {
             let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
-            let scene_deserializer = SceneDeserializer {
+            let prefab_deserializer = PrefabDeserializer {
                 type_registry: &*self.type_registry.read(),
             };
-            let scene = scene_deserializer.deserialize(&mut deserializer)?;
-            load_context.set_default_asset(LoadedAsset::new(scene));
+            let prefab = prefab_deserializer.deserialize(&mut deserializer)?;
+            prefab.check_base()?;
+            let base = prefab.base.clone();
+            load_context.set_default_asset(LoadedAsset::new(prefab).with_dependency(base));
             Ok(())
         })
     }
 
     fn extensions(&self) -> &[&str] {
-        &["scn", "scn.ron"]
+        &["prefab", "prefab.ron"]
     }
 }
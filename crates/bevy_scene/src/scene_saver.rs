@@ -0,0 +1,38 @@
+use crate::DynamicScene;
+use anyhow::Result;
+use bevy_asset::AssetSaver;
+use bevy_ecs::world::{FromWorld, World};
+use bevy_reflect::TypeRegistryArc;
+use std::path::Path;
+
+/// Saves [`DynamicScene`]s in the formats read by the [`SceneLoader`](crate::SceneLoader): the
+/// binary format for `.scn.bin` files, and RON otherwise.
+#[derive(Debug)]
+pub struct SceneSaver {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for SceneSaver {
+    fn from_world(world: &mut World) -> Self {
+        let type_registry = world.get_resource::<TypeRegistryArc>().unwrap();
+        SceneSaver {
+            type_registry: (&*type_registry).clone(),
+        }
+    }
+}
+
+impl AssetSaver for SceneSaver {
+    type Asset = DynamicScene;
+
+    fn save(&self, scene: &DynamicScene, path: &Path) -> Result<Vec<u8>> {
+        if path.to_string_lossy().ends_with(".scn.bin") {
+            Ok(scene.serialize_binary(&self.type_registry)?)
+        } else {
+            Ok(scene.serialize_ron(&self.type_registry)?.into_bytes())
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["scn", "scn.ron", "scn.bin"]
+    }
+}
@@ -1,8 +1,8 @@
-use crate::{DynamicScene, Scene};
+use crate::{DynamicScene, Prefab, PrefabInstance, Scene};
 use bevy_app::{Events, ManualEventReader};
-use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_asset::{AssetEvent, Assets, Handle, HandleId};
 use bevy_ecs::{
-    entity::{Entity, EntityMap},
+    entity::{Entity, EntityMap, MapEntitiesError},
     reflect::{ReflectComponent, ReflectMapEntities},
     system::Command,
     world::{Mut, World},
@@ -31,11 +31,17 @@ impl InstanceId {
 pub struct SceneSpawner {
     spawned_scenes: HashMap<Handle<Scene>, Vec<InstanceId>>,
     spawned_dynamic_scenes: HashMap<Handle<DynamicScene>, Vec<InstanceId>>,
+    spawned_prefabs: HashMap<Handle<Prefab>, Vec<InstanceId>>,
     spawned_instances: HashMap<InstanceId, InstanceInfo>,
     scene_asset_event_reader: ManualEventReader<AssetEvent<DynamicScene>>,
+    prefab_asset_event_reader: ManualEventReader<AssetEvent<Prefab>>,
     dynamic_scenes_to_spawn: Vec<Handle<DynamicScene>>,
+    prefabs_to_spawn: Vec<(Handle<Prefab>, InstanceId)>,
     scenes_to_spawn: Vec<(Handle<Scene>, InstanceId)>,
     scenes_to_despawn: Vec<Handle<DynamicScene>>,
+    prefabs_to_despawn: Vec<Handle<Prefab>>,
+    /// Spawned prefabs whose update waits for the prefab or its base scene to be loaded
+    prefabs_to_update: Vec<Handle<Prefab>>,
     scenes_with_parent: Vec<(InstanceId, Entity)>,
 }
 
@@ -49,6 +55,10 @@ pub enum SceneSpawnError {
     NonExistentScene { handle: Handle<DynamicScene> },
     #[error("scene does not exist")]
     NonExistentRealScene { handle: Handle<Scene> },
+    #[error("prefab does not exist")]
+    NonExistentPrefab { handle: Handle<Prefab> },
+    #[error("scene refers to an entity it doesn't contain: {0}")]
+    MapEntities(#[from] MapEntitiesError),
 }
 
 impl SceneSpawner {
@@ -69,10 +79,53 @@ impl SceneSpawner {
         instance_id
     }
 
+    /// Spawns an instance of a [`Prefab`]. The instance is updated when the prefab or its base
+    /// scene are modified.
+    pub fn spawn_prefab(&mut self, prefab_handle: Handle<Prefab>) -> InstanceId {
+        let instance_id = InstanceId::new();
+        self.prefabs_to_spawn.push((prefab_handle, instance_id));
+        instance_id
+    }
+
+    pub fn spawn_prefab_as_child(
+        &mut self,
+        prefab_handle: Handle<Prefab>,
+        parent: Entity,
+    ) -> InstanceId {
+        let instance_id = self.spawn_prefab(prefab_handle);
+        self.scenes_with_parent.push((instance_id, parent));
+        instance_id
+    }
+
     pub fn despawn(&mut self, scene_handle: Handle<DynamicScene>) {
         self.scenes_to_despawn.push(scene_handle);
     }
 
+    /// Despawns the instances of a [`Prefab`], including the ones waiting for the prefab to load
+    pub fn despawn_prefab(&mut self, prefab_handle: Handle<Prefab>) {
+        self.prefabs_to_despawn.push(prefab_handle);
+    }
+
+    pub fn despawn_prefab_sync(
+        &mut self,
+        world: &mut World,
+        prefab_handle: Handle<Prefab>,
+    ) -> Result<(), SceneSpawnError> {
+        self.prefabs_to_spawn
+            .retain(|(handle, _)| *handle != prefab_handle);
+        if let Some(instance_ids) = self.spawned_prefabs.remove(&prefab_handle) {
+            for instance_id in instance_ids {
+                if let Some(instance) = self.spawned_instances.remove(&instance_id) {
+                    for entity in instance.entity_map.values() {
+                        // the entity may have been despawned already
+                        let _ = world.despawn(entity);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn despawn_sync(
         &mut self,
         world: &mut World,
@@ -127,6 +180,65 @@ impl SceneSpawner {
         })
     }
 
+    pub fn spawn_prefab_sync(
+        &mut self,
+        world: &mut World,
+        prefab_handle: Handle<Prefab>,
+    ) -> Result<InstanceId, SceneSpawnError> {
+        self.spawn_prefab_sync_internal(world, prefab_handle, InstanceId::new())
+    }
+
+    fn spawn_prefab_sync_internal(
+        &mut self,
+        world: &mut World,
+        prefab_handle: Handle<Prefab>,
+        instance_id: InstanceId,
+    ) -> Result<InstanceId, SceneSpawnError> {
+        let mut entity_map = EntityMap::default();
+        Self::spawn_prefab_internal(world, &prefab_handle, instance_id, &mut entity_map)?;
+        self.spawned_instances
+            .insert(instance_id, InstanceInfo { entity_map });
+        self.spawned_prefabs
+            .entry(prefab_handle)
+            .or_insert_with(Vec::new)
+            .push(instance_id);
+        Ok(instance_id)
+    }
+
+    fn spawn_prefab_internal(
+        world: &mut World,
+        prefab_handle: &Handle<Prefab>,
+        instance_id: InstanceId,
+        entity_map: &mut EntityMap,
+    ) -> Result<(), SceneSpawnError> {
+        world.resource_scope(|world, prefabs: Mut<Assets<Prefab>>| {
+            let prefab =
+                prefabs
+                    .get(prefab_handle)
+                    .ok_or_else(|| SceneSpawnError::NonExistentPrefab {
+                        handle: prefab_handle.clone_weak(),
+                    })?;
+            world.resource_scope(|world, scenes: Mut<Assets<DynamicScene>>| {
+                let base_id = HandleId::from(prefab.base.clone());
+                let base =
+                    scenes
+                        .get(base_id)
+                        .ok_or_else(|| SceneSpawnError::NonExistentScene {
+                            handle: Handle::weak(base_id),
+                        })?;
+                prefab.write_to_world(base, world, entity_map)
+            })
+        })?;
+        let prefab_instance = PrefabInstance {
+            prefab: prefab_handle.clone_weak(),
+            instance_id,
+        };
+        for entity in entity_map.values() {
+            world.entity_mut(entity).insert(prefab_instance.clone());
+        }
+        Ok(())
+    }
+
     pub fn spawn_sync(
         &mut self,
         world: &mut World,
@@ -190,9 +302,7 @@ impl SceneSpawner {
             }
             for registration in type_registry.iter() {
                 if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
-                    map_entities_reflect
-                        .map_entities(world, &instance_info.entity_map)
-                        .unwrap();
+                    map_entities_reflect.map_entities(world, &instance_info.entity_map)?;
                 }
             }
             self.spawned_instances.insert(instance_id, instance_info);
@@ -226,12 +336,58 @@ impl SceneSpawner {
         Ok(())
     }
 
+    /// Updates the spawned instances of the prefabs. The prefabs that aren't loaded, or whose
+    /// base scene isn't loaded, like a new base scene that is still loading, are kept queued and
+    /// updated once they are loaded.
+    pub fn update_spawned_prefabs(
+        &mut self,
+        world: &mut World,
+        prefab_handles: &[Handle<Prefab>],
+    ) -> Result<(), SceneSpawnError> {
+        let mut prefabs_to_update = std::mem::take(&mut self.prefabs_to_update);
+        for prefab_handle in prefab_handles {
+            if !prefabs_to_update.contains(prefab_handle) {
+                prefabs_to_update.push(prefab_handle.clone_weak());
+            }
+        }
+
+        for prefab_handle in prefabs_to_update {
+            if let Some(spawned_instances) = self.spawned_prefabs.get(&prefab_handle) {
+                for instance_id in spawned_instances.iter() {
+                    if let Some(instance_info) = self.spawned_instances.get_mut(instance_id) {
+                        match Self::spawn_prefab_internal(
+                            world,
+                            &prefab_handle,
+                            *instance_id,
+                            &mut instance_info.entity_map,
+                        ) {
+                            Ok(()) => {}
+                            Err(SceneSpawnError::NonExistentPrefab { .. })
+                            | Err(SceneSpawnError::NonExistentScene { .. }) => {
+                                self.prefabs_to_update.push(prefab_handle);
+                                break;
+                            }
+                            Err(err) => return Err(err),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn despawn_queued_scenes(&mut self, world: &mut World) -> Result<(), SceneSpawnError> {
         let scenes_to_despawn = std::mem::take(&mut self.scenes_to_despawn);
 
         for scene_handle in scenes_to_despawn {
             self.despawn_sync(world, scene_handle)?;
         }
+
+        let prefabs_to_despawn = std::mem::take(&mut self.prefabs_to_despawn);
+
+        for prefab_handle in prefabs_to_despawn {
+            self.despawn_prefab_sync(world, prefab_handle)?;
+        }
         Ok(())
     }
 
@@ -248,6 +404,20 @@ impl SceneSpawner {
             }
         }
 
+        let prefabs_to_spawn = std::mem::take(&mut self.prefabs_to_spawn);
+
+        for (prefab_handle, instance_id) in prefabs_to_spawn {
+            match self.spawn_prefab_sync_internal(world, prefab_handle.clone(), instance_id) {
+                Ok(_) => {}
+                // the prefab or its base scene isn't loaded yet
+                Err(SceneSpawnError::NonExistentPrefab { .. })
+                | Err(SceneSpawnError::NonExistentScene { .. }) => {
+                    self.prefabs_to_spawn.push((prefab_handle, instance_id))
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
         let scenes_to_spawn = std::mem::take(&mut self.scenes_to_spawn);
 
         for (scene_handle, instance_id) in scenes_to_spawn {
@@ -316,17 +486,42 @@ pub fn scene_spawner_system(world: &mut World) {
             .unwrap();
 
         let mut updated_spawned_scenes = Vec::new();
+        let mut updated_scene_ids = Vec::new();
         for event in scene_spawner
             .scene_asset_event_reader
             .iter(scene_asset_events)
         {
             if let AssetEvent::Modified { handle } = event {
+                updated_scene_ids.push(handle.id);
                 if scene_spawner.spawned_dynamic_scenes.contains_key(handle) {
                     updated_spawned_scenes.push(handle.clone_weak());
                 }
             }
         }
 
+        // prefabs are updated when they are modified, or when their base scene is
+        let prefab_asset_events = world.get_resource::<Events<AssetEvent<Prefab>>>().unwrap();
+        let mut updated_spawned_prefabs = Vec::new();
+        for event in scene_spawner
+            .prefab_asset_event_reader
+            .iter(prefab_asset_events)
+        {
+            if let AssetEvent::Modified { handle } = event {
+                if scene_spawner.spawned_prefabs.contains_key(handle) {
+                    updated_spawned_prefabs.push(handle.clone_weak());
+                }
+            }
+        }
+        let prefabs = world.get_resource::<Assets<Prefab>>().unwrap();
+        for prefab_handle in scene_spawner.spawned_prefabs.keys() {
+            let base_is_updated = prefabs.get(prefab_handle).map_or(false, |prefab| {
+                updated_scene_ids.contains(&HandleId::from(prefab.base.clone()))
+            });
+            if base_is_updated && !updated_spawned_prefabs.contains(prefab_handle) {
+                updated_spawned_prefabs.push(prefab_handle.clone_weak());
+            }
+        }
+
         scene_spawner.despawn_queued_scenes(world).unwrap();
         scene_spawner
             .spawn_queued_scenes(world)
@@ -334,6 +529,124 @@ pub fn scene_spawner_system(world: &mut World) {
         scene_spawner
             .update_spawned_scenes(world, &updated_spawned_scenes)
             .unwrap();
+        if let Err(err) = scene_spawner.update_spawned_prefabs(world, &updated_spawned_prefabs) {
+            error!("failed to update the instances of a prefab: {}", err);
+        }
         scene_spawner.set_scene_instance_parent_sync(world);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScenePlugin;
+    use bevy_app::App;
+    use bevy_asset::AssetPlugin;
+    use bevy_core::CorePlugin;
+    use bevy_ecs::{component::Component, reflect::ReflectComponent};
+    use bevy_reflect::Reflect;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(f32);
+
+    #[test]
+    fn despawn_prefab_instances() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_plugin(ScenePlugin)
+            .register_type::<Health>();
+
+        let mut base = World::new();
+        base.spawn().insert(Health(10.0));
+        let base = DynamicScene::from_world(&base, app.world.get_resource().unwrap());
+        app.world
+            .get_resource_mut::<Assets<DynamicScene>>()
+            .unwrap()
+            .set_untracked("enemy.scn.ron", base);
+        let prefab = app
+            .world
+            .get_resource_mut::<Assets<Prefab>>()
+            .unwrap()
+            .add(Prefab {
+                base: "enemy.scn.ron".into(),
+                entities: Vec::new(),
+            });
+
+        app.world
+            .resource_scope(|world, mut scene_spawner: Mut<SceneSpawner>| {
+                scene_spawner.spawn_prefab_sync(world, prefab.clone())?;
+                scene_spawner.spawn_prefab_sync(world, prefab.clone())?;
+                Ok::<_, SceneSpawnError>(())
+            })
+            .unwrap();
+        assert_eq!(app.world.query::<&Health>().iter(&app.world).count(), 2);
+
+        app.world
+            .get_resource_mut::<SceneSpawner>()
+            .unwrap()
+            .despawn_prefab(prefab);
+        app.update();
+        assert_eq!(app.world.query::<&Health>().iter(&app.world).count(), 0);
+    }
+
+    #[test]
+    fn update_prefab_instances_once_their_base_is_loaded() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_plugin(ScenePlugin)
+            .register_type::<Health>();
+
+        let scene_with_health = |health: f32, app: &App| {
+            let mut world = World::new();
+            world.spawn().insert(Health(health));
+            DynamicScene::from_world(&world, app.world.get_resource().unwrap())
+        };
+        let enemy = scene_with_health(10.0, &app);
+        app.world
+            .get_resource_mut::<Assets<DynamicScene>>()
+            .unwrap()
+            .set_untracked("enemy.scn.ron", enemy);
+        let prefab = app
+            .world
+            .get_resource_mut::<Assets<Prefab>>()
+            .unwrap()
+            .add(Prefab {
+                base: "enemy.scn.ron".into(),
+                entities: Vec::new(),
+            });
+        app.world
+            .resource_scope(|world, mut scene_spawner: Mut<SceneSpawner>| {
+                scene_spawner.spawn_prefab_sync(world, prefab.clone())
+            })
+            .unwrap();
+
+        // the prefab is reloaded with a base that isn't loaded yet
+        app.world
+            .get_resource_mut::<Assets<Prefab>>()
+            .unwrap()
+            .get_mut(&prefab)
+            .unwrap()
+            .base = "boss.scn.ron".into();
+        app.update();
+        app.update();
+        let health = |app: &mut App| {
+            let mut query = app.world.query::<&Health>();
+            query
+                .iter(&app.world)
+                .map(|health| health.0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(health(&mut app), vec![10.0]);
+
+        let boss = scene_with_health(20.0, &app);
+        app.world
+            .get_resource_mut::<Assets<DynamicScene>>()
+            .unwrap()
+            .set_untracked("boss.scn.ron", boss);
+        app.update();
+        assert_eq!(health(&mut app), vec![20.0]);
+    }
+}
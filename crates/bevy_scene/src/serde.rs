@@ -1,14 +1,17 @@
-use crate::{DynamicScene, Entity};
+use crate::{migration::migrate, DynamicScene, Entity, Prefab, PrefabEntity};
 use anyhow::Result;
+use bevy_asset::AssetPath;
 use bevy_reflect::{
-    serde::{ReflectDeserializer, ReflectSerializer},
-    Reflect, TypeRegistry, TypeRegistryArc,
+    serde::{ReflectSerializer, SkipUnregisteredDeserializer},
+    Reflect, ReflectVersion, TypeRegistry, TypeRegistryArc,
 };
+use bevy_utils::{tracing::warn, HashMap};
 use serde::{
     de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor},
     ser::{SerializeSeq, SerializeStruct},
     Deserialize, Serialize,
 };
+use std::{cell::RefCell, collections::BTreeMap};
 
 pub struct SceneSerializer<'a> {
     pub scene: &'a DynamicScene,
@@ -21,13 +24,68 @@ impl<'a> SceneSerializer<'a> {
     }
 }
 
+/// The versions of the types of `values` that have migrations
+fn versions<'a>(
+    values: impl Iterator<Item = &'a dyn Reflect>,
+    type_registry: &TypeRegistry,
+) -> BTreeMap<&'static str, u32> {
+    values
+        .filter_map(|value| {
+            let registration = type_registry.get_with_name(value.type_name())?;
+            let version = registration.data::<ReflectVersion>()?.version();
+            Some((registration.name(), version))
+        })
+        .collect()
+}
+
 impl<'a> Serialize for SceneSerializer<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_seq(Some(self.scene.entities.len()))?;
-        for entity in self.scene.entities.iter() {
+        let components = self
+            .scene
+            .entities
+            .iter()
+            .flat_map(|entity| entity.components.iter());
+        let values = self.scene.resources.iter().chain(components);
+        let versions = versions(values.map(|value| &**value), &self.registry.read());
+        // versions are only written for scenes with types that have migrations
+        let len = if versions.is_empty() { 2 } else { 3 };
+        let mut state = serializer.serialize_struct(SCENE_STRUCT, len)?;
+        if !versions.is_empty() {
+            state.serialize_field(SCENE_FIELD_VERSIONS, &versions)?;
+        }
+        state.serialize_field(
+            SCENE_FIELD_RESOURCES,
+            &ComponentsSerializer {
+                components: &self.scene.resources,
+                registry: self.registry,
+            },
+        )?;
+        state.serialize_field(
+            SCENE_FIELD_ENTITIES,
+            &EntitiesSerializer {
+                entities: &self.scene.entities,
+                registry: self.registry,
+            },
+        )?;
+        state.end()
+    }
+}
+
+pub struct EntitiesSerializer<'a> {
+    pub entities: &'a [Entity],
+    pub registry: &'a TypeRegistryArc,
+}
+
+impl<'a> Serialize for EntitiesSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.entities.len()))?;
+        for entity in self.entities.iter() {
             state.serialize_element(&EntitySerializer {
                 entity,
                 registry: self.registry,
@@ -92,10 +150,119 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
     where
         D: serde::Deserializer<'de>,
     {
+        deserializer.deserialize_any(SceneVisitor {
+            type_registry: self.type_registry,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum SceneField {
+    Versions,
+    Resources,
+    Entities,
+}
+
+pub const SCENE_STRUCT: &str = "Scene";
+pub const SCENE_FIELD_VERSIONS: &str = "versions";
+pub const SCENE_FIELD_RESOURCES: &str = "resources";
+pub const SCENE_FIELD_ENTITIES: &str = "entities";
+
+struct SceneVisitor<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
+    type Value = DynamicScene;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("scene struct or list of entities")
+    }
+
+    /// Scenes saved before resources were supported are a list of entities
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut entities = SceneEntitySeqVisitor {
+            type_registry: self.type_registry,
+        }
+        .visit_seq(seq)?;
+        for entity in entities.iter_mut() {
+            migrate(
+                &mut entity.components,
+                &HashMap::default(),
+                self.type_registry,
+            );
+        }
         Ok(DynamicScene {
-            entities: deserializer.deserialize_seq(SceneEntitySeqVisitor {
-                type_registry: self.type_registry,
-            })?,
+            resources: Vec::new(),
+            entities,
+        })
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut versions = None;
+        let mut resources = None;
+        let mut entities = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                SceneField::Versions => {
+                    if versions.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_VERSIONS));
+                    }
+                    versions = Some(map.next_value::<HashMap<String, u32>>()?);
+                }
+                SceneField::Resources => {
+                    if resources.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_RESOURCES));
+                    }
+                    resources = Some(map.next_value_seed(ComponentVecDeserializer {
+                        registry: self.type_registry,
+                    })?);
+                }
+                SceneField::Entities => {
+                    if entities.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_ENTITIES));
+                    }
+                    entities = Some(map.next_value_seed(SceneEntitiesDeserializer {
+                        type_registry: self.type_registry,
+                    })?);
+                }
+            }
+        }
+
+        // all the fields are optional, to keep hand-written scenes short
+        let mut scene = DynamicScene {
+            resources: resources.unwrap_or_default(),
+            entities: entities.unwrap_or_default(),
+        };
+        let versions = versions.unwrap_or_default();
+        migrate(&mut scene.resources, &versions, self.type_registry);
+        for entity in scene.entities.iter_mut() {
+            migrate(&mut entity.components, &versions, self.type_registry);
+        }
+        Ok(scene)
+    }
+}
+
+pub struct SceneEntitiesDeserializer<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for SceneEntitiesDeserializer<'a> {
+    type Value = Vec<Entity>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SceneEntitySeqVisitor {
+            type_registry: self.type_registry,
         })
     }
 }
@@ -237,15 +404,358 @@ impl<'a, 'de> Visitor<'de> for ComponentSeqVisitor<'a> {
         formatter.write_str("list of components")
     }
 
+    /// The components of unregistered types, and the fields with values of unregistered types,
+    /// are left out with a warning instead of failing the whole scene
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
     where
         A: SeqAccess<'de>,
     {
+        let unregistered = RefCell::new(Vec::new());
         let mut dynamic_properties = Vec::new();
-        while let Some(entity) = seq.next_element_seed(ReflectDeserializer::new(self.registry))? {
-            dynamic_properties.push(entity);
+        while let Some(component) = seq.next_element_seed(SkipUnregisteredDeserializer::new(
+            self.registry,
+            &unregistered,
+        ))? {
+            match component {
+                Some(component) if self.registry.get_with_name(component.type_name()).is_none() => {
+                    unregistered
+                        .borrow_mut()
+                        .push(component.type_name().to_string());
+                }
+                Some(component) => dynamic_properties.push(component),
+                None => {}
+            }
         }
 
+        for type_name in unregistered.into_inner() {
+            warn!("scene data ignored: unregistered type {}", type_name);
+        }
         Ok(dynamic_properties)
     }
 }
+
+pub struct PrefabSerializer<'a> {
+    pub prefab: &'a Prefab,
+    pub registry: &'a TypeRegistryArc,
+}
+
+impl<'a> PrefabSerializer<'a> {
+    pub fn new(prefab: &'a Prefab, registry: &'a TypeRegistryArc) -> Self {
+        PrefabSerializer { prefab, registry }
+    }
+}
+
+impl<'a> Serialize for PrefabSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let components = self
+            .prefab
+            .entities
+            .iter()
+            .flat_map(|entity| entity.components.iter());
+        let versions = versions(components.map(|value| &**value), &self.registry.read());
+        // versions are only written for prefabs with types that have migrations
+        let len = if versions.is_empty() { 2 } else { 3 };
+        let mut state = serializer.serialize_struct(PREFAB_STRUCT, len)?;
+        if !versions.is_empty() {
+            state.serialize_field(SCENE_FIELD_VERSIONS, &versions)?;
+        }
+        let base = &self.prefab.base;
+        let mut base_path = String::new();
+        if let Some(source) = base.source() {
+            base_path.push_str(source);
+            base_path.push_str("://");
+        }
+        base_path.push_str(&base.path().to_string_lossy());
+        if let Some(label) = base.label() {
+            base_path.push('#');
+            base_path.push_str(label);
+        }
+        state.serialize_field(PREFAB_FIELD_BASE, &base_path)?;
+        state.serialize_field(
+            PREFAB_FIELD_ENTITIES,
+            &PrefabEntitiesSerializer {
+                entities: &self.prefab.entities,
+                registry: self.registry,
+            },
+        )?;
+        state.end()
+    }
+}
+
+struct PrefabEntitiesSerializer<'a> {
+    pub entities: &'a [PrefabEntity],
+    pub registry: &'a TypeRegistryArc,
+}
+
+impl<'a> Serialize for PrefabEntitiesSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.entities.len()))?;
+        for entity in self.entities.iter() {
+            state.serialize_element(&PrefabEntitySerializer {
+                entity,
+                registry: self.registry,
+            })?;
+        }
+        state.end()
+    }
+}
+
+struct PrefabEntitySerializer<'a> {
+    pub entity: &'a PrefabEntity,
+    pub registry: &'a TypeRegistryArc,
+}
+
+impl<'a> Serialize for PrefabEntitySerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let entity = self.entity;
+        let len = if entity.parent.is_some() { 4 } else { 3 };
+        let mut state = serializer.serialize_struct(ENTITY_STRUCT, len)?;
+        state.serialize_field(ENTITY_FIELD_ENTITY, &entity.entity)?;
+        if let Some(parent) = entity.parent {
+            state.serialize_field(PREFAB_ENTITY_FIELD_PARENT, &parent)?;
+        }
+        state.serialize_field(
+            ENTITY_FIELD_COMPONENTS,
+            &ComponentsSerializer {
+                components: &entity.components,
+                registry: self.registry,
+            },
+        )?;
+        state.serialize_field(
+            PREFAB_ENTITY_FIELD_REMOVED_COMPONENTS,
+            &entity.removed_components,
+        )?;
+        state.end()
+    }
+}
+
+pub struct PrefabDeserializer<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for PrefabDeserializer<'a> {
+    type Value = Prefab;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            PREFAB_STRUCT,
+            &[
+                SCENE_FIELD_VERSIONS,
+                PREFAB_FIELD_BASE,
+                PREFAB_FIELD_ENTITIES,
+            ],
+            PrefabVisitor {
+                type_registry: self.type_registry,
+            },
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum PrefabField {
+    Versions,
+    Base,
+    Entities,
+}
+
+pub const PREFAB_STRUCT: &str = "Prefab";
+pub const PREFAB_FIELD_BASE: &str = "base";
+pub const PREFAB_FIELD_ENTITIES: &str = "entities";
+
+struct PrefabVisitor<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for PrefabVisitor<'a> {
+    type Value = Prefab;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("prefab struct")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut versions = None;
+        let mut base = None;
+        let mut entities = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                PrefabField::Versions => {
+                    if versions.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_VERSIONS));
+                    }
+                    versions = Some(map.next_value::<HashMap<String, u32>>()?);
+                }
+                PrefabField::Base => {
+                    if base.is_some() {
+                        return Err(Error::duplicate_field(PREFAB_FIELD_BASE));
+                    }
+                    base = Some(map.next_value::<String>()?);
+                }
+                PrefabField::Entities => {
+                    if entities.is_some() {
+                        return Err(Error::duplicate_field(PREFAB_FIELD_ENTITIES));
+                    }
+                    entities = Some(map.next_value_seed(PrefabEntitiesDeserializer {
+                        type_registry: self.type_registry,
+                    })?);
+                }
+            }
+        }
+
+        let base = base.ok_or_else(|| Error::missing_field(PREFAB_FIELD_BASE))?;
+        let mut entities = entities.unwrap_or_default();
+        // like scenes, prefabs without versions were saved before their types had migrations
+        let versions = versions.unwrap_or_default();
+        for entity in entities.iter_mut() {
+            migrate(&mut entity.components, &versions, self.type_registry);
+        }
+        Ok(Prefab {
+            base: AssetPath::from(base.as_str()).to_owned(),
+            entities,
+        })
+    }
+}
+
+struct PrefabEntitiesDeserializer<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for PrefabEntitiesDeserializer<'a> {
+    type Value = Vec<PrefabEntity>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(PrefabEntitySeqVisitor {
+            type_registry: self.type_registry,
+        })
+    }
+}
+
+struct PrefabEntitySeqVisitor<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for PrefabEntitySeqVisitor<'a> {
+    type Value = Vec<PrefabEntity>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("list of prefab entities")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut entities = Vec::new();
+        while let Some(entity) = seq.next_element_seed(PrefabEntityDeserializer {
+            type_registry: self.type_registry,
+        })? {
+            entities.push(entity);
+        }
+
+        Ok(entities)
+    }
+}
+
+struct PrefabEntityDeserializer<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for PrefabEntityDeserializer<'a> {
+    type Value = PrefabEntity;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            ENTITY_STRUCT,
+            &[
+                ENTITY_FIELD_ENTITY,
+                PREFAB_ENTITY_FIELD_PARENT,
+                ENTITY_FIELD_COMPONENTS,
+                PREFAB_ENTITY_FIELD_REMOVED_COMPONENTS,
+            ],
+            PrefabEntityVisitor {
+                type_registry: self.type_registry,
+            },
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum PrefabEntityField {
+    Entity,
+    Parent,
+    Components,
+    RemovedComponents,
+}
+
+pub const PREFAB_ENTITY_FIELD_PARENT: &str = "parent";
+pub const PREFAB_ENTITY_FIELD_REMOVED_COMPONENTS: &str = "removed_components";
+
+struct PrefabEntityVisitor<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for PrefabEntityVisitor<'a> {
+    type Value = PrefabEntity;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("prefab entity")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut id = None;
+        let mut prefab_entity = PrefabEntity::default();
+        while let Some(key) = map.next_key()? {
+            match key {
+                PrefabEntityField::Entity => {
+                    if id.is_some() {
+                        return Err(Error::duplicate_field(ENTITY_FIELD_ENTITY));
+                    }
+                    id = Some(map.next_value::<u32>()?);
+                }
+                PrefabEntityField::Parent => {
+                    if prefab_entity.parent.is_some() {
+                        return Err(Error::duplicate_field(PREFAB_ENTITY_FIELD_PARENT));
+                    }
+                    prefab_entity.parent = Some(map.next_value::<u32>()?);
+                }
+                PrefabEntityField::Components => {
+                    prefab_entity.components = map.next_value_seed(ComponentVecDeserializer {
+                        registry: self.type_registry,
+                    })?;
+                }
+                PrefabEntityField::RemovedComponents => {
+                    prefab_entity.removed_components = map.next_value::<Vec<String>>()?;
+                }
+            }
+        }
+
+        prefab_entity.entity = id.ok_or_else(|| Error::missing_field(ENTITY_FIELD_ENTITY))?;
+        Ok(prefab_entity)
+    }
+}
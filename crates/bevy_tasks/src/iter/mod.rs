@@ -253,6 +253,30 @@ where
         })
     }
 
+    /// Reduces the items of a parallel iterator to a single one by repeatedly
+    /// applying a reducing operation.
+    ///
+    /// *Note that each batch is reduced independently, then the results of
+    /// the batches are reduced (in batch order). `f` should therefore be
+    /// associative.*
+    ///
+    /// See [`Iterator::reduce()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.reduce)
+    fn reduce<F>(mut self, pool: &TaskPool, f: F) -> Option<Self::Item>
+    where
+        F: FnMut(Self::Item, Self::Item) -> Self::Item + Send + Sync + Clone,
+        Self::Item: Send + 'static,
+    {
+        pool.scope(|s| {
+            while let Some(batch) = self.next_batch() {
+                let newf = f.clone();
+                s.spawn(async move { batch.reduce(newf) });
+            }
+        })
+        .into_iter()
+        .flatten()
+        .reduce(f)
+    }
+
     /// Tests if every element of the parallel iterator matches a predicate.
     ///
     /// *Note that all is **not** short circuiting.*
@@ -510,3 +534,55 @@ where
         .product()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Chunks {
+        items: std::vec::IntoIter<i32>,
+        batch_size: usize,
+    }
+
+    impl ParallelIterator<std::vec::IntoIter<i32>> for Chunks {
+        type Item = i32;
+
+        fn next_batch(&mut self) -> Option<std::vec::IntoIter<i32>> {
+            let batch = self
+                .items
+                .by_ref()
+                .take(self.batch_size)
+                .collect::<Vec<_>>();
+            if batch.is_empty() {
+                None
+            } else {
+                Some(batch.into_iter())
+            }
+        }
+    }
+
+    fn chunks(len: i32, batch_size: usize) -> Chunks {
+        Chunks {
+            items: (0..len).collect::<Vec<_>>().into_iter(),
+            batch_size,
+        }
+    }
+
+    #[test]
+    fn reduce() {
+        let pool = TaskPool::new();
+        assert_eq!(chunks(100, 7).reduce(&pool, |a, b| a + b), Some(4950));
+        assert_eq!(chunks(0, 7).reduce(&pool, |a, b| a + b), None);
+    }
+
+    #[test]
+    fn map_filter_collect() {
+        let pool = TaskPool::new();
+        let collected: Vec<i32> = chunks(10, 3)
+            .map(|i| i * 2)
+            .filter(|i| i % 4 == 0)
+            .collect(&pool);
+        assert_eq!(collected, vec![0, 4, 8, 12, 16]);
+    }
+}
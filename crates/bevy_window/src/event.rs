@@ -32,6 +32,11 @@ pub struct WindowCreated {
     pub id: WindowId,
 }
 
+/// An event that asks for the app to be updated again, even if the window backend only
+/// updates it when receiving input (see `UpdateMode` in `bevy_winit`).
+#[derive(Debug, Clone)]
+pub struct RequestRedraw;
+
 /// An event that is sent whenever a close was requested for a window. For example: when the "close"
 /// button is pressed on a window.
 #[derive(Debug, Clone)]
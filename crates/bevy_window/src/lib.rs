@@ -52,6 +52,7 @@ impl Plugin for WindowPlugin {
             .add_event::<WindowBackendScaleFactorChanged>()
             .add_event::<FileDragAndDrop>()
             .add_event::<WindowMoved>()
+            .add_event::<RequestRedraw>()
             .init_resource::<Windows>();
 
         if self.add_primary_window {
@@ -13,11 +13,14 @@ pub use winit_windows::*;
 use bevy_app::{App, AppExit, CoreStage, Events, ManualEventReader, Plugin};
 use bevy_ecs::{system::IntoExclusiveSystem, world::World};
 use bevy_math::{ivec2, DVec2, Vec2};
-use bevy_utils::tracing::{error, trace, warn};
+use bevy_utils::{
+    tracing::{error, trace, warn},
+    Instant,
+};
 use bevy_window::{
     CreateWindow, CursorEntered, CursorLeft, CursorMoved, FileDragAndDrop, ReceivedCharacter,
-    WindowBackendScaleFactorChanged, WindowCloseRequested, WindowCreated, WindowFocused,
-    WindowMoved, WindowResized, WindowScaleFactorChanged, Windows,
+    RequestRedraw, WindowBackendScaleFactorChanged, WindowCloseRequested, WindowCreated,
+    WindowFocused, WindowMoved, WindowResized, WindowScaleFactorChanged, Windows,
 };
 use winit::{
     dpi::PhysicalPosition,
@@ -33,6 +36,7 @@ pub struct WinitPlugin;
 impl Plugin for WinitPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<WinitWindows>()
+            .init_resource::<WinitConfig>()
             .set_runner(winit_runner)
             .add_system_to_stage(CoreStage::PostUpdate, change_window.exclusive_system());
         let event_loop = EventLoop::new();
@@ -226,6 +230,7 @@ pub fn winit_runner_with(mut app: App) {
     let mut event_loop = app.world.remove_non_send::<EventLoop<()>>().unwrap();
     let mut create_window_event_reader = ManualEventReader::<CreateWindow>::default();
     let mut app_exit_event_reader = ManualEventReader::<AppExit>::default();
+    let mut redraw_event_reader = ManualEventReader::<RequestRedraw>::default();
     app.world.insert_non_send(event_loop.create_proxy());
 
     trace!("Entering winit event loop");
@@ -236,12 +241,14 @@ pub fn winit_runner_with(mut app: App) {
         .map_or(false, |config| config.return_from_run);
 
     let mut active = true;
+    let mut last_update = Instant::now();
+    let mut window_event_received = false;
+    let mut device_event_received = false;
+    let mut redraw_requested = false;
 
     let event_handler = move |event: Event<()>,
                               event_loop: &EventLoopWindowTarget<()>,
                               control_flow: &mut ControlFlow| {
-        *control_flow = ControlFlow::Poll;
-
         if let Some(app_exit_events) = app.world.get_resource_mut::<Events<AppExit>>() {
             if app_exit_event_reader
                 .iter(&app_exit_events)
@@ -258,6 +265,7 @@ pub fn winit_runner_with(mut app: App) {
                 window_id: winit_window_id,
                 ..
             } => {
+                window_event_received = true;
                 let world = app.world.cell();
                 let winit_windows = world.get_resource_mut::<WinitWindows>().unwrap();
                 let mut windows = world.get_resource_mut::<Windows>().unwrap();
@@ -482,6 +490,7 @@ pub fn winit_runner_with(mut app: App) {
                 event: DeviceEvent::MouseMotion { delta },
                 ..
             } => {
+                device_event_received = true;
                 let mut mouse_motion_events =
                     app.world.get_resource_mut::<Events<MouseMotion>>().unwrap();
                 mouse_motion_events.send(MouseMotion {
@@ -494,14 +503,111 @@ pub fn winit_runner_with(mut app: App) {
             event::Event::Resumed => {
                 active = true;
             }
+            event::Event::RedrawRequested(_) => {
+                redraw_requested = true;
+            }
             event::Event::MainEventsCleared => {
                 handle_create_window_events(
                     &mut app.world,
                     event_loop,
                     &mut create_window_event_reader,
                 );
-                if active {
-                    app.update();
+                if *control_flow == ControlFlow::Exit {
+                    return;
+                }
+                if !active {
+                    *control_flow = ControlFlow::Wait;
+                    return;
+                }
+
+                let focused = app
+                    .world
+                    .get_resource::<Windows>()
+                    .map_or(false, |windows| {
+                        windows.iter().any(|window| window.is_focused())
+                    });
+                let config = app.world.get_resource::<WinitConfig>().unwrap();
+                let (update_mode, spin_duration) =
+                    (config.update_mode(focused), config.spin_duration);
+
+                let now = Instant::now();
+                let should_update = match update_mode {
+                    UpdateMode::Continuous { max_fps } => {
+                        match max_fps.and_then(bevy_app::frame_duration) {
+                            Some(frame_duration) => {
+                                let next_frame = last_update + frame_duration;
+                                if now + spin_duration < next_frame {
+                                    // too early: sleep until shortly before the next frame
+                                    *control_flow =
+                                        ControlFlow::WaitUntil(next_frame - spin_duration);
+                                    false
+                                } else {
+                                    #[cfg(not(target_arch = "wasm32"))]
+                                    bevy_app::wait_until(next_frame, spin_duration);
+                                    true
+                                }
+                            }
+                            None => true,
+                        }
+                    }
+                    UpdateMode::Reactive { max_wait }
+                    | UpdateMode::ReactiveLowPower { max_wait } => {
+                        let reacts_to_device_events =
+                            matches!(update_mode, UpdateMode::Reactive { .. });
+                        let next_update = last_update + max_wait;
+                        if window_event_received
+                            || (reacts_to_device_events && device_event_received)
+                            || redraw_requested
+                            || now >= next_update
+                        {
+                            true
+                        } else {
+                            *control_flow = ControlFlow::WaitUntil(next_update);
+                            false
+                        }
+                    }
+                };
+                if !should_update {
+                    return;
+                }
+
+                last_update = Instant::now();
+                window_event_received = false;
+                device_event_received = false;
+                redraw_requested = false;
+                app.update();
+
+                if let Some(redraw_events) = app.world.get_resource::<Events<RequestRedraw>>() {
+                    if redraw_event_reader.iter(redraw_events).last().is_some() {
+                        redraw_requested = true;
+                    }
+                }
+                *control_flow = match update_mode {
+                    UpdateMode::Continuous { max_fps } => {
+                        match max_fps.and_then(bevy_app::frame_duration) {
+                            Some(frame_duration) if frame_duration > spin_duration => {
+                                ControlFlow::WaitUntil(last_update + frame_duration - spin_duration)
+                            }
+                            _ => ControlFlow::Poll,
+                        }
+                    }
+                    UpdateMode::Reactive { max_wait }
+                    | UpdateMode::ReactiveLowPower { max_wait } => {
+                        if redraw_requested {
+                            ControlFlow::Poll
+                        } else {
+                            ControlFlow::WaitUntil(last_update + max_wait)
+                        }
+                    }
+                };
+                if let Some(app_exit_events) = app.world.get_resource::<Events<AppExit>>() {
+                    if app_exit_event_reader
+                        .iter(app_exit_events)
+                        .next_back()
+                        .is_some()
+                    {
+                        *control_flow = ControlFlow::Exit;
+                    }
                 }
             }
             _ => (),
@@ -1,5 +1,8 @@
+use bevy_app::DEFAULT_SPIN_DURATION;
+use bevy_utils::Duration;
+
 /// A resource for configuring usage of the `rust_winit` library.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct WinitConfig {
     /// Configures the winit library to return control to the main thread after
     /// the [run](bevy_app::App::run) loop is exited. Winit strongly recommends
@@ -12,4 +15,75 @@ pub struct WinitConfig {
     /// `openbsd`. If set to true on an unsupported platform
     /// [run](bevy_app::App::run) will panic.
     pub return_from_run: bool,
+    /// How the app is updated while one of its windows has focus.
+    pub focused_mode: UpdateMode,
+    /// How the app is updated while none of its windows has focus.
+    pub unfocused_mode: UpdateMode,
+    /// When the frame rate is capped, how long before the start of the next frame the event loop
+    /// stops sleeping and starts spinning, see [wait_until](bevy_app::wait_until).
+    pub spin_duration: Duration,
+}
+
+impl Default for WinitConfig {
+    fn default() -> Self {
+        WinitConfig {
+            return_from_run: false,
+            focused_mode: UpdateMode::Continuous { max_fps: None },
+            unfocused_mode: UpdateMode::Continuous { max_fps: None },
+            spin_duration: DEFAULT_SPIN_DURATION,
+        }
+    }
+}
+
+impl WinitConfig {
+    /// Settings suited for games: the app is updated continuously, at most `max_fps`
+    /// times per second while unfocused.
+    pub fn game(unfocused_max_fps: f64) -> Self {
+        WinitConfig {
+            unfocused_mode: UpdateMode::Continuous {
+                max_fps: Some(unfocused_max_fps),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Settings suited for desktop applications and tools: the app is only updated when
+    /// receiving input or a [`RequestRedraw`](bevy_window::RequestRedraw) event, and at least
+    /// every few seconds. Mouse motion outside of the windows doesn't update the app while
+    /// it is unfocused.
+    pub fn desktop_app() -> Self {
+        WinitConfig {
+            focused_mode: UpdateMode::Reactive {
+                max_wait: Duration::from_secs(5),
+            },
+            unfocused_mode: UpdateMode::ReactiveLowPower {
+                max_wait: Duration::from_secs(60),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Returns the update mode to use depending on whether a window has focus.
+    pub fn update_mode(&self, focused: bool) -> UpdateMode {
+        if focused {
+            self.focused_mode
+        } else {
+            self.unfocused_mode
+        }
+    }
+}
+
+/// Determines when the winit event loop updates the app.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpdateMode {
+    /// The app is updated continuously, as fast as possible or at most `max_fps` times per second.
+    Continuous { max_fps: Option<f64> },
+    /// The app is only updated when:
+    /// - a window or device event is received, such as input or a window resize,
+    /// - a [`RequestRedraw`](bevy_window::RequestRedraw) event was sent during the last update,
+    /// - or `max_wait` elapsed since the last update.
+    Reactive { max_wait: Duration },
+    /// Like [`UpdateMode::Reactive`], but device events, such as mouse motion outside of the
+    /// windows, don't update the app.
+    ReactiveLowPower { max_wait: Duration },
 }
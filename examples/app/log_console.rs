@@ -0,0 +1,59 @@
+use bevy::{
+    app::ScheduleRunnerSettings,
+    log::{CapturedLogs, Level, LogEvent, LogFileSettings, LogFilter, LogPlugin, LogSettings},
+    prelude::*,
+    utils::Duration,
+};
+
+/// This example shows how to capture logs in the app, for example to display them in an in-game
+/// console, how to change the log filter at runtime, and how to write logs to a rotating file.
+fn main() {
+    App::new()
+        .insert_resource(LogSettings {
+            // keep the 100 most recent records in the `CapturedLogs` resource
+            capture_capacity: Some(100),
+            // also write logs to `logs/log_console.log`, rotated every 10 MiB
+            file: Some(LogFileSettings::new("logs/log_console.log")),
+            ..Default::default()
+        })
+        .insert_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f64(
+            0.5,
+        )))
+        .add_plugins(MinimalPlugins)
+        .add_plugin(LogPlugin)
+        .add_system(log_system)
+        .add_system(console_system)
+        .add_system(toggle_debug_system)
+        .run();
+}
+
+fn log_system(mut frame: Local<u32>) {
+    *frame += 1;
+    let _span = info_span!("log_system").entered();
+    info!(frame = *frame, "a new frame started");
+    debug!("only visible when debug logs of this example are enabled");
+    if *frame % 5 == 0 {
+        warn!("frame {} is a multiple of 5", *frame);
+    }
+}
+
+/// Reads the logs sent since the last frame, as an in-game console would
+fn console_system(mut events: EventReader<LogEvent>, captured: Res<CapturedLogs>) {
+    for event in events.iter().filter(|event| event.level <= Level::WARN) {
+        println!("[console] {} ({} records captured)", event, captured.len());
+    }
+}
+
+/// Enables and disables debug logs for this example every 4 frames
+fn toggle_debug_system(mut frame: Local<u32>, mut filter: ResMut<LogFilter>) {
+    *frame += 1;
+    if *frame % 4 == 0 {
+        let level = if *frame % 8 == 0 {
+            Level::INFO
+        } else {
+            Level::DEBUG
+        };
+        filter.set_target_level(module_path!(), level).unwrap();
+        info!("log filter is now {}", filter.directives());
+    }
+}
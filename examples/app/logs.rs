@@ -7,6 +7,7 @@ fn main() {
         // .insert_resource(bevy::log::LogSettings {
         //     level: bevy::log::Level::TRACE,
         //     filter: "wgpu=warn,bevy_ecs=info".to_string(),
+        //     ..Default::default()
         // })
         .add_plugins(DefaultPlugins)
         .add_system(log_system)
@@ -28,4 +29,6 @@ fn log_system() {
     // ex: RUST_LOG=trace, RUST_LOG=info,bevy_ecs=warn
     // the format used here is super flexible. check out this documentation for more info:
     // https://docs.rs/tracing-subscriber/*/tracing_subscriber/filter/struct.EnvFilter.html
+    // the filter can also be changed at runtime using the LogFilter resource, see the
+    // log_console example
 }
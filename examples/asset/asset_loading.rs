@@ -1,4 +1,7 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    render::texture::{ImageFilter, ImageLoaderSettings},
+};
 
 /// This example illustrates various ways to load assets
 fn main() {
@@ -43,11 +46,24 @@ fn setup(
     // Then any asset in the folder can be accessed like this:
     let monkey_handle = asset_server.get_handle("models/monkey/Monkey.gltf#Mesh0/Primitive0");
 
+    // Loaders can have settings changing how assets are loaded. Here the texture is sampled
+    // without smoothing. Loading the same path with other settings gives a different asset.
+    let pixelated_texture: Handle<Image> = asset_server.load_with_settings(
+        "branding/icon.png",
+        |settings: &mut ImageLoaderSettings| {
+            settings.filter = Some(ImageFilter::Nearest);
+        },
+    );
+
     // You can also add assets directly to their Assets<T> storage:
     let material_handle = materials.add(StandardMaterial {
         base_color: Color::rgb(0.8, 0.7, 0.6),
         ..Default::default()
     });
+    let textured_material_handle = materials.add(StandardMaterial {
+        base_color_texture: Some(pixelated_texture),
+        ..Default::default()
+    });
 
     // monkey
     commands.spawn_bundle(PbrBundle {
@@ -59,7 +75,7 @@ fn setup(
     // cube
     commands.spawn_bundle(PbrBundle {
         mesh: cube_handle,
-        material: material_handle.clone(),
+        material: textured_material_handle,
         transform: Transform::from_xyz(0.0, 0.0, 0.0),
         ..Default::default()
     });
@@ -0,0 +1,120 @@
+use bevy::{
+    asset::{
+        AssetLoader, AssetProcessingMode, AssetServerSettings, LoadContext, LoadedAsset, Processor,
+    },
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// This example processes assets on startup: `assets/data/scaled.custom` is read along with its
+/// settings in `assets/data/scaled.custom.meta`, processed by the `ScaleProcessor` and written to
+/// the `imported_assets` folder, which the `AssetServer` then loads from.
+/// Running the example again skips the processing, as the asset didn't change.
+fn main() {
+    App::new()
+        .insert_resource(AssetServerSettings {
+            processing_mode: AssetProcessingMode::ProcessOnStartup,
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .init_resource::<State>()
+        .add_asset::<CustomAsset>()
+        .init_asset_loader::<CustomAssetLoader>()
+        .add_asset_processor(ScaleProcessor)
+        .add_startup_system(setup)
+        .add_system(print_on_load)
+        .run();
+}
+
+#[derive(Debug, Serialize, Deserialize, TypeUuid)]
+#[uuid = "bc4cc8b2-6a5b-4c8f-a1d2-6ef4b2f1e0a7"]
+pub struct CustomAsset {
+    pub value: i32,
+}
+
+#[derive(Default)]
+pub struct CustomAssetLoader;
+
+impl AssetLoader for CustomAssetLoader {
+    type Settings = ();
+
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let custom_asset = ron::de::from_bytes::<CustomAsset>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(custom_asset));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["custom"]
+    }
+}
+
+/// Settings of the `ScaleProcessor`, read from the `.meta` file of the asset
+#[derive(Deserialize)]
+pub struct ScaleSettings {
+    pub factor: i32,
+}
+
+impl Default for ScaleSettings {
+    fn default() -> Self {
+        ScaleSettings { factor: 1 }
+    }
+}
+
+/// Multiplies the value of a `CustomAsset`. This stands for expensive work, such as compressing
+/// textures, that should not be done at runtime.
+pub struct ScaleProcessor;
+
+impl Processor for ScaleProcessor {
+    type Settings = ScaleSettings;
+
+    fn process<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        settings: &'a Self::Settings,
+        _path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, anyhow::Error>> {
+        Box::pin(async move {
+            let mut custom_asset = ron::de::from_bytes::<CustomAsset>(bytes)?;
+            custom_asset.value *= settings.factor;
+            Ok(ron::ser::to_string(&custom_asset)?.into_bytes())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["custom"]
+    }
+
+    fn name(&self) -> &str {
+        "scale"
+    }
+}
+
+#[derive(Default)]
+struct State {
+    handle: Handle<CustomAsset>,
+    printed: bool,
+}
+
+fn setup(mut state: ResMut<State>, asset_server: Res<AssetServer>) {
+    state.handle = asset_server.load("data/scaled.custom");
+}
+
+fn print_on_load(mut state: ResMut<State>, custom_assets: Res<Assets<CustomAsset>>) {
+    let custom_asset = custom_assets.get(&state.handle);
+    if state.printed || custom_asset.is_none() {
+        return;
+    }
+
+    info!("Processed asset loaded: {:?}", custom_asset.unwrap());
+    state.printed = true;
+}
@@ -16,6 +16,8 @@ pub struct CustomAsset {
 pub struct CustomAssetLoader;
 
 impl AssetLoader for CustomAssetLoader {
+    type Settings = ();
+
     fn load<'a>(
         &'a self,
         bytes: &'a [u8],
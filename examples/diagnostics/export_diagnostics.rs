@@ -0,0 +1,47 @@
+use bevy::{
+    app::{AppExit, ScheduleRunnerSettings},
+    diagnostic::{
+        DiagnosticsExportFormat, DiagnosticsExportPlugin, DiagnosticsPlugin,
+        DiagnosticsSummarySettings, FrameTimeDiagnosticsPlugin, RegressionCheck,
+    },
+    prelude::*,
+    utils::Duration,
+};
+
+/// Runs a headless app for a fixed number of frames, streaming diagnostics to a JSON lines file.
+///
+/// When the app exits, a summary is written to `diagnostics_summary.ron`. Copy it to
+/// `diagnostics_baseline.ron` and subsequent runs will exit with a non-zero status code if the
+/// average frame time regressed by more than 20%.
+fn main() {
+    let baseline = std::path::PathBuf::from("diagnostics_baseline.ron");
+    App::new()
+        .insert_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .add_plugins(MinimalPlugins)
+        .add_plugin(DiagnosticsPlugin)
+        // Adds frame time diagnostics
+        .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        // Streams every measurement to a file, and writes a summary on exit
+        .add_plugin(DiagnosticsExportPlugin {
+            path: "diagnostics.jsonl".into(),
+            format: DiagnosticsExportFormat::JsonLines,
+            summary: Some(DiagnosticsSummarySettings {
+                path: "diagnostics_summary.ron".into(),
+                baseline: baseline.exists().then(|| baseline),
+                checks: vec![RegressionCheck::new(FrameTimeDiagnosticsPlugin::FRAME_TIME)
+                    .with_tolerance(0.2)],
+            }),
+            ..Default::default()
+        })
+        .add_system(exit_after_frames)
+        .run();
+}
+
+fn exit_after_frames(mut frames: Local<u32>, mut app_exit_events: EventWriter<AppExit>) {
+    *frames += 1;
+    if *frames == 300 {
+        app_exit_events.send(AppExit);
+    }
+}
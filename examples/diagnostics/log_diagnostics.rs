@@ -17,5 +17,11 @@ fn main() {
         // .add_plugin(bevy::diagnostic::EntityCountDiagnosticsPlugin::default())
         // Uncomment this to add an asset count diagnostics:
         // .add_plugin(bevy::asset::diagnostic::AssetCountDiagnosticsPlugin::<Texture>::default())
+        // Uncomment this to add diagnostics of the memory used by component storage:
+        // .add_plugin(bevy::diagnostic::ComponentMemoryDiagnosticsPlugin::default())
+        // Uncomment this to add diagnostics of the memory used by images, including pixel data:
+        // .add_plugin(bevy::asset::diagnostic::AssetMemoryDiagnosticsPlugin::<Image>::with_size(
+        //     |image| image.data.len(),
+        // ))
         .run();
 }
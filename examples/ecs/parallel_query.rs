@@ -1,4 +1,4 @@
-use bevy::{prelude::*, tasks::prelude::*};
+use bevy::{ecs::query::BatchingStrategy, prelude::*, tasks::prelude::*};
 use rand::random;
 
 #[derive(Component)]
@@ -23,16 +23,19 @@ fn spawn_system(mut commands: Commands, asset_server: Res<AssetServer>) {
 // Move sprites according to their velocity
 fn move_system(pool: Res<ComputeTaskPool>, mut sprites: Query<(&mut Transform, &Velocity)>) {
     // Compute the new location of each sprite in parallel on the
-    // ComputeTaskPool using batches of 32 sprites
+    // ComputeTaskPool. The batch size is picked automatically from the
+    // number of sprites and the number of threads in the pool.
     //
     // This example is only for demonstrative purposes.  Using a
     // ParallelIterator for an inexpensive operation like addition on only 128
     // elements will not typically be faster than just using a normal Iterator.
     // See the ParallelIterator documentation for more information on when
     // to use or not use ParallelIterator over a normal Iterator.
-    sprites.par_for_each_mut(&pool, 32, |(mut transform, velocity)| {
-        transform.translation += velocity.0.extend(0.0);
-    });
+    sprites
+        .par_iter_mut(&pool)
+        .for_each(|(mut transform, velocity)| {
+            transform.translation += velocity.0.extend(0.0);
+        });
 }
 
 // Bounce sprites outside the window
@@ -49,9 +52,11 @@ fn bounce_system(
     let bottom = height / -2.0;
     let top = height / 2.0;
     sprites
-        // Batch size of 32 is chosen to limit the overhead of
+        .par_iter_mut(&pool)
+        // A minimum batch size of 32 is chosen to limit the overhead of
         // ParallelIterator, since negating a vector is very inexpensive.
-        .par_for_each_mut(&pool, 32, |(transform, mut v)| {
+        .batching_strategy(BatchingStrategy::new().min_batch_size(32))
+        .for_each(|(transform, mut v)| {
             if !(left < transform.translation.x
                 && transform.translation.x < right
                 && bottom < transform.translation.y
@@ -97,7 +97,14 @@ fn save_scene_system(world: &mut World) {
     // Scenes can be serialized like this:
     info!("{}", scene.serialize_ron(type_registry).unwrap());
 
-    // TODO: save scene
+    // Or saved to the asset folder. The scene is serialized at the end of the frame, and the file
+    // is written in the background.
+    let scene_handle = world
+        .get_resource_mut::<Assets<DynamicScene>>()
+        .unwrap()
+        .add(scene);
+    let asset_server = world.get_resource::<AssetServer>().unwrap();
+    asset_server.save(&scene_handle, "scenes/saved_scene.scn.ron");
 }
 
 // This is only necessary for the info message in the UI. See examples/ui/text.rs for a standalone
@@ -9,6 +9,7 @@ fn main() {
     App::new()
         .insert_resource(AssetServerSettings {
             asset_folder: "/".to_string(),
+            ..Default::default()
         })
         .add_plugins(DefaultPlugins)
         .add_asset::<RustSourceCode>()
@@ -49,6 +50,8 @@ pub struct RustSourceCode(pub String);
 pub struct RustSourceCodeLoader;
 
 impl AssetLoader for RustSourceCodeLoader {
+    type Settings = ();
+
     fn load<'a>(
         &'a self,
         bytes: &'a [u8],
@@ -0,0 +1,61 @@
+use bevy::{
+    prelude::*,
+    window::RequestRedraw,
+    winit::{UpdateMode, WinitConfig},
+};
+
+/// This example illustrates how to run an app without using 100% of a CPU core when it is
+/// idle, by only updating it on input, and how to cap its frame rate.
+///
+/// Press space to cycle between the update modes, and R to request a redraw.
+fn main() {
+    App::new()
+        .insert_resource(WinitConfig::desktop_app())
+        .add_plugins(DefaultPlugins)
+        .add_system(cycle_update_mode)
+        .add_system(request_redraw)
+        .add_system(count_updates)
+        .run();
+}
+
+const MODES: &[UpdateMode] = &[
+    UpdateMode::Reactive {
+        max_wait: bevy::utils::Duration::from_secs(5),
+    },
+    UpdateMode::Continuous {
+        max_fps: Some(30.0),
+    },
+    UpdateMode::Continuous { max_fps: None },
+];
+
+/// This system switches the update mode used while the window is focused when space is pressed
+fn cycle_update_mode(
+    input: Res<Input<KeyCode>>,
+    mut config: ResMut<WinitConfig>,
+    mut index: Local<usize>,
+) {
+    if input.just_pressed(KeyCode::Space) {
+        *index = (*index + 1) % MODES.len();
+        config.focused_mode = MODES[*index];
+        info!("focused update mode: {:?}", config.focused_mode);
+    }
+}
+
+/// In reactive modes, sending a [`RequestRedraw`] event makes the app update again even without
+/// input, for example to finish an animation
+fn request_redraw(input: Res<Input<KeyCode>>, mut redraw: EventWriter<RequestRedraw>) {
+    if input.just_pressed(KeyCode::R) {
+        redraw.send(RequestRedraw);
+    }
+}
+
+/// This system shows how often the app is updated in the window title
+fn count_updates(time: Res<Time>, mut windows: ResMut<Windows>, mut updates: Local<u64>) {
+    *updates += 1;
+    let window = windows.get_primary_mut().unwrap();
+    window.set_title(format!(
+        "{} updates, last frame took {:.1} ms",
+        *updates,
+        time.delta_seconds_f64() * 1000.0
+    ));
+}
@@ -18,6 +18,7 @@ crates=(
     bevy_diagnostic
     bevy_transform
     bevy_window
+    bevy_animation
     bevy_crevice/bevy-crevice-derive
     bevy_crevice
     bevy_render